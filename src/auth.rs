@@ -27,3 +27,52 @@ impl AuthStrategy for StaticTokenAuth {
     }
 }
 // TODO: Implement other strategies
+
+/// Resolve a secret given on the command line, so it need not appear in shell
+/// history or process listings:
+///
+/// - `@env:NAME` reads environment variable NAME
+/// - `@file:PATH` reads the file at PATH, without its trailing newline
+/// - anything else is the secret itself
+pub fn resolve_secret(spec: &str) -> Result<String, String> {
+    if let Some(name) = spec.strip_prefix("@env:") {
+        return std::env::var(name)
+            .map_err(|_| format!("Environment variable {} is not set", name));
+    }
+    if let Some(path) = spec.strip_prefix("@file:") {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read secret file {}: {}", path, e))?;
+        let secret = content.trim_end_matches(['\r', '\n']);
+        if secret.is_empty() {
+            return Err(format!("Secret file {} is empty", path));
+        }
+        return Ok(secret.to_string());
+    }
+    Ok(spec.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_secret() {
+        assert_eq!(resolve_secret("plain-token").unwrap(), "plain-token");
+
+        std::env::set_var("DOPPEL_TEST_SECRET", "from-env");
+        assert_eq!(
+            resolve_secret("@env:DOPPEL_TEST_SECRET").unwrap(),
+            "from-env"
+        );
+        assert!(resolve_secret("@env:DOPPEL_TEST_SECRET_UNSET").is_err());
+
+        let path = std::env::temp_dir().join("doppel_test_secret.txt");
+        std::fs::write(&path, "from-file\n").unwrap();
+        let spec = format!("@file:{}", path.display());
+        assert_eq!(resolve_secret(&spec).unwrap(), "from-file");
+        std::fs::write(&path, "\n").unwrap();
+        assert!(resolve_secret(&spec).unwrap_err().contains("empty"));
+        let _ = std::fs::remove_file(&path);
+        assert!(resolve_secret("@file:/nonexistent/doppel-secret").is_err());
+    }
+}
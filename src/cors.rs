@@ -0,0 +1,103 @@
+// CORS misconfiguration detection for Doppel
+// Inspects the CORS headers of responses the scan already receives and flags
+// policies that let other sites read authenticated responses
+
+use crate::response_analysis::{Finding, ResponseAnalyzer, ResponseContext};
+
+/// Value of a response header, matched case-insensitively
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.trim())
+}
+
+/// Flag dangerous CORS policies in a response's headers.
+///
+/// `request_origin` is the Origin the request was sent with, if any; a
+/// response echoing it back means the server trusts any origin.
+pub fn analyze(headers: &[(String, String)], request_origin: Option<&str>) -> Option<Finding> {
+    let allow_origin = header(headers, "access-control-allow-origin")?;
+    let credentials = header(headers, "access-control-allow-credentials")
+        .is_some_and(|v| v.eq_ignore_ascii_case("true"));
+
+    let problem = if request_origin.is_some_and(|o| o == allow_origin) {
+        if credentials {
+            "reflects arbitrary Origin with credentials"
+        } else {
+            "reflects arbitrary Origin"
+        }
+    } else if allow_origin == "*" && credentials {
+        "wildcard origin with credentials"
+    } else if allow_origin.eq_ignore_ascii_case("null") && credentials {
+        "trusts the null origin with credentials"
+    } else {
+        return None;
+    };
+    Some(Finding::new(format!("CORS misconfiguration: {}", problem)))
+}
+
+/// Runs [`analyze`] over every response in the pipeline
+pub struct CorsAnalyzer;
+
+impl ResponseAnalyzer for CorsAnalyzer {
+    fn analyze(&self, ctx: &ResponseContext) -> Option<Finding> {
+        analyze(ctx.headers, ctx.request_origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(n, v)| (n.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_dangerous_policies() {
+        let wildcard = headers(&[
+            ("Access-Control-Allow-Origin", "*"),
+            ("Access-Control-Allow-Credentials", "true"),
+        ]);
+        assert_eq!(
+            analyze(&wildcard, None).unwrap().note,
+            "CORS misconfiguration: wildcard origin with credentials"
+        );
+
+        let reflected = headers(&[
+            ("access-control-allow-origin", "https://evil.example"),
+            ("access-control-allow-credentials", "true"),
+        ]);
+        assert_eq!(
+            analyze(&reflected, Some("https://evil.example"))
+                .unwrap()
+                .note,
+            "CORS misconfiguration: reflects arbitrary Origin with credentials"
+        );
+
+        let null_origin = headers(&[
+            ("Access-Control-Allow-Origin", "null"),
+            ("Access-Control-Allow-Credentials", "TRUE"),
+        ]);
+        assert!(analyze(&null_origin, None).is_some());
+    }
+
+    #[test]
+    fn test_safe_policies() {
+        // A public wildcard without credentials is a normal public API
+        let public = headers(&[("Access-Control-Allow-Origin", "*")]);
+        assert!(analyze(&public, None).is_none());
+
+        // A fixed allow-list entry that differs from the probe origin
+        let fixed = headers(&[
+            ("Access-Control-Allow-Origin", "https://app.example.com"),
+            ("Access-Control-Allow-Credentials", "true"),
+        ]);
+        assert!(analyze(&fixed, Some("https://evil.example")).is_none());
+        assert!(analyze(&[], Some("https://evil.example")).is_none());
+    }
+}
@@ -0,0 +1,347 @@
+// Resource id discovery for Doppel
+// Harvests ids of resources created during the scan (POST responses) so later
+// tests against the same resource type can target real ids instead of guesses,
+// and hypermedia links (`_links`, `href`) that point at endpoints the
+// collection doesn't list
+
+use crate::models::{Endpoint, Method, Parameter, ParameterLocation};
+use crate::mutator::{is_likely_random_id, is_object_id};
+use crate::parsers::add_url_parameters;
+use crate::response_analysis::parse_json_body;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use url::Url;
+
+/// Ids kept per resource type, so one busy create endpoint can't flood the scan
+const MAX_IDS_PER_RESOURCE: usize = 5;
+
+/// Identity fields checked in create responses, in priority order
+const ID_FIELDS: &[&str] = &["id", "_id", "uuid"];
+
+/// Envelope keys APIs commonly wrap a created object in
+const WRAPPER_FIELDS: &[&str] = &["data", "result", "item"];
+
+/// Keys whose string values are links (HAL `_links` entries use `href`)
+const LINK_FIELDS: &[&str] = &["href", "url"];
+
+/// Discovered ids keyed by resource type (e.g. "orders"), shared across endpoints
+#[derive(Debug, Default)]
+pub struct IdStore {
+    ids: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl IdStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember ids for a resource type, ignoring duplicates and anything past the cap
+    pub fn record(&self, resource: &str, ids: Vec<String>) {
+        let mut store = self.ids.lock().expect("id store poisoned");
+        let known = store.entry(resource.to_string()).or_default();
+        for id in ids {
+            if known.len() >= MAX_IDS_PER_RESOURCE {
+                break;
+            }
+            if !known.contains(&id) {
+                known.push(id);
+            }
+        }
+    }
+
+    /// Ids discovered so far for a resource type
+    pub fn candidates(&self, resource: &str) -> Vec<String> {
+        self.ids
+            .lock()
+            .expect("id store poisoned")
+            .get(resource)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Resource type of an endpoint: its last literal path segment, lowercased.
+/// `/api/orders/{id}`, `/api/orders` and `/users/{id}/orders` all give "orders".
+pub fn resource_type(path: &str) -> Option<String> {
+    // Absolute OpenAPI paths carry scheme and host
+    let path = path
+        .split_once("://")
+        .map_or(path, |(_, rest)| rest.find('/').map_or("", |i| &rest[i..]));
+    let path = path.split('?').next().unwrap_or_default();
+
+    path.split('/')
+        .rfind(|s| !s.is_empty() && !s.starts_with('{'))
+        .map(str::to_lowercase)
+}
+
+/// Ids of the resource a create response describes: identity fields of the
+/// top-level object or of a `data`/`result`/`item` wrapper
+pub fn extract_created_ids(body: &str) -> Vec<String> {
+    let Some(json) = parse_json_body(body) else {
+        return Vec::new();
+    };
+    let mut objects = vec![&json];
+    objects.extend(WRAPPER_FIELDS.iter().filter_map(|w| json.get(*w)));
+
+    let mut ids = Vec::new();
+    for object in objects {
+        for field in ID_FIELDS {
+            let id = match object.get(*field) {
+                Some(Value::String(s)) if !s.is_empty() => s.clone(),
+                Some(Value::Number(n)) => n.to_string(),
+                _ => continue,
+            };
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
+
+/// Same-origin URLs a response links to: `href`/`url` strings anywhere in the
+/// body, HAL `_links` included, resolved against the request URL
+pub fn extract_links(body: &str, base: &Url) -> Vec<Url> {
+    let Some(json) = parse_json_body(body) else {
+        return Vec::new();
+    };
+    let mut links = Vec::new();
+    collect_links(&json, base, &mut links);
+    links
+}
+
+fn collect_links(value: &Value, base: &Url, links: &mut Vec<Url>) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map {
+                match v {
+                    Value::String(s) if LINK_FIELDS.contains(&key.as_str()) => {
+                        let Ok(link) = base.join(s) else {
+                            continue;
+                        };
+                        if link.origin() == base.origin() && !links.contains(&link) {
+                            links.push(link);
+                        }
+                    }
+                    _ => collect_links(v, base, links),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for v in items {
+                collect_links(v, base, links);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Whether a path segment is an object id rather than a resource name
+fn is_id_segment(segment: &str) -> bool {
+    let uuid = segment.len() == 36
+        && segment.matches('-').count() == 4
+        && segment.chars().all(|c| c == '-' || c.is_ascii_hexdigit());
+    (!segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()))
+        || uuid
+        || is_object_id(segment)
+        || is_likely_random_id(segment)
+}
+
+/// A GET endpoint for a discovered link. Its last id-like path segment becomes
+/// an `{id}` parameter (the linked id is kept as its example) and query keys
+/// become parameters; links with nothing to inject into give `None`.
+pub fn link_endpoint(link: &Url) -> Option<Endpoint> {
+    let segments: Vec<&str> = link.path().split('/').collect();
+    let id_index = segments.iter().rposition(|s| is_id_segment(s));
+    let path = segments
+        .iter()
+        .enumerate()
+        .map(|(i, s)| if Some(i) == id_index { "{id}" } else { s })
+        .collect::<Vec<_>>()
+        .join("/");
+    let mut url = format!("{}{}", link.origin().ascii_serialization(), path);
+    if let Some(query) = link.query() {
+        url = format!("{}?{}", url, query);
+    }
+
+    let mut endpoint = Endpoint::new(Method::GET, url, None, vec![]);
+    if let Some(i) = id_index {
+        endpoint.add_parameter(Parameter::new("id", ParameterLocation::Path));
+        endpoint
+            .example_values
+            .insert("id".to_string(), Value::String(segments[i].to_string()));
+    }
+    add_url_parameters(&mut endpoint);
+    (!endpoint.params.is_empty()).then_some(endpoint)
+}
+
+/// Path shape used to tell endpoints apart: no origin, templates and ids collapsed
+fn link_key(method: &Method, path: &str) -> String {
+    let path = path
+        .split_once("://")
+        .map_or(path, |(_, rest)| rest.find('/').map_or("", |i| &rest[i..]));
+    let shape = path
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .split('/')
+        .map(|s| {
+            if s.starts_with('{') || is_id_segment(s) {
+                "{}".to_string()
+            } else {
+                s.to_lowercase()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    format!("{} {}", method, shape.trim_end_matches('/'))
+}
+
+/// Endpoints found by following links, waiting for the next crawl round.
+/// Endpoints already known (from the collection or an earlier round) are not
+/// queued again.
+#[derive(Debug, Default)]
+pub struct LinkStore {
+    seen: Mutex<HashSet<String>>,
+    pending: Mutex<Vec<Endpoint>>,
+}
+
+impl LinkStore {
+    pub fn new(known: &[Endpoint]) -> Self {
+        let seen = known.iter().map(|e| link_key(&e.method, &e.path)).collect();
+        Self {
+            seen: Mutex::new(seen),
+            pending: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Queue the new endpoints a response links to
+    pub fn record(&self, body: &str, request_url: &Url) {
+        for link in extract_links(body, request_url) {
+            let Some(endpoint) = link_endpoint(&link) else {
+                continue;
+            };
+            let key = link_key(&endpoint.method, &endpoint.path);
+            if self.seen.lock().expect("link store poisoned").insert(key) {
+                self.pending
+                    .lock()
+                    .expect("link store poisoned")
+                    .push(endpoint);
+            }
+        }
+    }
+
+    /// Endpoints queued since the last call
+    pub fn take(&self) -> Vec<Endpoint> {
+        std::mem::take(&mut *self.pending.lock().expect("link store poisoned"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_type() {
+        assert_eq!(resource_type("/api/orders"), Some("orders".to_string()));
+        assert_eq!(
+            resource_type("/api/orders/{orderId}"),
+            Some("orders".to_string())
+        );
+        assert_eq!(
+            resource_type("/users/{id}/Orders/{orderId}"),
+            Some("orders".to_string())
+        );
+        assert_eq!(
+            resource_type("https://api.example.com/v1/orders/{id}"),
+            Some("orders".to_string())
+        );
+        assert_eq!(resource_type("/{id}"), None);
+    }
+
+    #[test]
+    fn test_extract_created_ids() {
+        assert_eq!(extract_created_ids(r#"{"id":42,"name":"x"}"#), vec!["42"]);
+        assert_eq!(
+            extract_created_ids(r#"{"data":{"_id":"65a1f0c2e4b0a1b2c3d4e5f6"}}"#),
+            vec!["65a1f0c2e4b0a1b2c3d4e5f6"]
+        );
+        assert!(extract_created_ids(r#"{"status":"ok"}"#).is_empty());
+        assert!(extract_created_ids("created").is_empty());
+    }
+
+    #[test]
+    fn test_store_dedupes_and_caps() {
+        let store = IdStore::new();
+        store.record("orders", vec!["1".to_string(), "1".to_string()]);
+        store.record("orders", (2..20).map(|i| i.to_string()).collect());
+        let ids = store.candidates("orders");
+        assert_eq!(ids.len(), MAX_IDS_PER_RESOURCE);
+        assert_eq!(ids[0], "1");
+        assert!(store.candidates("users").is_empty());
+    }
+
+    #[test]
+    fn test_extract_links() {
+        let base = Url::parse("https://api.example.com/users/7").unwrap();
+        let body = r#"{
+            "id": 7,
+            "_links": {
+                "self": {"href": "/users/7"},
+                "orders": [{"href": "/users/7/orders/1001"}],
+                "avatar": {"href": "https://cdn.example.net/7.png"}
+            },
+            "invoice": {"url": "invoices/abc"}
+        }"#;
+        let links: Vec<String> = extract_links(body, &base)
+            .iter()
+            .map(|u| u.to_string())
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                "https://api.example.com/users/7/orders/1001",
+                "https://api.example.com/users/7",
+                "https://api.example.com/users/invoices/abc",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_link_endpoint_templates_last_id() {
+        let link = Url::parse("https://api.example.com/users/7/orders/1001").unwrap();
+        let endpoint = link_endpoint(&link).unwrap();
+        assert_eq!(endpoint.path, "https://api.example.com/users/7/orders/{id}");
+        assert_eq!(endpoint.example_values["id"], "1001");
+
+        let link = Url::parse("https://api.example.com/reports?ownerId=7").unwrap();
+        let endpoint = link_endpoint(&link).unwrap();
+        assert!(endpoint.parameter("ownerId").is_some());
+
+        let link = Url::parse("https://api.example.com/status").unwrap();
+        assert!(link_endpoint(&link).is_none());
+    }
+
+    #[test]
+    fn test_link_store_skips_known_shapes() {
+        let known = vec![Endpoint::new(
+            Method::GET,
+            "/users/{userId}".to_string(),
+            None,
+            vec![],
+        )];
+        let store = LinkStore::new(&known);
+        let base = Url::parse("https://api.example.com/users/7").unwrap();
+        store.record(
+            r#"{"_links": {"self": {"href": "/users/7"}, "next": {"href": "/users/8/orders/3"}}}"#,
+            &base,
+        );
+        // A second response linking to the same shapes adds nothing
+        store.record(r#"{"href": "/users/9/orders/4"}"#, &base);
+        let found = store.take();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, "https://api.example.com/users/8/orders/{id}");
+        assert!(store.take().is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+// Endpoint filtering for Doppel
+// Narrows a parsed collection down to the endpoints the user wants to scan
+
+use crate::models::{Endpoint, Method};
+use regex::Regex;
+
+/// Criteria for selecting endpoints. Empty criteria match everything.
+#[derive(Debug, Clone, Default)]
+pub struct EndpointFilter {
+    /// Only keep these methods (empty = all)
+    pub methods: Vec<Method>,
+    /// Only keep endpoints whose path matches this pattern
+    pub path_pattern: Option<Regex>,
+    /// Only keep endpoints carrying at least one of these tags (case-insensitive)
+    pub include_tags: Vec<String>,
+    /// Drop endpoints carrying any of these tags (case-insensitive)
+    pub exclude_tags: Vec<String>,
+    /// Drop operations marked deprecated
+    pub skip_deprecated: bool,
+    /// Only keep operations marked deprecated
+    pub only_deprecated: bool,
+}
+
+impl EndpointFilter {
+    /// Check a single endpoint against every criterion
+    pub fn matches(&self, endpoint: &Endpoint) -> bool {
+        if !self.methods.is_empty() && !self.methods.contains(&endpoint.method) {
+            return false;
+        }
+
+        if let Some(pattern) = &self.path_pattern {
+            if !pattern.is_match(&endpoint.path) {
+                return false;
+            }
+        }
+
+        let has_tag =
+            |wanted: &String| endpoint.tags.iter().any(|t| t.eq_ignore_ascii_case(wanted));
+        if !self.include_tags.is_empty() && !self.include_tags.iter().any(has_tag) {
+            return false;
+        }
+        if self.exclude_tags.iter().any(has_tag) {
+            return false;
+        }
+
+        if (self.skip_deprecated && endpoint.deprecated)
+            || (self.only_deprecated && !endpoint.deprecated)
+        {
+            return false;
+        }
+
+        true
+    }
+}
+
+/// Apply a filter to a list of endpoints, preserving order
+pub fn filter_endpoints(endpoints: Vec<Endpoint>, filter: &EndpointFilter) -> Vec<Endpoint> {
+    endpoints
+        .into_iter()
+        .filter(|e| filter.matches(e))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tagged(method: Method, path: &str, tags: &[&str]) -> Endpoint {
+        let mut endpoint = Endpoint::new(method, path.to_string(), None, vec![]);
+        endpoint.tags = tags.iter().map(|t| t.to_string()).collect();
+        endpoint
+    }
+
+    fn sample() -> Vec<Endpoint> {
+        vec![
+            tagged(Method::GET, "/admin/users", &["admin"]),
+            tagged(Method::GET, "/users/{id}", &["users", "public"]),
+            tagged(Method::DELETE, "/internal/cache", &["Internal", "admin"]),
+            tagged(Method::POST, "/health", &[]),
+        ]
+    }
+
+    #[test]
+    fn test_empty_filter_keeps_everything() {
+        assert_eq!(
+            filter_endpoints(sample(), &EndpointFilter::default()).len(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_include_and_exclude_tags() {
+        let filter = EndpointFilter {
+            include_tags: vec!["admin".to_string()],
+            exclude_tags: vec!["internal".to_string()],
+            ..Default::default()
+        };
+        let kept = filter_endpoints(sample(), &filter);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].path, "/admin/users");
+    }
+
+    #[test]
+    fn test_untagged_endpoints_excluded_by_tag_filter() {
+        let filter = EndpointFilter {
+            include_tags: vec!["users".to_string()],
+            ..Default::default()
+        };
+        let kept = filter_endpoints(sample(), &filter);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].path, "/users/{id}");
+    }
+
+    #[test]
+    fn test_deprecated_filters() {
+        let mut endpoints = sample();
+        endpoints[1].deprecated = true;
+
+        let skip = EndpointFilter {
+            skip_deprecated: true,
+            ..Default::default()
+        };
+        let kept = filter_endpoints(endpoints.clone(), &skip);
+        assert_eq!(kept.len(), 3);
+        assert!(kept.iter().all(|e| !e.deprecated));
+
+        let only = EndpointFilter {
+            only_deprecated: true,
+            ..Default::default()
+        };
+        let kept = filter_endpoints(endpoints, &only);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].path, "/users/{id}");
+    }
+
+    #[test]
+    fn test_method_and_path_filters_combine_with_tags() {
+        let filter = EndpointFilter {
+            methods: vec![Method::GET],
+            path_pattern: Some(Regex::new("^/users").unwrap()),
+            exclude_tags: vec!["admin".to_string()],
+            ..Default::default()
+        };
+        let kept = filter_endpoints(sample(), &filter);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].path, "/users/{id}");
+    }
+}
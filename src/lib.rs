@@ -1,22 +1,40 @@
 pub mod auth;
+pub mod cors;
+pub mod discovery;
 pub mod engine;
+pub mod filter;
+pub mod metrics;
 pub mod models;
 pub mod mutator;
+pub mod notify;
 pub mod ollama;
 pub mod parameters; // New hierarchical module
 pub mod parsers;
+pub mod recording;
+pub mod redact;
 pub mod reporting;
+pub mod request_plan;
 pub mod response_analysis;
+pub mod scan;
 pub mod verdict;
 
 // Re-export commonly used items
 pub use auth::*;
+pub use cors::*;
+pub use discovery::*;
 pub use engine::*;
+pub use filter::*;
+pub use metrics::*;
 pub use models::*;
 pub use mutator::*;
+pub use notify::*;
 pub use ollama::*;
 pub use parameters::*; // Re-exports all parameter functionality
 pub use parsers::*;
+pub use recording::*;
+pub use redact::*;
 pub use reporting::*;
+pub use request_plan::*;
 pub use response_analysis::*;
+pub use scan::*;
 pub use verdict::*;
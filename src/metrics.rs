@@ -0,0 +1,140 @@
+// Prometheus metrics for Doppel
+//
+// Renders the scan summary in the Prometheus text exposition format so
+// recurring scans can feed dashboards and alerting: either written to a file
+// (for node_exporter's textfile collector) or pushed to a Pushgateway.
+
+use crate::reporting::ScanSummary;
+use reqwest::Client;
+
+/// Pushgateway job name the metrics are grouped under
+const PUSHGATEWAY_JOB: &str = "doppel";
+
+/// Render a scan summary as Prometheus text exposition format
+pub fn render_prometheus(summary: &ScanSummary) -> String {
+    let mut out = String::new();
+
+    out.push_str("# HELP doppel_findings Results of the last scan by verdict\n");
+    out.push_str("# TYPE doppel_findings gauge\n");
+    for (verdict, count) in [
+        ("vulnerable", summary.vulnerable),
+        ("uncertain", summary.uncertain),
+        ("secure", summary.secure),
+        ("error", summary.errors),
+    ] {
+        out.push_str(&format!(
+            "doppel_findings{{verdict=\"{}\"}} {}\n",
+            verdict, count
+        ));
+    }
+
+    let gauges = [
+        (
+            "doppel_findings_with_pii",
+            "Results of the last scan whose response contained PII",
+            summary.with_pii as f64,
+        ),
+        (
+            "doppel_endpoints_scanned",
+            "Endpoints attacked in the last scan",
+            summary.endpoints_scanned as f64,
+        ),
+        (
+            "doppel_requests_sent",
+            "Attack requests sent in the last scan",
+            summary.requests_sent as f64,
+        ),
+        (
+            "doppel_scan_duration_seconds",
+            "Wall-clock duration of the last scan",
+            summary.duration_secs.unwrap_or_default(),
+        ),
+    ];
+    for (name, help, value) in gauges {
+        out.push_str(&format!("# HELP {} {}\n", name, help));
+        out.push_str(&format!("# TYPE {} gauge\n", name));
+        out.push_str(&format!("{} {}\n", name, value));
+    }
+
+    out
+}
+
+/// Write metrics to a file, e.g. for node_exporter's textfile collector
+pub fn write_prometheus(path: &str, summary: &ScanSummary) -> Result<(), String> {
+    std::fs::write(path, render_prometheus(summary))
+        .map_err(|e| format!("Failed to write metrics to {}: {}", path, e))
+}
+
+/// Push metrics to a Prometheus Pushgateway, replacing the previous scan's values.
+///
+/// Callers should treat errors as warnings: a failed push must not fail the scan.
+pub async fn push_prometheus(
+    client: &Client,
+    gateway_url: &str,
+    summary: &ScanSummary,
+) -> Result<(), String> {
+    let url = format!(
+        "{}/metrics/job/{}",
+        gateway_url.trim_end_matches('/'),
+        PUSHGATEWAY_JOB
+    );
+    let resp = client
+        .put(&url)
+        .header("Content-Type", "text/plain; version=0.0.4")
+        .body(render_prometheus(summary))
+        .send()
+        .await
+        .map_err(|e| format!("Pushgateway request failed: {}", e))?;
+
+    if !resp.status().is_success() {
+        return Err(format!(
+            "Pushgateway returned HTTP {}",
+            resp.status().as_u16()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus() {
+        let summary = ScanSummary {
+            endpoints_scanned: 4,
+            requests_sent: 12,
+            vulnerable: 2,
+            secure: 8,
+            uncertain: 1,
+            errors: 1,
+            with_pii: 1,
+            unauthenticated: 0,
+            duration_secs: Some(3.5),
+            coverage: None,
+            budget_truncated: false,
+            unreachable: Vec::new(),
+            accessible_ids: Vec::new(),
+            not_candidates: Vec::new(),
+        };
+        let text = render_prometheus(&summary);
+        assert!(text.contains("doppel_findings{verdict=\"vulnerable\"} 2\n"));
+        assert!(text.contains("doppel_findings{verdict=\"error\"} 1\n"));
+        assert!(text.contains("doppel_requests_sent 12\n"));
+        assert!(text.contains("doppel_scan_duration_seconds 3.5\n"));
+        assert!(text.contains("# TYPE doppel_endpoints_scanned gauge\n"));
+    }
+
+    #[test]
+    fn test_every_sample_has_type() {
+        let text = render_prometheus(&ScanSummary::default());
+        for line in text.lines().filter(|l| !l.starts_with('#')) {
+            let name = line.split(['{', ' ']).next().unwrap();
+            assert!(
+                text.contains(&format!("# TYPE {} gauge", name)),
+                "{} has no TYPE line",
+                name
+            );
+        }
+    }
+}
@@ -1,5 +1,8 @@
 // Core data models and traits for Doppel
 
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 
 /// Supported HTTP methods
@@ -12,6 +15,49 @@ pub enum Method {
     PATCH,
     OPTIONS,
     HEAD,
+    TRACE,
+    CONNECT,
+    /// Any other verb, e.g. WebDAV's `PROPFIND` or `REPORT`
+    Other(String),
+}
+
+impl Method {
+    /// Parse a method name as written in a collection. Names are
+    /// case-sensitive; anything that isn't a valid HTTP token is rejected.
+    pub fn parse(name: &str) -> Option<Method> {
+        let method = match name {
+            "GET" => Method::GET,
+            "POST" => Method::POST,
+            "PUT" => Method::PUT,
+            "DELETE" => Method::DELETE,
+            "PATCH" => Method::PATCH,
+            "OPTIONS" => Method::OPTIONS,
+            "HEAD" => Method::HEAD,
+            "TRACE" => Method::TRACE,
+            "CONNECT" => Method::CONNECT,
+            other => {
+                // RFC 9110 token characters
+                let is_token = !other.is_empty()
+                    && other
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c));
+                if !is_token {
+                    return None;
+                }
+                Method::Other(other.to_string())
+            }
+        };
+        Some(method)
+    }
+
+    /// Read-only by definition (RFC 9110 safe methods): sending one never
+    /// changes server state. Unknown verbs are assumed unsafe.
+    pub fn is_safe(&self) -> bool {
+        matches!(
+            self,
+            Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+        )
+    }
 }
 
 impl fmt::Display for Method {
@@ -24,6 +70,9 @@ impl fmt::Display for Method {
             Method::PATCH => write!(f, "PATCH"),
             Method::OPTIONS => write!(f, "OPTIONS"),
             Method::HEAD => write!(f, "HEAD"),
+            Method::TRACE => write!(f, "TRACE"),
+            Method::CONNECT => write!(f, "CONNECT"),
+            Method::Other(name) => f.write_str(name),
         }
     }
 }
@@ -35,6 +84,55 @@ pub enum ParameterLocation {
     Query,
     Body,
     Header,
+    Cookie,
+}
+
+/// How an array-valued query parameter is serialized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayStyle {
+    /// One key per item: `?tag=a&tag=b` (`explode: true`)
+    Repeated,
+    /// Items joined into one value: `?tag=a,b` (`explode: false`)
+    Delimited(char),
+}
+
+/// Kind of credential an OpenAPI security scheme expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecurityKind {
+    /// `type: http, scheme: bearer`
+    Bearer,
+    /// `type: http, scheme: basic`
+    Basic,
+    /// `type: apiKey`, in a header, query parameter or cookie
+    ApiKey,
+    OAuth2,
+    OpenIdConnect,
+    /// Any other scheme (mutualTLS, unknown http schemes, unresolved names)
+    Other,
+}
+
+impl SecurityKind {
+    /// Whether a bearer token in the Authorization header satisfies the scheme
+    pub fn accepts_bearer(self) -> bool {
+        matches!(
+            self,
+            SecurityKind::Bearer | SecurityKind::OAuth2 | SecurityKind::OpenIdConnect
+        )
+    }
+}
+
+impl fmt::Display for SecurityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            SecurityKind::Bearer => "bearer token",
+            SecurityKind::Basic => "HTTP basic",
+            SecurityKind::ApiKey => "API key",
+            SecurityKind::OAuth2 => "OAuth2",
+            SecurityKind::OpenIdConnect => "OpenID Connect",
+            SecurityKind::Other => "other",
+        };
+        f.write_str(label)
+    }
 }
 
 /// Represents a parameter for an endpoint
@@ -44,6 +142,32 @@ pub struct Parameter {
     pub location: ParameterLocation,
     pub required: bool,
     pub schema_type: Option<String>, // e.g., "string", "integer", "object"
+    /// Serialization of array-typed query parameters
+    pub array_style: Option<ArrayStyle>,
+    /// Media type of a parameter declared with `content` instead of
+    /// `schema` (e.g. "application/json"); its value is sent encoded
+    pub content_type: Option<String>,
+}
+
+impl Parameter {
+    /// A required parameter of unknown type
+    pub fn new(name: impl Into<String>, location: ParameterLocation) -> Self {
+        Self {
+            name: name.into(),
+            location,
+            required: true,
+            schema_type: None,
+            array_style: None,
+            content_type: None,
+        }
+    }
+
+    /// The value goes into its slot as JSON text
+    pub fn is_json_encoded(&self) -> bool {
+        self.content_type
+            .as_deref()
+            .is_some_and(|t| t.contains("json"))
+    }
 }
 
 /// Represents an API endpoint
@@ -52,9 +176,24 @@ pub struct Endpoint {
     pub method: Method,
     pub path: String,
     pub description: Option<String>,
-    pub params: Vec<String>, // Used for simple parameter list
-    #[allow(dead_code)]
-    pub parameters: Vec<Parameter>, // New: structured parameters (future use)
+    pub params: Vec<String>,        // Flat name list, kept for compatibility
+    pub parameters: Vec<Parameter>, // Structured parameters; authoritative for location/required/type
+    /// Plausible values from the spec (example/default/enum), keyed by param name
+    pub example_values: HashMap<String, Value>,
+    /// Example JSON request body from the source collection, used as the
+    /// template for attack bodies instead of a synthesized one
+    pub example_body: Option<Value>,
+    /// Grouping tags from the source collection (OpenAPI `tags`)
+    pub tags: Vec<String>,
+    /// Operation is marked `deprecated: true` in the spec
+    pub deprecated: bool,
+    /// Credential kinds the operation accepts (OpenAPI `security`, any one
+    /// suffices). None when the source doesn't say; empty for public operations.
+    pub security: Option<Vec<SecurityKind>>,
+    /// Query keys in the order the collection's URL lists them, one entry per
+    /// pair, so repeated keys keep their place (`?id=1&sort=asc&id=2` is
+    /// `[id, sort, id]`). Empty when the source has no literal query string.
+    pub query_order: Vec<String>,
 }
 
 impl Endpoint {
@@ -71,6 +210,321 @@ impl Endpoint {
             description,
             params,
             parameters: Vec::new(), // Default to empty for now
+            example_values: HashMap::new(),
+            example_body: None,
+            tags: Vec::new(),
+            deprecated: false,
+            security: None,
+            query_order: Vec::new(),
+        }
+    }
+
+    /// Structured metadata for a parameter, if the parser provided any
+    pub fn parameter(&self, name: &str) -> Option<&Parameter> {
+        self.parameters.iter().find(|p| p.name == name)
+    }
+
+    /// Whether a user config key names this endpoint: `"METHOD path"` or just
+    /// `"path"` for every method. A path also matches endpoints whose path ends
+    /// with it, so spec server prefixes can be left out.
+    pub fn matches_key(&self, key: &str) -> bool {
+        let path_matches = |path: &str| self.path == path || self.path.ends_with(path);
+        match key.split_once(' ') {
+            Some((m, path)) => {
+                m.eq_ignore_ascii_case(&self.method.to_string()) && path_matches(path.trim())
+            }
+            None => path_matches(key),
+        }
+    }
+
+    /// The entry of a user config map keyed as in [`Endpoint::matches_key`]
+    /// that names this endpoint most specifically: an exact `"METHOD path"`
+    /// key, then an exact `"path"`, then the longest suffix match. The result
+    /// does not depend on the map's iteration order.
+    pub fn lookup_key<'a, V>(&self, entries: &'a HashMap<String, V>) -> Option<&'a V> {
+        entries
+            .iter()
+            .filter(|(key, _)| self.matches_key(key))
+            .max_by(|(a, _), (b, _)| {
+                self.key_specificity(a)
+                    .cmp(&self.key_specificity(b))
+                    .then_with(|| b.cmp(a))
+            })
+            .map(|(_, value)| value)
+    }
+
+    /// Ranking of a matching key: exact path, then path length, then whether
+    /// it names the method
+    fn key_specificity(&self, key: &str) -> (bool, usize, bool) {
+        let (has_method, path) = match key.split_once(' ') {
+            Some((_, path)) => (true, path.trim()),
+            None => (false, key),
+        };
+        (path == self.path, path.len(), has_method)
+    }
+
+    /// Explicitly public: the spec declares `security: []`
+    pub fn is_public(&self) -> bool {
+        self.security.as_ref().is_some_and(|s| s.is_empty())
+    }
+
+    /// The declared security rules out the attacker's bearer token
+    pub fn rejects_bearer(&self) -> bool {
+        self.security
+            .as_ref()
+            .is_some_and(|s| !s.is_empty() && !s.iter().any(|k| k.accepts_bearer()))
+    }
+
+    /// Record a structured parameter (first one wins) and keep `params` in sync
+    pub fn add_parameter(&mut self, param: Parameter) {
+        if !self.params.contains(&param.name) {
+            self.params.push(param.name.clone());
+        }
+        if self.parameter(&param.name).is_none() {
+            self.parameters.push(param);
+        }
+    }
+
+    /// All parameter names: the flat list plus any structured-only parameters
+    pub fn param_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.params.iter().map(|p| p.as_str()).collect();
+        for p in &self.parameters {
+            if !names.contains(&p.name.as_str()) {
+                names.push(&p.name);
+            }
+        }
+        names
+    }
+
+    /// Whether a parameter is required. Parameters without structured
+    /// metadata are assumed required, matching the scanner's default.
+    pub fn is_param_required(&self, name: &str) -> bool {
+        self.parameter(name).map(|p| p.required).unwrap_or(true)
+    }
+}
+
+/// Outcome of a single attack request, as exported to reports
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanResult {
+    pub method: String,
+    pub url: String,
+    pub verdict: String, // e.g., "VULNERABLE | PII: YES", "SECURE", "ERROR: ..."
+    /// Number of requests represented (greater than 1 after grouping)
+    #[serde(default = "default_count")]
+    pub count: usize,
+    /// Injected values that produced this result
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triggering_values: Vec<String>,
+    /// Value injected into each attacked parameter, by parameter name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub substitutions: BTreeMap<String, String>,
+    /// Time from sending the request to receiving the response headers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    /// The endpoint is a deprecated operation
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    /// Stable operation name (OpenAPI operationId, or the collection's request name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Highest BOLA risk score (0-100) among the parameters that were attacked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk_score: Option<u8>,
+    /// HTTP status of the response; None when the request failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// How strongly the response supports the verdict (0-100); None when
+    /// the verdict claims nothing (UNCERTAIN, ERROR)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<u8>,
+}
+
+fn default_count() -> usize {
+    1
+}
+
+impl ScanResult {
+    pub fn new(
+        method: impl Into<String>,
+        url: impl Into<String>,
+        verdict: impl Into<String>,
+    ) -> Self {
+        Self {
+            method: method.into(),
+            url: url.into(),
+            verdict: verdict.into(),
+            count: 1,
+            triggering_values: Vec::new(),
+            substitutions: BTreeMap::new(),
+            latency_ms: None,
+            deprecated: false,
+            name: None,
+            risk_score: None,
+            status: None,
+            confidence: None,
+        }
+    }
+
+    /// Record the HTTP status of the response
+    pub fn with_status(mut self, status: Option<u16>) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Record how strongly the response supports the verdict
+    pub fn with_confidence(mut self, confidence: Option<u8>) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    /// Record the BOLA risk score of the attacked endpoint
+    pub fn with_risk_score(mut self, score: Option<u8>) -> Self {
+        self.risk_score = score;
+        self
+    }
+
+    /// Attach the operation name of the endpoint that produced this result
+    pub fn with_name(mut self, name: Option<String>) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Identity used to match findings across reports: the operation name when
+    /// known, otherwise the URL
+    pub fn finding_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.url)
+    }
+
+    /// Record the injected value that produced this result
+    pub fn with_value(mut self, value: impl Into<String>) -> Self {
+        self.triggering_values.push(value.into());
+        self
+    }
+
+    /// Record which parameter received which injected value
+    pub fn with_substitutions<I>(mut self, substitutions: I) -> Self
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        self.substitutions.extend(substitutions);
+        self
+    }
+
+    /// What was injected, for reports: "param=value" pairs when the
+    /// parameters are known, otherwise the injected values
+    pub fn injected_label(&self) -> String {
+        let show = |v: &str| {
+            if v.is_empty() {
+                "\"\"".to_string()
+            } else {
+                v.to_string()
+            }
+        };
+        if self.count <= 1 && !self.substitutions.is_empty() {
+            return self
+                .substitutions
+                .iter()
+                .map(|(param, value)| format!("{}={}", param, show(value)))
+                .collect::<Vec<_>>()
+                .join(", ");
+        }
+        self.triggering_values
+            .iter()
+            .map(|v| show(v))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Record how long the request took
+    pub fn with_latency(mut self, latency_ms: u64) -> Self {
+        self.latency_ms = Some(latency_ms);
+        self
+    }
+
+    /// Mark the result as coming from a deprecated operation
+    pub fn with_deprecated(mut self, deprecated: bool) -> Self {
+        self.deprecated = deprecated;
+        self
+    }
+
+    /// Leading verdict class without annotations (VULNERABLE, SECURE, UNCERTAIN, ERROR)
+    pub fn verdict_class(&self) -> &str {
+        self.verdict.split(['|', ':']).next().unwrap_or("").trim()
+    }
+
+    /// Value of a "Name: value" note of the verdict, e.g. "YES" for "PII"
+    pub fn verdict_note(&self, name: &str) -> Option<&str> {
+        self.verdict
+            .split(" | ")
+            .skip(1)
+            .find_map(|note| note.strip_prefix(name)?.strip_prefix(": "))
+    }
+
+    /// Keyword that made a 2xx response a soft fail, if any
+    pub fn soft_fail(&self) -> Option<&str> {
+        self.verdict_note("Soft fail").map(|k| k.trim_matches('\''))
+    }
+}
+
+/// Why a collection could not be parsed
+#[derive(Debug)]
+pub enum ParseError {
+    /// The collection file or directory could not be read
+    IoError {
+        path: String,
+        source: std::io::Error,
+    },
+    /// The content is not valid JSON; line and column are 1-based
+    JsonError {
+        line: usize,
+        column: usize,
+        source: serde_json::Error,
+    },
+    /// Valid input, but not in a format this parser handles
+    UnsupportedFormat(String),
+    /// The collection parsed but contains no requests
+    EmptyCollection,
+}
+
+impl ParseError {
+    pub fn io(path: impl Into<String>, source: std::io::Error) -> Self {
+        ParseError::IoError {
+            path: path.into(),
+            source,
+        }
+    }
+}
+
+impl From<serde_json::Error> for ParseError {
+    fn from(source: serde_json::Error) -> Self {
+        ParseError::JsonError {
+            line: source.line(),
+            column: source.column(),
+            source,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::IoError { path, source } => {
+                write!(f, "Failed to read {}: {}", path, source)
+            }
+            // serde_json's message already ends with "at line L column C"
+            ParseError::JsonError { source, .. } => write!(f, "Failed to parse JSON: {}", source),
+            ParseError::UnsupportedFormat(reason) => f.write_str(reason),
+            ParseError::EmptyCollection => f.write_str("The collection contains no requests"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::IoError { source, .. } => Some(source),
+            ParseError::JsonError { source, .. } => Some(source),
+            _ => None,
         }
     }
 }
@@ -78,5 +532,12 @@ impl Endpoint {
 /// Trait for parsing API collections (Bruno, Postman, etc.)
 pub trait CollectionParser {
     /// Parse a collection file and return a list of endpoints
-    fn parse(&self, file_path: &str) -> Result<Vec<Endpoint>, String>;
+    fn parse(&self, file_path: &str) -> Result<Vec<Endpoint>, ParseError>;
+
+    /// Parse collection content already in memory (e.g., downloaded over HTTP)
+    fn parse_content(&self, _content: &str) -> Result<Vec<Endpoint>, ParseError> {
+        Err(ParseError::UnsupportedFormat(
+            "This collection format can only be parsed from a local path".to_string(),
+        ))
+    }
 }
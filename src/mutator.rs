@@ -1,28 +1,35 @@
 // Mutational fuzzing for Doppel
 // Generates BOLA-specific mutations based on parameter type
 
+use base64::{engine::general_purpose, Engine as _};
+
 /// Generate BOLA-focused mutations for a parameter.
 /// These mutations test for broken object level authorization by trying:
 /// - Adjacent IDs (e.g., user_123 → user_122, user_124)
-/// - Common privileged IDs (0, 1, admin)
+/// - Adjacent MongoDB ObjectIds (counter ± n)
+/// - Common privileged IDs (0, 1, admin) and current-user aliases (me, self, current)
 /// - Boundary values (-1, empty)
 pub fn mutate_param(param: &str) -> Vec<String> {
     let mut mutations = vec![param.to_string()]; // Always include original
 
-    // Try to detect ID pattern and generate smart mutations
-    if let Some(adjacent) = generate_adjacent_ids(param, 2) {
-        mutations.extend(adjacent);
+    // Try to detect ID pattern and generate smart mutations.
+    // Neighbors of random tokens don't exist, so don't waste requests on them.
+    if is_object_id(param) {
+        mutations.extend(generate_adjacent_object_ids(param, 2));
+    } else if !is_likely_random_id(param) {
+        if let Some(adjacent) = generate_adjacent_ids(param, 2) {
+            mutations.extend(adjacent);
+        }
     }
 
     // Add common BOLA test values
-    mutations.extend(vec![
-        "0".to_string(),     // Often admin/system user
-        "1".to_string(),     // Often first user
-        "admin".to_string(), // Common admin identifier
-        "-1".to_string(),    // Out of bounds test
-        "".to_string(),      // Empty value
-        "null".to_string(),  // Null string test
-    ]);
+    mutations.extend(
+        PRIVILEGED_IDS
+            .iter()
+            .chain(CURRENT_USER_ALIASES)
+            .chain(BOUNDARY_VALUES)
+            .map(|v| v.to_string()),
+    );
 
     // Deduplicate
     mutations.sort();
@@ -30,6 +37,282 @@ pub fn mutate_param(param: &str) -> Vec<String> {
     mutations
 }
 
+/// Ids of accounts that exist on most systems
+const PRIVILEGED_IDS: &[&str] = &[
+    "0",     // Often admin/system user
+    "1",     // Often first user
+    "admin", // Common admin identifier
+];
+
+/// Aliases many APIs resolve to the caller; a route that accepts them may
+/// not check ownership of the ids it accepts either
+const CURRENT_USER_ALIASES: &[&str] = &["me", "self", "current"];
+
+const BOUNDARY_VALUES: &[&str] = &[
+    "-1",   // Out of bounds test
+    "",     // Empty value
+    "null", // Null string test
+];
+
+/// Order mutations by how likely they are to reach another user's object and
+/// keep the first `max` (--max-mutations): the original value, then guesses
+/// derived from it (adjacent ids, name variants, user-supplied and
+/// discovered ids), then well-known privileged ids, current-user aliases,
+/// boundary values and numeric edge cases. Order within a group is kept.
+pub fn cap_mutations(mutations: &mut Vec<String>, original: &str, max: usize) {
+    let priority = |value: &str| {
+        let is = |list: &[&str]| list.contains(&value);
+        if value == original {
+            0
+        } else if is(PRIVILEGED_IDS) {
+            2
+        } else if is(CURRENT_USER_ALIASES) {
+            3
+        } else if is(BOUNDARY_VALUES) {
+            4
+        } else if is(NUMERIC_EDGE_VALUES) || value == format!("{}.0", original) {
+            5
+        } else {
+            1
+        }
+    };
+    mutations.sort_by_key(|m| priority(m));
+    mutations.truncate(max);
+}
+
+/// Whether an id has the MongoDB ObjectId shape (24 hex characters)
+pub fn is_object_id(id: &str) -> bool {
+    id.len() == 24 && id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Generate neighboring ObjectIds by stepping the trailing 3-byte counter.
+///
+/// An ObjectId is a 4-byte timestamp, a 5-byte random value fixed per
+/// process, and a 3-byte incrementing counter, so documents created by the
+/// same process in sequence differ only in the counter. The counter wraps
+/// within its 24 bits; the original hex case is preserved.
+fn generate_adjacent_object_ids(id: &str, range: usize) -> Vec<String> {
+    let (prefix, counter_hex) = id.split_at(18);
+    let counter = match u32::from_str_radix(counter_hex, 16) {
+        Ok(c) => c,
+        Err(_) => return Vec::new(),
+    };
+    let uppercase = id.chars().any(|c| c.is_ascii_uppercase());
+
+    let mut adjacent = Vec::new();
+    for offset in -(range as i64)..=(range as i64) {
+        if offset == 0 {
+            continue;
+        }
+        let next = (counter as i64 + offset).rem_euclid(1 << 24);
+        let hex = if uppercase {
+            format!("{:06X}", next)
+        } else {
+            format!("{:06x}", next)
+        };
+        adjacent.push(format!("{}{}", prefix, hex));
+    }
+    adjacent
+}
+
+/// Minimum length for an identifier to be considered a random token
+const RANDOM_ID_MIN_LEN: usize = 16;
+
+/// Heuristic for cryptographically random identifiers (base62/base64url
+/// tokens, random UUIDs) where adjacent-id enumeration is meaningless.
+///
+/// Random tokens are long, use only token characters, have high per-character
+/// entropy, and switch between letters and digits often. Structured ids such
+/// as "order-2024-000123" have long same-class runs and are not matched.
+pub fn is_likely_random_id(id: &str) -> bool {
+    let len = id.chars().count();
+    if len < RANDOM_ID_MIN_LEN
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        || id.chars().all(|c| c.is_ascii_digit())
+    {
+        return false;
+    }
+
+    // Shannon entropy in bits per character
+    let mut counts = std::collections::HashMap::new();
+    for c in id.chars() {
+        *counts.entry(c).or_insert(0usize) += 1;
+    }
+    let entropy: f64 = counts
+        .values()
+        .map(|&n| {
+            let p = n as f64 / len as f64;
+            -p * p.log2()
+        })
+        .sum();
+
+    // Changes between character classes (upper, lower, digit, separator)
+    let class = |c: char| {
+        if c.is_ascii_uppercase() {
+            0
+        } else if c.is_ascii_lowercase() {
+            1
+        } else if c.is_ascii_digit() {
+            2
+        } else {
+            3
+        }
+    };
+    let chars: Vec<char> = id.chars().collect();
+    let transitions = chars
+        .windows(2)
+        .filter(|w| class(w[0]) != class(w[1]))
+        .count();
+
+    entropy >= 3.0 && transitions * 4 >= len
+}
+
+/// Integer edge values that expose parsing and overflow bugs in id handling
+const NUMERIC_EDGE_VALUES: &[&str] = &[
+    "2147483647",          // i32::MAX
+    "2147483648",          // i32::MAX + 1 (int32 overflow)
+    "-2147483648",         // i32::MIN
+    "4294967296",          // u32::MAX + 1
+    "9223372036854775807", // i64::MAX
+    "9223372036854775808", // i64::MAX + 1 (int64 overflow)
+    "1.0",                 // non-integer forms of a valid id
+    "1e1",
+];
+
+/// Mutations for a parameter, adding numeric edge cases when the parameter
+/// is classified as numeric. Non-numeric parameters get `mutate_param` as is.
+pub fn mutate_param_typed(param: &str, numeric: bool) -> Vec<String> {
+    let mut mutations = mutate_param(param);
+    if numeric {
+        mutations.extend(NUMERIC_EDGE_VALUES.iter().map(|v| v.to_string()));
+        // The victim's own id in float form may bypass an equality check
+        // while still resolving to the same record
+        if param.parse::<u64>().is_ok() {
+            mutations.push(format!("{}.0", param));
+        }
+        mutations.sort();
+        mutations.dedup();
+    }
+    mutations
+}
+
+/// Usernames that exist on most systems and are worth guessing
+const COMMON_USERNAMES: &[&str] = &[
+    "admin",
+    "administrator",
+    "root",
+    "support",
+    "test",
+    "demo",
+    "system",
+];
+
+/// Mutations for a username or handle: the id mutations, commonly present
+/// accounts, and case variants that may slip past an exact-match ownership
+/// check while resolving to the same user
+pub fn mutate_username(username: &str) -> Vec<String> {
+    let mut mutations = mutate_param(username);
+    mutations.extend(COMMON_USERNAMES.iter().map(|u| u.to_string()));
+    mutations.push(username.to_uppercase());
+    mutations.push(capitalize(username));
+    mutations.sort();
+    mutations.dedup();
+    mutations
+}
+
+/// Mutations for a resource slug: the id mutations plus the numbered
+/// variants many apps generate for duplicate titles ("my-post-2")
+pub fn mutate_slug(slug: &str) -> Vec<String> {
+    let mut mutations = mutate_param(slug);
+    if !slug.is_empty() && !slug.ends_with(|c: char| c.is_ascii_digit()) {
+        mutations.extend((1..=2).map(|n| format!("{}-{}", slug, n)));
+    }
+    mutations.sort();
+    mutations.dedup();
+    mutations
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Base64 alphabets cursors are commonly encoded with
+const CURSOR_ENGINES: [general_purpose::GeneralPurpose; 4] = [
+    general_purpose::STANDARD,
+    general_purpose::URL_SAFE,
+    general_purpose::STANDARD_NO_PAD,
+    general_purpose::URL_SAFE_NO_PAD,
+];
+
+/// Embedded numbers shifted per cursor, so long cursors don't explode the request count
+const MAX_CURSOR_NUMBERS: usize = 3;
+
+/// Mutations for an opaque pagination cursor. Base64 cursors are decoded,
+/// each embedded number (offsets, row ids, user ids) is shifted by one, and
+/// the result is re-encoded with the same alphabet; other cursors have their
+/// numbers shifted in place. The original cursor is always included.
+///
+/// Example: "eyJpZCI6MTB9" (`{"id":10}`) → `{"id":9}` and `{"id":11}`, re-encoded
+pub fn mutate_cursor(cursor: &str) -> Vec<String> {
+    let decoded = CURSOR_ENGINES.iter().find_map(|engine| {
+        let bytes = engine.decode(cursor).ok()?;
+        let text = String::from_utf8(bytes).ok()?;
+        let printable = text.chars().all(|c| c.is_ascii_graphic() || c == ' ');
+        printable.then_some((engine, text))
+    });
+
+    let mut mutations = vec![cursor.to_string()];
+    match decoded {
+        Some((engine, text)) => mutations.extend(
+            shift_numbers(&text)
+                .into_iter()
+                .map(|shifted| engine.encode(shifted)),
+        ),
+        None => mutations.extend(shift_numbers(cursor)),
+    }
+    mutations.dedup();
+    mutations
+}
+
+/// Copies of `text` with one embedded number moved by ±1 each
+fn shift_numbers(text: &str) -> Vec<String> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        match (c.is_ascii_digit(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    let mut shifted = Vec::new();
+    for (s, e) in spans.into_iter().take(MAX_CURSOR_NUMBERS) {
+        let Ok(number) = text[s..e].parse::<u64>() else {
+            continue;
+        };
+        for neighbor in [number.checked_sub(1), number.checked_add(1)]
+            .into_iter()
+            .flatten()
+        {
+            shifted.push(format!("{}{}{}", &text[..s], neighbor, &text[e..]));
+        }
+    }
+    shifted
+}
+
 /// Generate adjacent IDs by detecting and modifying numeric suffixes.
 ///
 /// Examples:
@@ -119,6 +402,46 @@ fn count_digits(n: usize) -> usize {
     }
 }
 
+/// Inclusive numeric id range swept by --enumerate, e.g. "1-1000"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl IdRange {
+    /// Parse "START-END" (a single number is a one-id range)
+    pub fn parse(s: &str) -> Result<IdRange, String> {
+        let number = |part: &str| {
+            part.trim()
+                .parse::<u64>()
+                .map_err(|_| format!("'{}' is not a non-negative integer", part.trim()))
+        };
+        let (start, end) = match s.split_once('-') {
+            Some((start, end)) => (number(start)?, number(end)?),
+            None => {
+                let id = number(s)?;
+                (id, id)
+            }
+        };
+        if start > end {
+            return Err(format!("range start {} is after its end {}", start, end));
+        }
+        Ok(IdRange { start, end })
+    }
+
+    /// Number of ids in the range; the full `0-18446744073709551615` range
+    /// saturates at `u64::MAX`
+    pub fn count(&self) -> u64 {
+        (self.end - self.start).saturating_add(1)
+    }
+
+    /// Every id in the range, in order
+    pub fn ids(&self) -> impl Iterator<Item = String> {
+        (self.start..=self.end).map(|id| id.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -225,6 +548,21 @@ mod tests {
         assert!(mutations.contains(&"null".to_string()));
     }
 
+    #[test]
+    fn test_cap_mutations_keeps_best_guesses() {
+        let mut mutations = mutate_param_typed("123", true);
+        cap_mutations(&mut mutations, "123", 5);
+        assert_eq!(mutations, vec!["123", "121", "122", "124", "125"]);
+
+        let mut mutations = mutate_param_typed("123", true);
+        cap_mutations(&mut mutations, "123", 8);
+        assert_eq!(&mutations[5..], ["0", "1", "admin"]);
+
+        let mut short = vec!["5".to_string(), "6".to_string()];
+        cap_mutations(&mut short, "5", 10);
+        assert_eq!(short, vec!["5", "6"]);
+    }
+
     #[test]
     fn test_mutate_param_without_number() {
         let mutations = mutate_param("username");
@@ -236,6 +574,9 @@ mod tests {
         assert!(mutations.contains(&"0".to_string()));
         assert!(mutations.contains(&"1".to_string()));
         assert!(mutations.contains(&"admin".to_string()));
+        for alias in ["me", "self", "current"] {
+            assert!(mutations.contains(&alias.to_string()), "missing {}", alias);
+        }
     }
 
     #[test]
@@ -288,4 +629,165 @@ mod tests {
         assert!(mutations.contains(&"0".to_string()));
         assert!(mutations.contains(&"admin".to_string()));
     }
+
+    // ============================================
+    // Random ID Detection Tests
+    // ============================================
+
+    #[test]
+    fn test_random_ids_detected() {
+        assert!(is_likely_random_id("cus_NffrFeUfNV2Hib"));
+        assert!(is_likely_random_id("k3Jd9QxL2mZp8RtV4wYb"));
+        assert!(is_likely_random_id("550e8400-e29b-41d4-a716-446655440000"));
+    }
+
+    #[test]
+    fn test_structured_ids_not_random() {
+        assert!(!is_likely_random_id("user_123"));
+        assert!(!is_likely_random_id("123456789012345678"));
+        assert!(!is_likely_random_id("order-2024-000123"));
+        assert!(!is_likely_random_id("customer_account_42"));
+        assert!(!is_likely_random_id("abc/def+ghi=jklmnop"));
+    }
+
+    #[test]
+    fn test_random_id_skips_adjacency() {
+        let token = "k3Jd9QxL2mZp8RtV4wY7";
+        let mutations = mutate_param(token);
+        assert!(mutations.contains(&token.to_string()));
+        assert!(!mutations.contains(&"k3Jd9QxL2mZp8RtV4wY8".to_string()));
+        assert!(!mutations.contains(&"k3Jd9QxL2mZp8RtV4wY6".to_string()));
+        // Privileged values are still tried
+        assert!(mutations.contains(&"0".to_string()));
+        assert!(mutations.contains(&"admin".to_string()));
+    }
+
+    // ============================================
+    // MongoDB ObjectId Tests
+    // ============================================
+
+    #[test]
+    fn test_is_object_id() {
+        assert!(is_object_id("507f1f77bcf86cd799439011"));
+        assert!(is_object_id("507F1F77BCF86CD799439011"));
+        assert!(!is_object_id("507f1f77bcf86cd79943901")); // 23 chars
+        assert!(!is_object_id("507f1f77bcf86cd79943901g"));
+    }
+
+    #[test]
+    fn test_object_id_neighbors_step_counter() {
+        let adjacent = generate_adjacent_object_ids("507f1f77bcf86cd79943900f", 2);
+        assert_eq!(
+            adjacent,
+            vec![
+                "507f1f77bcf86cd79943900d",
+                "507f1f77bcf86cd79943900e",
+                "507f1f77bcf86cd799439010",
+                "507f1f77bcf86cd799439011",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_object_id_counter_wraps() {
+        let adjacent = generate_adjacent_object_ids("507f1f77bcf86cd799ffffff", 1);
+        assert!(adjacent.contains(&"507f1f77bcf86cd799fffffe".to_string()));
+        assert!(adjacent.contains(&"507f1f77bcf86cd799000000".to_string()));
+    }
+
+    #[test]
+    fn test_mutate_param_object_id() {
+        let mutations = mutate_param("507f1f77bcf86cd799439011");
+        assert!(mutations.contains(&"507f1f77bcf86cd799439010".to_string()));
+        assert!(mutations.contains(&"507f1f77bcf86cd799439012".to_string()));
+        assert!(mutations.contains(&"admin".to_string()));
+    }
+
+    // ============================================
+    // Numeric Edge Mutation Tests
+    // ============================================
+
+    #[test]
+    fn test_numeric_edges_only_for_numeric_params() {
+        let numeric = mutate_param_typed("123", true);
+        for edge in [
+            "0",
+            "-1",
+            "2147483648",
+            "9223372036854775807",
+            "1.0",
+            "1e1",
+            "123.0",
+        ] {
+            assert!(numeric.contains(&edge.to_string()), "missing {}", edge);
+        }
+
+        let plain = mutate_param_typed("123", false);
+        assert_eq!(plain, mutate_param("123"));
+        assert!(!plain.contains(&"2147483648".to_string()));
+    }
+
+    #[test]
+    fn test_mutate_cursor_base64() {
+        let cursor = general_purpose::STANDARD.encode(r#"{"offset":20,"uid":7}"#);
+        let decoded: Vec<String> = mutate_cursor(&cursor)
+            .iter()
+            .map(|m| String::from_utf8(general_purpose::STANDARD.decode(m).unwrap()).unwrap())
+            .collect();
+        assert_eq!(
+            decoded,
+            vec![
+                r#"{"offset":20,"uid":7}"#,
+                r#"{"offset":19,"uid":7}"#,
+                r#"{"offset":21,"uid":7}"#,
+                r#"{"offset":20,"uid":6}"#,
+                r#"{"offset":20,"uid":8}"#,
+            ]
+        );
+
+        // URL-safe, unpadded cursors keep their alphabet
+        let cursor = general_purpose::URL_SAFE_NO_PAD.encode("user:41");
+        let mutations = mutate_cursor(&cursor);
+        assert!(mutations.contains(&general_purpose::URL_SAFE_NO_PAD.encode("user:42")));
+    }
+
+    #[test]
+    fn test_mutate_username() {
+        let mutations = mutate_username("alice");
+        for expected in ["alice", "Alice", "ALICE", "admin", "root"] {
+            assert!(mutations.contains(&expected.to_string()), "{}", expected);
+        }
+        // Numbered handles get their neighbors
+        assert!(mutate_username("bob42").contains(&"bob43".to_string()));
+    }
+
+    #[test]
+    fn test_mutate_slug() {
+        let mutations = mutate_slug("my-first-post");
+        assert!(mutations.contains(&"my-first-post".to_string()));
+        assert!(mutations.contains(&"my-first-post-2".to_string()));
+        // Already numbered slugs are shifted instead
+        assert!(mutate_slug("my-post-2").contains(&"my-post-3".to_string()));
+    }
+
+    #[test]
+    fn test_id_range() {
+        let range = IdRange::parse("3-6").unwrap();
+        assert_eq!(range.count(), 4);
+        assert_eq!(range.ids().collect::<Vec<_>>(), vec!["3", "4", "5", "6"]);
+        assert_eq!(IdRange::parse("42").unwrap().count(), 1);
+        assert!(IdRange::parse("10-1").is_err());
+        assert!(IdRange::parse("a-5").is_err());
+        assert!(IdRange::parse("-5").is_err());
+        let full = IdRange::parse(&format!("0-{}", u64::MAX)).unwrap();
+        assert_eq!(full.count(), u64::MAX);
+        assert_eq!(full.ids().nth(2).as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn test_mutate_cursor_plain() {
+        assert_eq!(mutate_cursor("page_0"), vec!["page_0", "page_1"]);
+        // Nothing to shift: only the original is sent
+        assert_eq!(mutate_cursor("next"), vec!["next"]);
+    }
 }
@@ -0,0 +1,109 @@
+// Scan-completion notifications for Doppel
+//
+// Posts a short JSON summary to a Slack-compatible incoming webhook so
+// scheduled scans can push alerts instead of requiring report parsing.
+// The payload carries a human-readable `text` field (rendered by Slack and
+// most chat tools) plus structured counts for other consumers.
+
+use crate::models::ScanResult;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// Maximum number of VULNERABLE endpoints listed in a notification
+const MAX_LISTED_FINDINGS: usize = 10;
+
+/// Build the webhook payload: counts by verdict and the top VULNERABLE endpoints
+pub fn build_webhook_payload(results: &[ScanResult]) -> Value {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for r in results {
+        *counts.entry(r.verdict_class().to_string()).or_insert(0) += r.count;
+    }
+
+    let vulnerable: Vec<String> = results
+        .iter()
+        .filter(|r| r.verdict_class() == "VULNERABLE")
+        .take(MAX_LISTED_FINDINGS)
+        .map(|r| format!("{} {}", r.method, r.url))
+        .collect();
+
+    let vulnerable_count = counts.get("VULNERABLE").copied().unwrap_or(0);
+    let mut text = format!(
+        "Doppel scan finished: {} VULNERABLE, {} UNCERTAIN, {} SECURE, {} ERROR",
+        vulnerable_count,
+        counts.get("UNCERTAIN").copied().unwrap_or(0),
+        counts.get("SECURE").copied().unwrap_or(0),
+        counts.get("ERROR").copied().unwrap_or(0),
+    );
+    for endpoint in &vulnerable {
+        text.push_str(&format!("\n• {}", endpoint));
+    }
+
+    json!({
+        "text": text,
+        "counts": counts,
+        "top_vulnerable": vulnerable,
+    })
+}
+
+/// POST the scan summary to a webhook URL.
+///
+/// Callers should treat errors as warnings: a failed notification must not
+/// fail the scan.
+pub async fn send_webhook(
+    client: &Client,
+    url: &str,
+    results: &[ScanResult],
+) -> Result<(), String> {
+    let resp = client
+        .post(url)
+        .json(&build_webhook_payload(results))
+        .send()
+        .await
+        .map_err(|e| format!("Webhook request failed: {}", e))?;
+
+    if !resp.status().is_success() {
+        return Err(format!("Webhook returned HTTP {}", resp.status().as_u16()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_counts_and_top_findings() {
+        let mut grouped = ScanResult::new("GET", "/users/{id}", "VULNERABLE | PII: YES");
+        grouped.count = 3;
+        let results = vec![
+            grouped,
+            ScanResult::new("GET", "/health", "SECURE"),
+            ScanResult::new("DELETE", "/users/1", "ERROR: timeout"),
+        ];
+
+        let payload = build_webhook_payload(&results);
+
+        assert_eq!(payload["counts"]["VULNERABLE"], 3);
+        assert_eq!(payload["counts"]["SECURE"], 1);
+        assert_eq!(payload["counts"]["ERROR"], 1);
+        assert_eq!(payload["top_vulnerable"][0], "GET /users/{id}");
+        let text = payload["text"].as_str().unwrap();
+        assert!(text.starts_with("Doppel scan finished: 3 VULNERABLE"));
+        assert!(text.contains("GET /users/{id}"));
+    }
+
+    #[test]
+    fn test_payload_limits_listed_findings() {
+        let results: Vec<ScanResult> = (0..25)
+            .map(|i| ScanResult::new("GET", format!("/items/{}", i), "VULNERABLE"))
+            .collect();
+
+        let payload = build_webhook_payload(&results);
+        assert_eq!(
+            payload["top_vulnerable"].as_array().unwrap().len(),
+            MAX_LISTED_FINDINGS
+        );
+        assert_eq!(payload["counts"]["VULNERABLE"], 25);
+    }
+}
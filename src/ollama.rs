@@ -9,15 +9,21 @@
 // - Prompt injection protection (structured format)
 // - Response validation
 // - Optional caching for efficiency
+// - Batch analysis off the scan's critical path, with a per-analysis timeout
 
+use futures_util::{stream, StreamExt};
 use reqwest::Client;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Mutex;
 use std::time::Duration;
 
 const OLLAMA_URL: &str = "http://localhost:11434/api/generate";
 const DEFAULT_TIMEOUT_SECS: u64 = 30;
+/// Failures in a row after which the remaining analyses of a batch are
+/// skipped: Ollama is down or overloaded and waiting on it gains nothing
+const MAX_CONSECUTIVE_FAILURES: usize = 3;
 
 /// Result of PII analysis
 #[derive(Debug, Clone)]
@@ -26,6 +32,29 @@ pub struct PiiAnalysis {
     pub raw_response: String,
 }
 
+/// Outcome of one analysis in a batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PiiOutcome {
+    Found,
+    NotFound,
+    /// The request failed or did not answer within the timeout
+    Failed(String),
+    /// Not attempted after repeated failures
+    Skipped,
+}
+
+impl PiiOutcome {
+    /// Value of the "PII: ..." note in a verdict
+    pub fn label(&self) -> &'static str {
+        match self {
+            PiiOutcome::Found => "YES",
+            PiiOutcome::NotFound => "NO",
+            PiiOutcome::Failed(_) => "ERROR",
+            PiiOutcome::Skipped => "SKIPPED",
+        }
+    }
+}
+
 /// Ollama analyzer for PII detection with caching and timeout support
 pub struct OllamaAnalyzer {
     client: Client,
@@ -46,6 +75,12 @@ impl OllamaAnalyzer {
             .build()
             .expect("Failed to create HTTP client for Ollama");
 
+        Self::with_client(model, client)
+    }
+
+    /// Create a new Ollama analyzer that reuses an existing client.
+    /// The client's timeout and proxy settings apply to Ollama calls.
+    pub fn with_client(model: String, client: Client) -> Self {
         Self {
             client,
             model,
@@ -85,6 +120,21 @@ impl OllamaAnalyzer {
         Ok(analysis)
     }
 
+    /// Analyze many bodies with at most `concurrency` requests in flight,
+    /// giving up on each after `timeout`. Once several analyses in a row have
+    /// failed the rest are skipped. Outcomes are in input order.
+    pub async fn analyze_batch(
+        &self,
+        bodies: &[Value],
+        concurrency: usize,
+        timeout: Duration,
+    ) -> Vec<PiiOutcome> {
+        run_batch(bodies, concurrency, timeout, |body| {
+            self.analyze_response(body)
+        })
+        .await
+    }
+
     /// Internal: Analyze without caching
     async fn analyze_uncached(&self, json_body: &Value) -> Result<PiiAnalysis, String> {
         // Construct prompt with injection protection
@@ -155,6 +205,48 @@ impl OllamaAnalyzer {
     }
 }
 
+/// Batch driver behind `analyze_batch`, with the analysis passed in so
+/// tests can stand in for Ollama
+async fn run_batch<'a, F, Fut>(
+    bodies: &'a [Value],
+    concurrency: usize,
+    timeout: Duration,
+    analyze: F,
+) -> Vec<PiiOutcome>
+where
+    F: Fn(&'a Value) -> Fut,
+    Fut: std::future::Future<Output = Result<PiiAnalysis, String>>,
+{
+    let failures = AtomicUsize::new(0);
+    stream::iter(bodies)
+        .map(|body| {
+            let failures = &failures;
+            let analysis = analyze(body);
+            async move {
+                if failures.load(Ordering::Relaxed) >= MAX_CONSECUTIVE_FAILURES {
+                    return PiiOutcome::Skipped;
+                }
+                let outcome = match tokio::time::timeout(timeout, analysis).await {
+                    Ok(Ok(analysis)) if analysis.contains_pii => PiiOutcome::Found,
+                    Ok(Ok(_)) => PiiOutcome::NotFound,
+                    Ok(Err(e)) => PiiOutcome::Failed(e),
+                    Err(_) => {
+                        PiiOutcome::Failed(format!("no answer within {}s", timeout.as_secs()))
+                    }
+                };
+                if matches!(outcome, PiiOutcome::Failed(_)) {
+                    failures.fetch_add(1, Ordering::Relaxed);
+                } else {
+                    failures.store(0, Ordering::Relaxed);
+                }
+                outcome
+            }
+        })
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,6 +258,14 @@ mod tests {
         assert_eq!(analyzer.cache_size(), 0);
     }
 
+    #[test]
+    fn test_ollama_analyzer_with_shared_client() {
+        let client = Client::new();
+        let analyzer = OllamaAnalyzer::with_client("llama3".to_string(), client);
+        assert_eq!(analyzer.model, "llama3");
+        assert_eq!(analyzer.cache_size(), 0);
+    }
+
     #[test]
     fn test_ollama_url_is_localhost() {
         // Ensure hardcoded URL is localhost only (security check)
@@ -197,6 +297,27 @@ mod tests {
         assert_eq!(analyzer.cache_size(), 0);
     }
 
+    #[tokio::test]
+    async fn test_batch_times_out_then_skips() {
+        let bodies: Vec<Value> = std::iter::once(serde_json::json!({"email": "a@b.c"}))
+            .chain((0..4).map(|i| serde_json::json!({ "id": i })))
+            .collect();
+
+        // Stub: answers for the email body, never answers for the rest
+        let outcomes = run_batch(&bodies, 1, Duration::from_millis(20), |body| async move {
+            if body.get("email").is_none() {
+                std::future::pending::<()>().await;
+            }
+            Ok(PiiAnalysis {
+                contains_pii: true,
+                raw_response: "YES".to_string(),
+            })
+        })
+        .await;
+        let labels: Vec<&str> = outcomes.iter().map(PiiOutcome::label).collect();
+        assert_eq!(labels, ["YES", "ERROR", "ERROR", "ERROR", "SKIPPED"]);
+    }
+
     #[tokio::test]
     #[ignore] // Requires Ollama running locally
     async fn test_pii_analysis_with_ollama() {
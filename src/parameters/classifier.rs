@@ -6,6 +6,7 @@
 // Responsibilities:
 // - Classify parameter types (UserId, ResourceId, UUID, Email, etc.)
 // - Calculate BOLA risk scores (0-100) based on multiple factors
+// - Judge whether ids are enumerable (sequential) or random
 // - Assign confidence levels to classifications
 // - Pattern matching using optimized regex
 //
@@ -37,6 +38,12 @@ pub enum ParamType {
     NumericId,
     /// Email address
     Email,
+    /// Opaque pagination cursor or page token (may encode another user's position)
+    Cursor,
+    /// Username or handle addressing a user (`/users/{username}`)
+    Username,
+    /// Human-readable resource slug (`/posts/{slug}`)
+    Slug,
     /// Date/DateTime
     DateTime,
     /// Boolean flag
@@ -82,6 +89,8 @@ pub struct DetectedParameter {
     pub param_type: ParamType,
     pub confidence: Confidence,
     pub bola_risk_score: u8, // 0-100, higher = more likely to be BOLA vulnerable
+    /// Values can be guessed by counting (sequential numeric ids)
+    pub enumerable: bool,
     pub context: ParameterContext,
 }
 
@@ -134,6 +143,27 @@ lazy_static! {
         r"(?i)^[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}$"
     ).unwrap();
 
+    // Pagination cursor / page token names
+    static ref CURSOR_PATTERNS: Vec<Regex> = vec![
+        Regex::new(r"(?i)^(cursor|next_?cursor|prev_?cursor)$").unwrap(),
+        Regex::new(r"(?i)^(page_?token|next_?page_?token|continuation_?token|next_?token)$").unwrap(),
+        Regex::new(r"(?i)^(after|before|starting_?after|ending_?before)$").unwrap(),
+    ];
+
+    // Human-readable user addressing
+    static ref USERNAME_PATTERNS: Vec<Regex> = vec![
+        Regex::new(r"(?i)^(user_?name|login|handle|screen_?name|nick_?name)$").unwrap(),
+    ];
+
+    // Slug names: slug, postSlug, org_slug, permalink
+    static ref SLUG_PATTERNS: Vec<Regex> = vec![
+        Regex::new(r"(?i)^([a-z]+_?)?slug$").unwrap(),
+        Regex::new(r"(?i)^permalink$").unwrap(),
+    ];
+
+    // Lowercase words joined by hyphens, e.g. "my-first-post"
+    static ref SLUG_VALUE_PATTERN: Regex = Regex::new(r"^[a-z0-9]+(?:-[a-z0-9]+)+$").unwrap();
+
     // Date/DateTime patterns
     static ref DATE_PATTERNS: Vec<Regex> = vec![
         Regex::new(r"(?i)^(created_?at|createdat)$").unwrap(),
@@ -143,6 +173,9 @@ lazy_static! {
         Regex::new(r"(?i)^.*_?(date|time)$").unwrap(),
     ];
 
+    // Short all-digit values look like auto-increment keys
+    static ref SEQUENTIAL_ID_PATTERN: Regex = Regex::new(r"^[0-9]{1,10}$").unwrap();
+
     // Resource type extraction from endpoint paths
     static ref RESOURCE_PATTERN: Regex = Regex::new(
         r"/([a-z]+)(?:/\{[^}]+\}|$)"
@@ -161,8 +194,32 @@ impl ParameterDetector {
         location: crate::models::ParameterLocation,
         is_required: bool,
     ) -> DetectedParameter {
-        let param_type = Self::classify_type(name);
+        Self::analyze_parameter_with_example(
+            name,
+            endpoint_path,
+            http_method,
+            location,
+            is_required,
+            None,
+        )
+    }
+
+    /// Analyze a parameter, using an example value (if the spec has one) to
+    /// judge whether its ids can be enumerated
+    pub fn analyze_parameter_with_example(
+        name: &str,
+        endpoint_path: &str,
+        http_method: &str,
+        location: crate::models::ParameterLocation,
+        is_required: bool,
+        example: Option<&str>,
+    ) -> DetectedParameter {
+        let param_type = match Self::classify_type(name) {
+            ParamType::Unknown if example.is_some_and(Self::looks_like_slug) => ParamType::Slug,
+            param_type => param_type,
+        };
         let confidence = Self::calculate_confidence(name, &param_type, endpoint_path, &location);
+        let enumerability = Self::enumerability(&param_type, example);
         let bola_risk_score = Self::calculate_bola_risk(
             name,
             &param_type,
@@ -170,6 +227,7 @@ impl ParameterDetector {
             http_method,
             &location,
             is_required,
+            enumerability,
         );
         let related_resources = Self::extract_related_resources(endpoint_path);
 
@@ -178,6 +236,7 @@ impl ParameterDetector {
             param_type,
             confidence,
             bola_risk_score,
+            enumerable: enumerability == Some(true),
             context: ParameterContext {
                 endpoint_path: endpoint_path.to_string(),
                 http_method: http_method.to_string(),
@@ -209,6 +268,25 @@ impl ParameterDetector {
             return ParamType::Uuid;
         }
 
+        // Check for pagination cursors
+        for pattern in CURSOR_PATTERNS.iter() {
+            if pattern.is_match(name) {
+                return ParamType::Cursor;
+            }
+        }
+
+        // Human-readable addressing, before the generic id patterns
+        for pattern in USERNAME_PATTERNS.iter() {
+            if pattern.is_match(name) {
+                return ParamType::Username;
+            }
+        }
+        for pattern in SLUG_PATTERNS.iter() {
+            if pattern.is_match(name) {
+                return ParamType::Slug;
+            }
+        }
+
         // Check for generic ID patterns
         for pattern in GENERIC_ID_PATTERNS.iter() {
             if pattern.is_match(name) {
@@ -244,6 +322,14 @@ impl ParameterDetector {
         ParamType::Unknown
     }
 
+    /// Whether a value has the lowercase-hyphenated shape of a slug; UUIDs
+    /// share the alphabet but are ids
+    fn looks_like_slug(value: &str) -> bool {
+        SLUG_VALUE_PATTERN.is_match(value)
+            && !UUID_PATTERN.is_match(value)
+            && value.chars().any(|c| c.is_ascii_lowercase())
+    }
+
     /// Calculate confidence level for the classification
     fn calculate_confidence(
         name: &str,
@@ -260,6 +346,9 @@ impl ParameterDetector {
             ParamType::Uuid => score += 30,
             ParamType::NumericId => score += 25,
             ParamType::Email => score += 30,
+            ParamType::Cursor => score += 25,
+            ParamType::Username => score += 35,
+            ParamType::Slug => score += 30,
             ParamType::DateTime => score += 20,
             ParamType::Boolean => score += 20,
             _ => score += 10,
@@ -298,6 +387,24 @@ impl ParameterDetector {
         }
     }
 
+    /// Whether the parameter's ids can be guessed by counting. An example value
+    /// is stronger evidence than the name; `None` means unknown.
+    fn enumerability(param_type: &ParamType, example: Option<&str>) -> Option<bool> {
+        if let Some(value) = example {
+            if SEQUENTIAL_ID_PATTERN.is_match(value) {
+                return Some(true);
+            }
+            if UUID_PATTERN.is_match(value) || crate::mutator::is_likely_random_id(value) {
+                return Some(false);
+            }
+        }
+        match param_type {
+            ParamType::NumericId => Some(true),
+            ParamType::Uuid => Some(false),
+            _ => None,
+        }
+    }
+
     /// Calculate BOLA risk score (0-100, higher = more likely vulnerable)
     fn calculate_bola_risk(
         name: &str,
@@ -306,6 +413,7 @@ impl ParameterDetector {
         http_method: &str,
         location: &crate::models::ParameterLocation,
         is_required: bool,
+        enumerability: Option<bool>,
     ) -> u8 {
         let mut risk_score = 0u8;
 
@@ -316,6 +424,11 @@ impl ParameterDetector {
             ParamType::Uuid => risk_score += 30,
             ParamType::NumericId => risk_score += 30,
             ParamType::Email => risk_score += 15,
+            // Cursors only leak data when they encode a position the server trusts
+            ParamType::Cursor => risk_score += 20,
+            // Names are guessable from profiles, links and mentions
+            ParamType::Username => risk_score += 35,
+            ParamType::Slug => risk_score += 30,
             _ => risk_score += 5,
         }
 
@@ -363,6 +476,13 @@ impl ParameterDetector {
             risk_score += 10;
         }
 
+        // Guessable ids can be enumerated; random ones must be leaked first
+        match enumerability {
+            Some(true) => risk_score += 15,
+            Some(false) => risk_score = risk_score.saturating_sub(15),
+            None => {}
+        }
+
         risk_score.min(100)
     }
 
@@ -387,7 +507,7 @@ impl ParameterDetector {
     /// Prioritize parameters for BOLA testing (returns sorted by risk score)
     pub fn prioritize_parameters(params: Vec<DetectedParameter>) -> Vec<DetectedParameter> {
         let mut sorted = params;
-        sorted.sort_by(|a, b| b.bola_risk_score.cmp(&a.bola_risk_score));
+        sorted.sort_by_key(|p| std::cmp::Reverse(p.bola_risk_score));
         sorted
     }
 
@@ -408,6 +528,13 @@ impl ParameterDetector {
             ParamType::Uuid => UUID_PATTERN.is_match(value),
             ParamType::NumericId => value.chars().all(|c| c.is_ascii_digit()),
             ParamType::Email => EMAIL_PATTERN.is_match(value),
+            ParamType::Slug => Self::looks_like_slug(value),
+            ParamType::Username => {
+                !value.is_empty()
+                    && value
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+            }
             ParamType::UserId | ParamType::ResourceId => {
                 // Accept alphanumeric with underscores, hyphens
                 !value.is_empty()
@@ -470,6 +597,75 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_classify_cursor() {
+        for name in [
+            "cursor",
+            "pageToken",
+            "page_token",
+            "after",
+            "starting_after",
+        ] {
+            assert_eq!(ParameterDetector::classify_type(name), ParamType::Cursor);
+        }
+        // A cursor on a user listing is worth testing, but ranks below ids
+        let cursor = ParameterDetector::analyze_parameter(
+            "cursor",
+            "/api/users",
+            "GET",
+            ParameterLocation::Query,
+            false,
+        );
+        let id = ParameterDetector::analyze_parameter(
+            "userId",
+            "/api/users",
+            "GET",
+            ParameterLocation::Query,
+            false,
+        );
+        assert!(cursor.bola_risk_score >= 50);
+        assert!(cursor.bola_risk_score < id.bola_risk_score);
+    }
+
+    #[test]
+    fn test_classify_username_and_slug() {
+        for name in ["username", "user_name", "handle", "login", "screenName"] {
+            assert_eq!(ParameterDetector::classify_type(name), ParamType::Username);
+        }
+        for name in ["slug", "postSlug", "org_slug", "permalink"] {
+            assert_eq!(ParameterDetector::classify_type(name), ParamType::Slug);
+        }
+
+        let username = ParameterDetector::analyze_parameter(
+            "username",
+            "/api/users/{username}",
+            "GET",
+            ParameterLocation::Path,
+            true,
+        );
+        assert!(username.bola_risk_score >= 80);
+
+        // An unknown name with a slug-shaped example is a slug; a UUID is not
+        let analyze = |example| {
+            ParameterDetector::analyze_parameter_with_example(
+                "key",
+                "/api/posts/{key}",
+                "GET",
+                ParameterLocation::Path,
+                true,
+                Some(example),
+            )
+        };
+        let slug = analyze("my-first-post");
+        assert_eq!(slug.param_type, ParamType::Slug);
+        assert!(slug.bola_risk_score >= 50);
+        assert_eq!(
+            analyze("550e8400-e29b-41d4-a716-446655440000").param_type,
+            ParamType::Unknown
+        );
+        assert_eq!(analyze("draft").param_type, ParamType::Unknown);
+    }
+
     #[test]
     fn test_bola_risk_calculation() {
         let param = ParameterDetector::analyze_parameter(
@@ -598,4 +794,49 @@ mod tests {
         assert_eq!(high_risk.len(), 1);
         assert_eq!(high_risk[0].name, "userId");
     }
+
+    #[test]
+    fn test_sequential_ids_outrank_uuids() {
+        let numeric = ParameterDetector::analyze_parameter(
+            "numId",
+            "/api/orders/{numId}",
+            "GET",
+            ParameterLocation::Path,
+            true,
+        );
+        let uuid = ParameterDetector::analyze_parameter(
+            "orderUuid",
+            "/api/orders/{orderUuid}",
+            "GET",
+            ParameterLocation::Path,
+            true,
+        );
+        assert_eq!(numeric.param_type, ParamType::NumericId);
+        assert!(numeric.enumerable);
+        assert!(!uuid.enumerable);
+        assert!(numeric.bola_risk_score > uuid.bola_risk_score);
+    }
+
+    #[test]
+    fn test_example_value_decides_enumerability() {
+        let analyze = |example| {
+            ParameterDetector::analyze_parameter_with_example(
+                "accountId",
+                "/api/transfers",
+                "POST",
+                ParameterLocation::Body,
+                false,
+                example,
+            )
+        };
+        let unknown = analyze(None);
+        let sequential = analyze(Some("1042"));
+        let random = analyze(Some("550e8400-e29b-41d4-a716-446655440000"));
+
+        assert!(!unknown.enumerable);
+        assert!(sequential.enumerable);
+        assert!(!random.enumerable);
+        assert!(sequential.bola_risk_score > unknown.bola_risk_score);
+        assert!(random.bola_risk_score < unknown.bola_risk_score);
+    }
 }
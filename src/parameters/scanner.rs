@@ -8,7 +8,8 @@
 // - Filter parameters by risk threshold
 // - Prioritize parameters for testing
 // - Generate human-readable summaries
-// - Infer parameter locations from naming conventions
+// - Resolve parameter locations (structured metadata, else naming conventions)
+// - Apply user overrides of the targeted parameter
 //
 // This module operates at the ENDPOINT level, while param_detector.rs
 // operates at the individual PARAMETER level.
@@ -19,25 +20,36 @@
 //
 // Used by: main.rs during scan planning and execution
 
-use super::classifier::{DetectedParameter, ParameterDetector};
+use super::classifier::{DetectedParameter, ParamType, ParameterDetector};
 use crate::models::{Endpoint, ParameterLocation};
+use std::collections::HashMap;
 
 /// Analyze all parameters in an endpoint and return prioritized list
 pub fn analyze_endpoint_parameters(endpoint: &Endpoint) -> Vec<DetectedParameter> {
     let mut detected_params = Vec::new();
     let method_str = format!("{}", endpoint.method);
 
-    for param_name in &endpoint.params {
-        // Determine parameter location based on naming convention and endpoint structure
-        let location = infer_parameter_location(param_name, &endpoint.path);
+    for param_name in endpoint.param_names() {
+        let location = parameter_location(endpoint, param_name);
+
+        // Spec examples tell sequential ids apart from random ones
+        let example = endpoint
+            .example_values
+            .get(param_name)
+            .and_then(|v| match v {
+                serde_json::Value::String(s) => Some(s.clone()),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            });
 
         // Use detector to analyze the parameter
-        let detected = ParameterDetector::analyze_parameter(
+        let detected = ParameterDetector::analyze_parameter_with_example(
             param_name,
             &endpoint.path,
             &method_str,
             location,
-            true, // Assume required for now (parsers can improve this)
+            endpoint.is_param_required(param_name),
+            example.as_deref(),
         );
 
         detected_params.push(detected);
@@ -47,6 +59,15 @@ pub fn analyze_endpoint_parameters(endpoint: &Endpoint) -> Vec<DetectedParameter
     ParameterDetector::prioritize_parameters(detected_params)
 }
 
+/// Where a parameter goes in the request. Structured metadata from the parser
+/// is authoritative; naming conventions are only a fallback.
+pub fn parameter_location(endpoint: &Endpoint, param_name: &str) -> ParameterLocation {
+    endpoint
+        .parameter(param_name)
+        .map(|p| p.location.clone())
+        .unwrap_or_else(|| infer_parameter_location(param_name, &endpoint.path))
+}
+
 /// Infer parameter location from naming convention
 fn infer_parameter_location(param_name: &str, endpoint_path: &str) -> ParameterLocation {
     // Body parameters are prefixed with "body."
@@ -64,12 +85,92 @@ fn infer_parameter_location(param_name: &str, endpoint_path: &str) -> ParameterL
     ParameterLocation::Query
 }
 
+/// Whether anything in the request can name an object: a parameter or a
+/// `{param}` path segment. Endpoints without one (health checks, login) are
+/// not BOLA candidates. Postman `{{variables}}` do not count.
+pub fn has_object_reference(endpoint: &Endpoint) -> bool {
+    !endpoint.param_names().is_empty()
+        || endpoint
+            .path
+            .split('/')
+            .any(|s| s.starts_with('{') && !s.starts_with("{{"))
+}
+
+/// Whether a parameter takes numeric values, from its spec type or its classification
+pub fn is_numeric_param(endpoint: &Endpoint, param: &DetectedParameter) -> bool {
+    let schema_numeric = endpoint
+        .parameter(&param.name)
+        .and_then(|p| p.schema_type.as_deref())
+        .is_some_and(|t| t == "integer" || t == "number");
+    schema_numeric || matches!(param.param_type, ParamType::NumericId | ParamType::Number)
+}
+
 /// Get only high-risk BOLA parameters from an endpoint
 pub fn get_high_risk_params(endpoint: &Endpoint, min_risk_score: u8) -> Vec<DetectedParameter> {
     let all_params = analyze_endpoint_parameters(endpoint);
     ParameterDetector::filter_high_risk(all_params, min_risk_score)
 }
 
+/// Parameters the user names as the object id, overriding classification.
+///
+/// Per-endpoint entries come from a JSON object mapping `"METHOD path"` (or
+/// just `"path"` for every method) to a parameter name, e.g.
+/// `{"GET /api/orders/{orderId}": "orderId"}`. A path also matches endpoints
+/// whose path ends with it, so spec server prefixes can be left out; when
+/// several keys match, the most specific one wins.
+#[derive(Debug, Clone, Default)]
+pub struct TargetOverrides {
+    /// Parameter targeted on every endpoint that has it (--target-param)
+    pub global: Option<String>,
+    pub per_endpoint: HashMap<String, String>,
+}
+
+impl TargetOverrides {
+    /// Parse the per-endpoint override file
+    pub fn parse_overrides(content: &str) -> Result<HashMap<String, String>, String> {
+        serde_json::from_str(content).map_err(|e| {
+            format!(
+                "Invalid target override file (expected {{\"METHOD path\": \"param\"}}): {}",
+                e
+            )
+        })
+    }
+
+    /// Read and parse the per-endpoint override file
+    pub fn load_overrides(path: &str) -> Result<HashMap<String, String>, String> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        Self::parse_overrides(&content)
+    }
+
+    /// Target parameter for an endpoint: its own override, else the global one
+    pub fn target_for(&self, endpoint: &Endpoint) -> Option<&str> {
+        endpoint
+            .lookup_key(&self.per_endpoint)
+            .map(String::as_str)
+            .or(self.global.as_deref())
+    }
+}
+
+/// Parameters to attack on an endpoint. An override targets exactly the named
+/// parameter (body fields may be named without their "body." prefix),
+/// whatever its risk score, and skips endpoints that don't have it; otherwise
+/// the high-risk parameters are used.
+pub fn get_target_params(
+    endpoint: &Endpoint,
+    min_risk_score: u8,
+    overrides: &TargetOverrides,
+) -> Vec<DetectedParameter> {
+    let Some(target) = overrides.target_for(endpoint) else {
+        return get_high_risk_params(endpoint, min_risk_score);
+    };
+    analyze_endpoint_parameters(endpoint)
+        .into_iter()
+        .filter(|p| p.name == target || p.name.strip_prefix("body.") == Some(target))
+        .take(1)
+        .collect()
+}
+
 /// Get a summary report of parameter analysis for an endpoint
 pub fn get_parameter_summary(endpoint: &Endpoint) -> String {
     let params = analyze_endpoint_parameters(endpoint);
@@ -102,7 +203,7 @@ pub fn get_parameter_summary(endpoint: &Endpoint) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::Method;
+    use crate::models::{Method, Parameter};
 
     #[test]
     fn test_analyze_endpoint_with_user_id() {
@@ -121,6 +222,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_has_object_reference() {
+        let static_endpoint =
+            |path: &str| Endpoint::new(Method::GET, path.to_string(), None, Vec::new());
+        assert!(!has_object_reference(&static_endpoint(
+            "{{baseUrl}}/health"
+        )));
+        assert!(has_object_reference(&static_endpoint("/users/{id}")));
+        let login = Endpoint::new(
+            Method::POST,
+            "/login".to_string(),
+            None,
+            vec!["body.username".to_string()],
+        );
+        assert!(has_object_reference(&login));
+    }
+
     #[test]
     fn test_high_risk_filtering() {
         let endpoint = Endpoint::new(
@@ -136,10 +254,112 @@ mod tests {
 
         let high_risk = get_high_risk_params(&endpoint, 50);
         // userId should be high risk, name and email should be lower
-        assert!(high_risk.len() >= 1);
+        assert!(!high_risk.is_empty());
         assert!(high_risk.iter().any(|p| p.name == "body.userId"));
     }
 
+    #[test]
+    fn test_optional_parameter_scores_lower() {
+        let mut endpoint = Endpoint::new(
+            Method::PUT,
+            "/api/users".to_string(),
+            None,
+            vec!["body.ownerId".to_string(), "body.userId".to_string()],
+        );
+        endpoint.parameters.push(Parameter {
+            name: "body.ownerId".to_string(),
+            location: ParameterLocation::Body,
+            required: false,
+            schema_type: None,
+            array_style: None,
+            content_type: None,
+        });
+
+        let params = analyze_endpoint_parameters(&endpoint);
+        let owner = params.iter().find(|p| p.name == "body.ownerId").unwrap();
+        let user = params.iter().find(|p| p.name == "body.userId").unwrap();
+        assert!(!owner.context.is_required);
+        assert!(user.context.is_required);
+        assert!(owner.bola_risk_score < user.bola_risk_score);
+    }
+
+    #[test]
+    fn test_target_overrides() {
+        let endpoint = Endpoint::new(
+            Method::POST,
+            "https://api.example.com/v1/orders/{orderId}".to_string(),
+            None,
+            vec!["orderId".to_string(), "body.customerRef".to_string()],
+        );
+        let pick = |overrides: &TargetOverrides| -> Vec<String> {
+            get_target_params(&endpoint, 90, overrides)
+                .into_iter()
+                .map(|p| p.name)
+                .collect()
+        };
+
+        // Per-endpoint override wins, even below the risk threshold
+        let overrides = TargetOverrides {
+            global: Some("orderId".to_string()),
+            per_endpoint: TargetOverrides::parse_overrides(
+                r#"{"post /v1/orders/{orderId}": "customerRef"}"#,
+            )
+            .unwrap(),
+        };
+        assert_eq!(pick(&overrides), vec!["body.customerRef"]);
+
+        let global = TargetOverrides {
+            global: Some("orderId".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(pick(&global), vec!["orderId"]);
+
+        // Endpoints without the named parameter are skipped
+        let missing = TargetOverrides {
+            global: Some("accountId".to_string()),
+            ..Default::default()
+        };
+        assert!(pick(&missing).is_empty());
+        assert!(TargetOverrides::parse_overrides("[1]").is_err());
+    }
+
+    #[test]
+    fn test_target_overrides_prefer_the_most_specific_key() {
+        let nested = Endpoint::new(
+            Method::GET,
+            "/orgs/{orgId}/users/{id}".to_string(),
+            None,
+            vec!["orgId".to_string(), "id".to_string()],
+        );
+        let flat = Endpoint::new(
+            Method::GET,
+            "/users/{id}".to_string(),
+            None,
+            vec!["id".to_string()],
+        );
+        let overrides = TargetOverrides {
+            global: None,
+            per_endpoint: TargetOverrides::parse_overrides(
+                r#"{
+                    "/users/{id}": "id",
+                    "/orgs/{orgId}/users/{id}": "orgId",
+                    "DELETE /orgs/{orgId}/users/{id}": "id"
+                }"#,
+            )
+            .unwrap(),
+        };
+        // The suffix key matches both, but the longer key names the nested path
+        assert_eq!(overrides.target_for(&nested), Some("orgId"));
+        assert_eq!(overrides.target_for(&flat), Some("id"));
+
+        // An exact "METHOD path" key beats the same path without a method
+        let delete = Endpoint {
+            method: Method::DELETE,
+            ..nested
+        };
+        assert_eq!(overrides.target_for(&delete), Some("id"));
+    }
+
     #[test]
     fn test_parameter_location_inference() {
         assert_eq!(
@@ -170,4 +390,58 @@ mod tests {
         assert!(params[0].bola_risk_score > params[2].bola_risk_score);
         assert!(params[1].bola_risk_score > params[2].bola_risk_score);
     }
+
+    #[test]
+    fn test_structured_location_wins() {
+        // A header parameter cannot be guessed from its name
+        let mut endpoint = Endpoint::new(Method::GET, "/api/orders".to_string(), None, vec![]);
+        endpoint.add_parameter(Parameter::new("X-Account-Id", ParameterLocation::Header));
+
+        let params = analyze_endpoint_parameters(&endpoint);
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].context.location, ParameterLocation::Header);
+        assert_eq!(
+            parameter_location(&endpoint, "X-Account-Id"),
+            ParameterLocation::Header
+        );
+        assert_eq!(
+            parameter_location(&endpoint, "search"),
+            ParameterLocation::Query
+        );
+    }
+
+    #[test]
+    fn test_example_value_marks_enumerable() {
+        let mut endpoint = Endpoint::new(
+            Method::GET,
+            "/api/accounts/{accountId}".to_string(),
+            None,
+            vec!["accountId".to_string()],
+        );
+        endpoint
+            .example_values
+            .insert("accountId".to_string(), serde_json::json!(1042));
+
+        let params = analyze_endpoint_parameters(&endpoint);
+        assert!(params[0].enumerable);
+    }
+
+    #[test]
+    fn test_numeric_param_from_schema_type() {
+        let mut endpoint = Endpoint::new(Method::GET, "/api/orders".to_string(), None, vec![]);
+        endpoint.add_parameter(Parameter {
+            name: "ref".to_string(),
+            location: ParameterLocation::Query,
+            required: true,
+            schema_type: Some("integer".to_string()),
+            array_style: None,
+            content_type: None,
+        });
+        endpoint.add_parameter(Parameter::new("token", ParameterLocation::Query));
+
+        let params = analyze_endpoint_parameters(&endpoint);
+        let by_name = |n: &str| params.iter().find(|p| p.name == n).unwrap();
+        assert!(is_numeric_param(&endpoint, by_name("ref")));
+        assert!(!is_numeric_param(&endpoint, by_name("token")));
+    }
 }
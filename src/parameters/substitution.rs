@@ -12,9 +12,20 @@
 //   Map:    {"userId" => "victim_456", "id" => "victim_456"}
 //   Output: {"userId": "victim_456", "nested": {"id": "victim_456"}}
 //
+// Body templates: per-endpoint JSON bodies with {{NAME}} placeholders that
+// are filled at request time, for endpoints whose exact body shape matters.
+//
 // Used by: main.rs during request building and fuzzing
 
+use crate::models::Endpoint;
+use serde::Deserialize;
 use serde_json::Value;
+use std::collections::HashMap;
+
+/// Placeholder for the id injected into the request
+pub const VICTIM_ID_PLACEHOLDER: &str = "VICTIM_ID";
+/// Placeholder for the attacker's own id
+pub const ATTACKER_ID_PLACEHOLDER: &str = "ATTACKER_ID";
 
 pub fn substitute_params(json: &mut Value, param_map: &std::collections::HashMap<String, String>) {
     match json {
@@ -36,6 +47,95 @@ pub fn substitute_params(json: &mut Value, param_map: &std::collections::HashMap
     }
 }
 
+/// User-supplied request bodies, keyed like target overrides (`"METHOD path"`
+/// or `"path"`, the most specific match winning). When an endpoint has a template it is sent as the whole body
+/// and schema-derived body fields are left out, e.g.
+/// `{"variables": {"TENANT": "acme"}, "templates": {"POST /api/orders":
+/// {"order": {"ownerId": "{{VICTIM_ID}}", "tenant": "{{TENANT}}"}}}}`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BodyTemplates {
+    /// Values for `{{NAME}}` placeholders; `ATTACKER_ID` is filled from the token
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    #[serde(default)]
+    pub templates: HashMap<String, Value>,
+}
+
+impl BodyTemplates {
+    /// Parse a body template file
+    pub fn parse(content: &str) -> Result<Self, String> {
+        serde_json::from_str(content).map_err(|e| {
+            format!(
+                "Invalid body template file (expected {{\"templates\": {{\"METHOD path\": {{...}}}}}}): {}",
+                e
+            )
+        })
+    }
+
+    /// Read and parse a body template file
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        Self::parse(&content)
+    }
+
+    /// The endpoint's body with `victim_id` and the config variables filled in
+    pub fn render(&self, endpoint: &Endpoint, victim_id: &str) -> Option<Value> {
+        let template = endpoint.lookup_key(&self.templates)?;
+        let mut variables = self.variables.clone();
+        variables.insert(VICTIM_ID_PLACEHOLDER.to_string(), victim_id.to_string());
+        Some(render_template(template, &variables))
+    }
+}
+
+/// Replace `{{NAME}}` placeholders in every string of a JSON value. Unknown
+/// names are left as written so a typo shows up in the request.
+pub fn render_template(template: &Value, variables: &HashMap<String, String>) -> Value {
+    match template {
+        Value::String(s) => {
+            let mut rendered = s.clone();
+            for (name, value) in variables {
+                rendered = rendered.replace(&format!("{{{{{}}}}}", name), value);
+            }
+            Value::String(rendered)
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), render_template(v, variables)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|v| render_template(v, variables))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Replace `{{NAME}}` placeholders in a header value. Names match
+/// case-insensitively, so `{{victim_id}}` is `{{VICTIM_ID}}`; unknown names
+/// are left as written.
+pub fn render_header(value: &str, variables: &HashMap<String, String>) -> String {
+    let mut rendered = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim();
+        rendered.push_str(&rest[..start]);
+        match variables.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)) {
+            Some((_, v)) => rendered.push_str(v),
+            None => rendered.push_str(&rest[start..start + 4 + len]),
+        }
+        rest = &rest[start + 4 + len..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -51,4 +151,67 @@ mod tests {
         assert_eq!(v["userId"], json!("NEW_USER"));
         assert_eq!(v["nested"]["id"], json!("NEW_ID"));
     }
+
+    #[test]
+    fn render_body_template() {
+        let templates = BodyTemplates::parse(
+            r#"{
+                "variables": {"TENANT": "acme", "ATTACKER_ID": "7"},
+                "templates": {"POST /api/orders": {
+                    "order": {"ownerId": "{{VICTIM_ID}}", "tenant": "{{TENANT}}"},
+                    "note": "from {{ATTACKER_ID}} about {{VICTIM_ID}}",
+                    "items": [{"ref": "{{UNKNOWN}}"}],
+                    "qty": 1
+                }}
+            }"#,
+        )
+        .unwrap();
+        let endpoint = Endpoint::new(
+            crate::models::Method::POST,
+            "/v1/api/orders".to_string(),
+            None,
+            vec![],
+        );
+        let body = templates.render(&endpoint, "42").unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "order": {"ownerId": "42", "tenant": "acme"},
+                "note": "from 7 about 42",
+                "items": [{"ref": "{{UNKNOWN}}"}],
+                "qty": 1
+            })
+        );
+
+        let other = Endpoint::new(
+            crate::models::Method::GET,
+            "/v1/api/orders".to_string(),
+            None,
+            vec![],
+        );
+        assert!(templates.render(&other, "42").is_none());
+        assert!(BodyTemplates::parse(r#"{"templates": []}"#).is_err());
+    }
+
+    #[test]
+    fn render_picks_the_most_specific_template() {
+        let templates = BodyTemplates::parse(
+            r#"{"templates": {
+                "PUT /users/{id}": {"scope": "user"},
+                "PUT /orgs/{orgId}/users/{id}": {"scope": "org"}
+            }}"#,
+        )
+        .unwrap();
+        let nested = Endpoint::new(
+            crate::models::Method::PUT,
+            "/orgs/{orgId}/users/{id}".to_string(),
+            None,
+            vec![],
+        );
+        // Both keys match the nested path; the longer one names it exactly
+        assert_eq!(
+            templates.render(&nested, "42"),
+            Some(json!({"scope": "org"}))
+        );
+    }
 }
@@ -1,29 +1,111 @@
 // Bruno collection parser for Doppel
-// Uses walkdir and regex to extract endpoints from .bru files
+// Uses walkdir and regex to extract endpoints from .bru files, resolving
+// request URLs against the collection's bruno.json and folder settings
 
-use crate::models::{CollectionParser, Endpoint, Method};
+use super::{add_url_parameters, normalize_path_variables};
+use crate::models::{CollectionParser, Endpoint, Method, Parameter, ParameterLocation, ParseError};
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
 use walkdir::WalkDir;
 
 lazy_static! {
     static ref METHOD_REGEX: Regex =
-        Regex::new(r#"method"\s*:\s*"(GET|POST|PUT|DELETE|PATCH|OPTIONS|HEAD)"#)
+        Regex::new(r#"method"\s*:\s*"([^"]+)"#)
             .expect("Failed to compile METHOD_REGEX");
     static ref URL_REGEX: Regex =
         Regex::new(r#"url"\s*:\s*"([^"]+)"#).expect("Failed to compile URL_REGEX");
+    // `body:json { ... }` block, closed by a `}` at the start of a line
+    static ref BODY_JSON_REGEX: Regex = Regex::new(r"(?ms)^body:json\s*\{\s*$(.*?)^\}")
+        .expect("Failed to compile BODY_JSON_REGEX");
+    // `vars { ... }` / `vars:pre-request { ... }` block of folder.bru or collection.bru
+    static ref VARS_REGEX: Regex = Regex::new(r"(?ms)^vars(?::pre-request)?\s*\{\s*$(.*?)^\}")
+        .expect("Failed to compile VARS_REGEX");
+}
+
+/// Defaults a request inherits from the folders and collection around it
+#[derive(Debug, Default)]
+struct CollectionSettings {
+    /// `presets.requestUrl` of bruno.json, prefixed to relative request URLs
+    base_url: Option<String>,
+    /// Variables from folder.bru and collection.bru; the nearest definition wins
+    vars: HashMap<String, String>,
+}
+
+impl CollectionSettings {
+    /// Walk up from a request file to the nearest bruno.json, collecting the
+    /// settings of every folder on the way
+    fn for_request(file: &Path) -> Self {
+        let mut settings = Self::default();
+        for dir in file.ancestors().skip(1) {
+            for settings_file in ["folder.bru", "collection.bru"] {
+                if let Ok(content) = std::fs::read_to_string(dir.join(settings_file)) {
+                    for (name, value) in parse_vars(&content) {
+                        settings.vars.entry(name).or_insert(value);
+                    }
+                }
+            }
+            if let Ok(content) = std::fs::read_to_string(dir.join("bruno.json")) {
+                settings.base_url = serde_json::from_str::<serde_json::Value>(&content)
+                    .ok()
+                    .and_then(|config| {
+                        config
+                            .pointer("/presets/requestUrl")
+                            .and_then(|u| u.as_str())
+                            .map(|u| u.trim().to_string())
+                    })
+                    .filter(|u| !u.is_empty());
+                break;
+            }
+        }
+        settings
+    }
+
+    /// Substitute known `{{var}}`s and anchor a relative URL at the collection base
+    fn resolve(&self, url: &str) -> String {
+        let mut resolved = url.to_string();
+        for (name, value) in &self.vars {
+            resolved = resolved.replace(&format!("{{{{{}}}}}", name), value);
+        }
+        match &self.base_url {
+            Some(base) if !resolved.contains("://") && !resolved.starts_with("{{") => format!(
+                "{}/{}",
+                base.trim_end_matches('/'),
+                resolved.trim_start_matches('/')
+            ),
+            _ => resolved,
+        }
+    }
+}
+
+/// Enabled `name: value` pairs of a settings file's vars block
+fn parse_vars(content: &str) -> Vec<(String, String)> {
+    let Some(block) = VARS_REGEX.captures(content).and_then(|cap| cap.get(1)) else {
+        return Vec::new();
+    };
+    block
+        .as_str()
+        .lines()
+        .filter_map(|line| line.trim().split_once(':'))
+        // A leading `~` marks a disabled variable
+        .filter(|(name, _)| !name.trim().is_empty() && !name.trim().starts_with('~'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect()
 }
 
 pub struct BrunoParser;
 
 impl CollectionParser for BrunoParser {
-    fn parse(&self, dir_path: &str) -> Result<Vec<Endpoint>, String> {
+    fn parse(&self, dir_path: &str) -> Result<Vec<Endpoint>, ParseError> {
+        // WalkDir only reports a missing root as an entry error
+        std::fs::read_dir(dir_path).map_err(|e| ParseError::io(dir_path, e))?;
         let mut endpoints = Vec::new();
 
         for entry in WalkDir::new(dir_path).into_iter().filter_map(|e| e.ok()) {
             if entry.path().extension().is_some_and(|ext| ext == "bru") {
                 let content = std::fs::read_to_string(entry.path())
-                    .map_err(|e| format!("Failed to read {:?}: {}", entry.path(), e))?;
+                    .map_err(|e| ParseError::io(entry.path().display().to_string(), e))?;
                 let method = METHOD_REGEX
                     .captures(&content)
                     .and_then(|cap| cap.get(1))
@@ -33,20 +115,39 @@ impl CollectionParser for BrunoParser {
                     .and_then(|cap| cap.get(1))
                     .map(|u| u.as_str().to_string());
                 if let (Some(method), Some(url)) = (method, url) {
-                    let method = match method.as_str() {
-                        "GET" => Method::GET,
-                        "POST" => Method::POST,
-                        "PUT" => Method::PUT,
-                        "DELETE" => Method::DELETE,
-                        "PATCH" => Method::PATCH,
-                        "OPTIONS" => Method::OPTIONS,
-                        "HEAD" => Method::HEAD,
-                        _ => continue,
+                    let Some(method) = Method::parse(&method) else {
+                        continue;
                     };
-                    endpoints.push(Endpoint::new(method, url, None, vec![]));
+                    let url = CollectionSettings::for_request(entry.path()).resolve(&url);
+                    let url = normalize_path_variables(&url);
+                    let mut endpoint = Endpoint::new(method, url, None, vec![]);
+                    add_url_parameters(&mut endpoint);
+                    endpoint.example_body = BODY_JSON_REGEX
+                        .captures(&content)
+                        .and_then(|cap| cap.get(1))
+                        .and_then(|b| {
+                            serde_json::from_str::<serde_json::Value>(b.as_str().trim()).ok()
+                        });
+                    // Top-level body fields become "body.<field>" parameters
+                    let fields = endpoint
+                        .example_body
+                        .as_ref()
+                        .and_then(|b| b.as_object())
+                        .map(|o| o.keys().cloned().collect::<Vec<_>>())
+                        .unwrap_or_default();
+                    for field in fields {
+                        endpoint.add_parameter(Parameter::new(
+                            format!("body.{}", field),
+                            ParameterLocation::Body,
+                        ));
+                    }
+                    endpoints.push(endpoint);
                 }
             }
         }
+        if endpoints.is_empty() {
+            return Err(ParseError::EmptyCollection);
+        }
         Ok(endpoints)
     }
 }
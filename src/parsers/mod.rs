@@ -5,3 +5,169 @@ pub mod postman;
 pub use bruno::BrunoParser;
 pub use openapi::OpenApiParser;
 pub use postman::PostmanParser;
+
+use crate::models::{Endpoint, Parameter, ParameterLocation, ParseError};
+use serde_json::Value;
+
+/// Parse a collection or spec as JSON. Hand-maintained files often carry
+/// `//` or `/* */` comments and trailing commas, which strict JSON rejects;
+/// those are stripped and parsing retried, with a warning. The error of the
+/// strict parse is returned when the lenient one fails too, so its line and
+/// column point into the original file.
+pub(crate) fn parse_json(content: &str) -> Result<Value, ParseError> {
+    let strict_error = match serde_json::from_str(content) {
+        Ok(json) => return Ok(json),
+        Err(e) => e,
+    };
+    let stripped = strip_json_extensions(content);
+    match serde_json::from_str(&stripped) {
+        Ok(json) if stripped != content => {
+            eprintln!("[WARN] Input is not strict JSON (comments or trailing commas); parsed it leniently");
+            Ok(json)
+        }
+        _ => Err(strict_error.into()),
+    }
+}
+
+/// Remove comments and trailing commas outside of strings. Newlines are kept
+/// so line numbers still match the original.
+fn strip_json_extensions(content: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let mut out = String::with_capacity(content.len());
+    let mut in_string = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            out.push(c);
+            if c == '\\' {
+                if let Some(&next) = chars.get(i + 1) {
+                    out.push(next);
+                    i += 1;
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+            i += 1;
+            continue;
+        }
+        match (c, chars.get(i + 1)) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+                i += 1;
+            }
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            ('/', Some('*')) => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    if chars[i] == '\n' {
+                        out.push('\n');
+                    }
+                    i += 1;
+                }
+                i += 2;
+            }
+            (',', _) if next_token(&chars, i + 1).is_some_and(|t| t == '}' || t == ']') => {
+                i += 1;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// First character after `from` that is neither whitespace nor in a comment
+fn next_token(chars: &[char], mut from: usize) -> Option<char> {
+    loop {
+        match (chars.get(from)?, chars.get(from + 1)) {
+            (c, _) if c.is_whitespace() => from += 1,
+            ('/', Some('/')) => {
+                while chars.get(from).is_some_and(|c| *c != '\n') {
+                    from += 1;
+                }
+            }
+            ('/', Some('*')) => {
+                from += 2;
+                while chars.get(from).is_some()
+                    && !(chars[from] == '*' && chars.get(from + 1) == Some(&'/'))
+                {
+                    from += 1;
+                }
+                from += 2;
+            }
+            (c, _) => return Some(*c),
+        }
+    }
+}
+
+/// Rewrite `:name` path segments (Postman/Bruno style) to `{name}` templates
+pub(crate) fn normalize_path_variables(url: &str) -> String {
+    let (path, query) = match url.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (url, None),
+    };
+    let path = path
+        .split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(name) if !name.is_empty() => format!("{{{}}}", name),
+            _ => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    match query {
+        Some(query) => format!("{}?{}", path, query),
+        None => path,
+    }
+}
+
+/// Structured parameters visible in a URL: `{name}` path templates and query keys.
+/// Collection variables (`{{var}}`) are not parameters. Query parameters are
+/// moved out of the path into the endpoint's structured parameters, keeping
+/// their literal values as examples so they are still sent. A key repeated in
+/// the query (`?id=1&id=2`) keeps all its values, in order, as an array, and
+/// the order of the pairs is kept in `query_order`.
+pub(crate) fn add_url_parameters(endpoint: &mut Endpoint) {
+    let (path, query) = match endpoint.path.split_once('?') {
+        Some((path, query)) => (path.to_string(), query.to_string()),
+        None => (endpoint.path.clone(), String::new()),
+    };
+    for segment in path.split('/') {
+        if segment.starts_with("{{") {
+            continue;
+        }
+        if let Some(name) = segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            endpoint.add_parameter(Parameter::new(name, ParameterLocation::Path));
+        }
+    }
+    // Keys and values are percent-decoded; reqwest re-encodes them when sending
+    let mut values: Vec<(String, Vec<Value>)> = Vec::new();
+    for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+        if key.is_empty() {
+            continue;
+        }
+        endpoint.query_order.push(key.to_string());
+        let value = Value::String(value.into_owned());
+        match values.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => existing.push(value),
+            None => values.push((key.into_owned(), vec![value])),
+        }
+    }
+    for (key, mut key_values) in values {
+        endpoint.add_parameter(Parameter::new(&key, ParameterLocation::Query));
+        let example = if key_values.len() == 1 {
+            key_values.remove(0)
+        } else {
+            Value::Array(key_values)
+        };
+        endpoint.example_values.entry(key).or_insert(example);
+    }
+    endpoint.path = path;
+}
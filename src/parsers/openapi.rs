@@ -1,10 +1,21 @@
 // OpenAPI/Swagger parser for Doppel
 // Uses serde_json to parse openapi.json files
 
-use crate::models::{CollectionParser, Endpoint, Method};
+use super::parse_json;
+use crate::models::{
+    ArrayStyle, CollectionParser, Endpoint, Method, Parameter, ParameterLocation, ParseError,
+    SecurityKind,
+};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Parsed external $ref documents, shared by the threads parsing a spec
+type ExternalCache = Mutex<HashMap<PathBuf, Value>>;
+
+/// Specs with at least this many paths are parsed on several threads
+const PARALLEL_PATH_THRESHOLD: usize = 256;
 
 pub struct OpenApiParser;
 
@@ -18,11 +29,15 @@ impl OpenApiParser {
         root: &Value,
         ref_str: &str,
         base_path: Option<&Path>,
-        external_cache: &mut HashMap<PathBuf, Value>,
+        external_cache: &ExternalCache,
     ) -> Option<Value> {
         // Check for external file reference
         if let Some((file_part, pointer_part)) = ref_str.split_once('#') {
             if !file_part.is_empty() {
+                if base_path.is_none() {
+                    OpenApiParser::warn_external_ref_skipped(ref_str);
+                    return None;
+                }
                 // External file reference
                 return OpenApiParser::resolve_external_ref(
                     file_part,
@@ -38,12 +53,24 @@ impl OpenApiParser {
 
         // No '#' found, treat as external file without pointer
         if !ref_str.starts_with("#") {
+            if base_path.is_none() {
+                OpenApiParser::warn_external_ref_skipped(ref_str);
+                return None;
+            }
             return OpenApiParser::resolve_external_ref(ref_str, "", base_path, external_cache);
         }
 
         None
     }
 
+    // Specs fetched over HTTP have no local directory, so external file refs can't be resolved
+    fn warn_external_ref_skipped(ref_str: &str) {
+        eprintln!(
+            "Warning: Skipping external reference '{}' in remote spec (only local refs are resolved)",
+            ref_str
+        );
+    }
+
     // Resolve local JSON Pointer refs like "#/components/schemas/Foo"
     fn resolve_local_ref<'a>(root: &'a Value, pointer: &str) -> Option<&'a Value> {
         if !pointer.starts_with("/") {
@@ -69,7 +96,7 @@ impl OpenApiParser {
         file_path: &str,
         pointer: &str,
         base_path: Option<&Path>,
-        cache: &mut HashMap<PathBuf, Value>,
+        cache: &ExternalCache,
     ) -> Option<Value> {
         // Resolve relative path
         let resolved_path = if let Some(base) = base_path {
@@ -94,11 +121,13 @@ impl OpenApiParser {
             }
         }
 
-        // Check cache first
+        // Check cache first. The lock is held while loading so threads
+        // resolving into the same file read it once.
+        let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
         if !cache.contains_key(&canonical_path) {
             // Load external file
             let data = std::fs::read_to_string(&canonical_path).ok()?;
-            let json: Value = serde_json::from_str(&data).ok()?;
+            let json = parse_json(&data).ok()?;
             cache.insert(canonical_path.clone(), json);
         }
 
@@ -113,6 +142,172 @@ impl OpenApiParser {
         OpenApiParser::resolve_local_ref(external_doc, pointer).cloned()
     }
 
+    // Pick a plausible value for a schema: example, then default, then first enum/examples entry
+    fn example_value(schema: &Value) -> Option<Value> {
+        schema
+            .get("example")
+            .or_else(|| schema.get("default"))
+            .or_else(|| schema.get("enum").and_then(|e| e.get(0)))
+            .or_else(|| schema.get("examples").and_then(|e| e.get(0)))
+            .cloned()
+    }
+
+    // Credential kinds a `security` requirement list accepts. Each entry is an
+    // alternative; `[]` (or only `{}`, anonymous) yields an empty list.
+    fn security_kinds(json: &Value, requirements: &Value) -> Option<Vec<SecurityKind>> {
+        let requirements = requirements.as_array()?;
+        // OpenAPI 3 components, or Swagger 2 securityDefinitions
+        let schemes = json
+            .get("components")
+            .and_then(|c| c.get("securitySchemes"))
+            .or_else(|| json.get("securityDefinitions"));
+        let mut kinds = Vec::new();
+        for name in requirements
+            .iter()
+            .filter_map(|r| r.as_object())
+            .flat_map(|r| r.keys())
+        {
+            let scheme = schemes.and_then(|s| s.get(name));
+            let kind = match scheme.and_then(|s| s.get("type")).and_then(|t| t.as_str()) {
+                Some("http") => {
+                    let http = scheme
+                        .and_then(|s| s.get("scheme"))
+                        .and_then(|s| s.as_str())
+                        .unwrap_or_default();
+                    if http.eq_ignore_ascii_case("bearer") {
+                        SecurityKind::Bearer
+                    } else if http.eq_ignore_ascii_case("basic") {
+                        SecurityKind::Basic
+                    } else {
+                        SecurityKind::Other
+                    }
+                }
+                Some("basic") => SecurityKind::Basic,
+                Some("apiKey") => SecurityKind::ApiKey,
+                Some("oauth2") => SecurityKind::OAuth2,
+                Some("openIdConnect") => SecurityKind::OpenIdConnect,
+                _ => SecurityKind::Other,
+            };
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+        Some(kinds)
+    }
+
+    // Whole-body example of a media type object: `example`, else the first of
+    // `examples`, else an inline schema's example
+    fn media_example(media_type: &Value) -> Option<Value> {
+        media_type
+            .get("example")
+            .or_else(|| {
+                media_type
+                    .get("examples")
+                    .and_then(|e| e.as_object())
+                    .and_then(|e| e.values().next())
+                    .and_then(|e| e.get("value"))
+            })
+            .or_else(|| media_type.get("schema").and_then(|s| s.get("example")))
+            .cloned()
+    }
+
+    // Example for a parameter object: its own example first, then its schema's
+    fn parameter_example(param: &Value) -> Option<Value> {
+        param
+            .get("example")
+            .cloned()
+            .or_else(|| param.get("schema").and_then(OpenApiParser::example_value))
+            .or_else(|| {
+                let (_, media_type) = OpenApiParser::parameter_content(param)?;
+                OpenApiParser::media_example(media_type).or_else(|| {
+                    media_type
+                        .get("schema")
+                        .and_then(OpenApiParser::example_value)
+                })
+            })
+    }
+
+    // The single media type of a parameter declared with `content` instead of `schema`
+    fn parameter_content(param: &Value) -> Option<(&String, &Value)> {
+        param.get("content")?.as_object()?.iter().next()
+    }
+
+    // Structured form of a parameter object: location from `in`, plus required flag and type
+    fn structured_parameter(param: &Value) -> Option<Parameter> {
+        let name = param.get("name")?.as_str()?;
+        let location = match param.get("in").and_then(|i| i.as_str()) {
+            Some("path") => ParameterLocation::Path,
+            Some("header") => ParameterLocation::Header,
+            Some("cookie") => ParameterLocation::Cookie,
+            _ => ParameterLocation::Query,
+        };
+        let content = OpenApiParser::parameter_content(param);
+        let schema_type = param
+            .get("schema")
+            .or_else(|| content.and_then(|(_, media_type)| media_type.get("schema")))
+            .and_then(|s| s.get("type"))
+            .and_then(|t| t.as_str())
+            .map(|t| t.to_string());
+        // A `content` parameter is serialized whole by its media type
+        let array_style = if content.is_none()
+            && location == ParameterLocation::Query
+            && schema_type.as_deref() == Some("array")
+        {
+            Some(OpenApiParser::array_style(param))
+        } else {
+            None
+        };
+        Some(Parameter {
+            name: name.to_string(),
+            // Path parameters are always required per the spec
+            required: location == ParameterLocation::Path
+                || param.get("required").and_then(|r| r.as_bool()) == Some(true),
+            location,
+            schema_type,
+            array_style,
+            content_type: content.map(|(media_type, _)| media_type.clone()),
+        })
+    }
+
+    // Serialization of an array query parameter from `style`/`explode`;
+    // the default form style explodes into repeated keys
+    fn array_style(param: &Value) -> ArrayStyle {
+        let style = param
+            .get("style")
+            .and_then(|s| s.as_str())
+            .unwrap_or("form");
+        let explode = param
+            .get("explode")
+            .and_then(|e| e.as_bool())
+            .unwrap_or(style == "form");
+        if explode {
+            return ArrayStyle::Repeated;
+        }
+        match style {
+            "spaceDelimited" => ArrayStyle::Delimited(' '),
+            "pipeDelimited" => ArrayStyle::Delimited('|'),
+            _ => ArrayStyle::Delimited(','),
+        }
+    }
+
+    // Record a structured parameter unless one with the same name already exists
+    fn push_structured(structured: &mut Vec<Parameter>, param: Option<Parameter>) {
+        if let Some(param) = param {
+            if !structured.iter().any(|p| p.name == param.name) {
+                structured.push(param);
+            }
+        }
+    }
+
+    // servers[0].url of a spec, path item or operation, with variables substituted
+    fn first_server(node: &Value) -> Option<String> {
+        node.get("servers")
+            .and_then(|s| s.as_array())
+            .and_then(|arr| arr.first())
+            .and_then(OpenApiParser::server_with_vars)
+            .map(|s| s.trim_end_matches('/').to_string())
+    }
+
     // If server URL contains variables like {env}, replace with defaults when available
     fn server_with_vars(server: &Value) -> Option<String> {
         let url = server.get("url")?.as_str()?;
@@ -129,261 +324,378 @@ impl OpenApiParser {
 }
 
 impl CollectionParser for OpenApiParser {
-    fn parse(&self, file_path: &str) -> Result<Vec<Endpoint>, String> {
-        let data = std::fs::read_to_string(file_path)
-            .map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
-        let json: Value =
-            serde_json::from_str(&data).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    fn parse(&self, file_path: &str) -> Result<Vec<Endpoint>, ParseError> {
+        let data = std::fs::read_to_string(file_path).map_err(|e| ParseError::io(file_path, e))?;
+        let json = parse_json(&data)?;
+        OpenApiParser::parse_spec(&json, Some(Path::new(file_path)))
+    }
+
+    fn parse_content(&self, content: &str) -> Result<Vec<Endpoint>, ParseError> {
+        let json = parse_json(content)?;
+        OpenApiParser::parse_spec(&json, None)
+    }
+}
+
+impl OpenApiParser {
+    // Extract endpoints from a parsed spec. `spec_path` locates the spec on disk for
+    // external $ref resolution; None (remote specs) skips external refs.
+    fn parse_spec(json: &Value, spec_path: Option<&Path>) -> Result<Vec<Endpoint>, ParseError> {
+        if !json.get("paths").is_some_and(Value::is_object) {
+            return Err(ParseError::UnsupportedFormat(
+                "Not an OpenAPI/Swagger spec: no paths object".to_string(),
+            ));
+        }
         let mut endpoints = Vec::new();
-        let mut external_cache: HashMap<PathBuf, Value> = HashMap::new();
-        let spec_file_path = Path::new(file_path);
+        let external_cache = ExternalCache::default();
 
-        // Prefer servers[0].url and substitute variables if present
-        let base_url = json
-            .get("servers")
-            .and_then(|s| s.as_array())
-            .and_then(|arr| arr.first())
-            .and_then(OpenApiParser::server_with_vars)
-            .map(|s| s.trim_end_matches('/').to_string());
-
-        if let Some(paths) = json.get("paths") {
-            if let Some(map) = paths.as_object() {
-                for (path, methods) in map {
-                    if let Some(methods_map) = methods.as_object() {
-                        for (method, details) in methods_map {
-                            let method_enum = match method.to_uppercase().as_str() {
-                                "GET" => Method::GET,
-                                "POST" => Method::POST,
-                                "PUT" => Method::PUT,
-                                "DELETE" => Method::DELETE,
-                                "PATCH" => Method::PATCH,
-                                "OPTIONS" => Method::OPTIONS,
-                                "HEAD" => Method::HEAD,
-                                _ => continue,
-                            };
-
-                            let mut params = Vec::new();
-
-                            // collect parameters (may be local or $ref)
-                            if let Some(parameters) = details.get("parameters") {
-                                if let Some(arr) = parameters.as_array() {
-                                    for p in arr {
-                                        if let Some(r) = p.get("$ref").and_then(|r| r.as_str()) {
-                                            if let Some(resolved) = OpenApiParser::resolve_ref(
-                                                &json,
-                                                r,
-                                                Some(spec_file_path),
-                                                &mut external_cache,
-                                            ) {
-                                                if let Some(name) =
-                                                    resolved.get("name").and_then(|n| n.as_str())
-                                                {
-                                                    params.push(name.to_string());
-                                                }
-                                            }
-                                        } else if let Some(name) =
-                                            p.get("name").and_then(|n| n.as_str())
-                                        {
-                                            params.push(name.to_string());
-                                        }
+        let base_url = OpenApiParser::first_server(json);
+
+        let paths: Vec<(&String, &Value)> = json
+            .get("paths")
+            .and_then(Value::as_object)
+            .map(|map| map.iter().collect())
+            .unwrap_or_default();
+        let parse_chunk = |chunk: &[(&String, &Value)]| -> Vec<Endpoint> {
+            chunk
+                .iter()
+                .flat_map(|(path, methods)| {
+                    OpenApiParser::parse_path_item(
+                        json,
+                        path,
+                        methods,
+                        base_url.as_deref(),
+                        spec_path,
+                        &external_cache,
+                    )
+                })
+                .collect()
+        };
+        // Large specs parse their paths on worker threads. Chunks are joined
+        // back in order, so endpoints come out in the same path/method order
+        // as a sequential parse.
+        let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+        if workers > 1 && paths.len() >= PARALLEL_PATH_THRESHOLD {
+            let chunk_size = paths.len().div_ceil(workers);
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = paths
+                    .chunks(chunk_size)
+                    .map(|chunk| scope.spawn(|| parse_chunk(chunk)))
+                    .collect();
+                for handle in handles {
+                    endpoints.extend(handle.join().expect("OpenAPI parser thread panicked"));
+                }
+            });
+        } else {
+            endpoints = parse_chunk(&paths);
+        }
+        if endpoints.is_empty() {
+            return Err(ParseError::EmptyCollection);
+        }
+        Ok(endpoints)
+    }
+
+    // Endpoints for every operation of one path item
+    fn parse_path_item(
+        json: &Value,
+        path: &str,
+        methods: &Value,
+        base_url: Option<&str>,
+        spec_path: Option<&Path>,
+        external_cache: &ExternalCache,
+    ) -> Vec<Endpoint> {
+        let mut endpoints = Vec::new();
+        let Some(methods_map) = methods.as_object() else {
+            return endpoints;
+        };
+        for (method, details) in methods_map {
+            // Other keys of a path item (parameters, summary,
+            // servers, ...) are not operations
+            let method_enum = match method.to_uppercase().as_str() {
+                "GET" => Method::GET,
+                "POST" => Method::POST,
+                "PUT" => Method::PUT,
+                "DELETE" => Method::DELETE,
+                "PATCH" => Method::PATCH,
+                "OPTIONS" => Method::OPTIONS,
+                "HEAD" => Method::HEAD,
+                "TRACE" => Method::TRACE,
+                _ => continue,
+            };
+
+            let mut params = Vec::new();
+            let mut examples: HashMap<String, Value> = HashMap::new();
+            let mut example_body = None;
+            let mut structured: Vec<Parameter> = Vec::new();
+
+            // collect parameters (may be local or $ref)
+            if let Some(parameters) = details.get("parameters") {
+                if let Some(arr) = parameters.as_array() {
+                    for p in arr {
+                        if let Some(r) = p.get("$ref").and_then(|r| r.as_str()) {
+                            if let Some(resolved) =
+                                OpenApiParser::resolve_ref(json, r, spec_path, external_cache)
+                            {
+                                if let Some(name) = resolved.get("name").and_then(|n| n.as_str()) {
+                                    params.push(name.to_string());
+                                    OpenApiParser::push_structured(
+                                        &mut structured,
+                                        OpenApiParser::structured_parameter(&resolved),
+                                    );
+                                    if let Some(ex) = OpenApiParser::parameter_example(&resolved) {
+                                        examples.insert(name.to_string(), ex);
                                     }
                                 }
                             }
+                        } else if let Some(name) = p.get("name").and_then(|n| n.as_str()) {
+                            params.push(name.to_string());
+                            OpenApiParser::push_structured(
+                                &mut structured,
+                                OpenApiParser::structured_parameter(p),
+                            );
+                            if let Some(ex) = OpenApiParser::parameter_example(p) {
+                                examples.insert(name.to_string(), ex);
+                            }
+                        }
+                    }
+                }
+            }
 
-                            // path-level parameters
-                            if let Some(path_obj) = map.get(path) {
-                                if let Some(path_params) = path_obj.get("parameters") {
-                                    if let Some(arr) = path_params.as_array() {
-                                        for p in arr {
-                                            if let Some(r) = p.get("$ref").and_then(|r| r.as_str())
-                                            {
-                                                if let Some(resolved) = OpenApiParser::resolve_ref(
-                                                    &json,
-                                                    r,
-                                                    Some(spec_file_path),
-                                                    &mut external_cache,
-                                                ) {
-                                                    if let Some(name) = resolved
-                                                        .get("name")
-                                                        .and_then(|n| n.as_str())
-                                                    {
-                                                        if !params.contains(&name.to_string()) {
-                                                            params.push(name.to_string());
-                                                        }
-                                                    }
-                                                }
-                                            } else if let Some(name) =
-                                                p.get("name").and_then(|n| n.as_str())
-                                            {
-                                                if !params.contains(&name.to_string()) {
-                                                    params.push(name.to_string());
-                                                }
-                                            }
-                                        }
+            // path-level parameters
+            if let Some(path_params) = methods.get("parameters") {
+                if let Some(arr) = path_params.as_array() {
+                    for p in arr {
+                        if let Some(r) = p.get("$ref").and_then(|r| r.as_str()) {
+                            if let Some(resolved) =
+                                OpenApiParser::resolve_ref(json, r, spec_path, external_cache)
+                            {
+                                if let Some(name) = resolved.get("name").and_then(|n| n.as_str()) {
+                                    if !params.contains(&name.to_string()) {
+                                        params.push(name.to_string());
+                                    }
+                                    OpenApiParser::push_structured(
+                                        &mut structured,
+                                        OpenApiParser::structured_parameter(&resolved),
+                                    );
+                                    if let Some(ex) = OpenApiParser::parameter_example(&resolved) {
+                                        examples.entry(name.to_string()).or_insert(ex);
                                     }
                                 }
                             }
+                        } else if let Some(name) = p.get("name").and_then(|n| n.as_str()) {
+                            if !params.contains(&name.to_string()) {
+                                params.push(name.to_string());
+                            }
+                            OpenApiParser::push_structured(
+                                &mut structured,
+                                OpenApiParser::structured_parameter(p),
+                            );
+                            if let Some(ex) = OpenApiParser::parameter_example(p) {
+                                examples.entry(name.to_string()).or_insert(ex);
+                            }
+                        }
+                    }
+                }
+            }
+
+            // handle requestBody with support for multiple content types
+            if let Some(rb) = details.get("requestBody") {
+                // if it's a $ref, resolve it
+                let rb_obj = if let Some(r) = rb.get("$ref").and_then(|r| r.as_str()) {
+                    OpenApiParser::resolve_ref(json, r, spec_path, external_cache)
+                        .unwrap_or_else(|| rb.clone())
+                } else {
+                    rb.clone()
+                };
+                if let Some(content) = rb_obj.get("content") {
+                    // Support multiple content types
+                    let content_types = vec![
+                        "application/json",
+                        "application/x-www-form-urlencoded",
+                        "multipart/form-data",
+                        "application/xml",
+                        "text/plain",
+                    ];
+
+                    for content_type in content_types {
+                        if let Some(media_type_obj) = content.get(content_type) {
+                            if content_type == "application/json" {
+                                example_body = OpenApiParser::media_example(media_type_obj);
+                            }
+                            if let Some(schema) = media_type_obj.get("schema") {
+                                // if schema is a $ref, resolve
+                                let schema_obj = if let Some(r) =
+                                    schema.get("$ref").and_then(|r| r.as_str())
+                                {
+                                    OpenApiParser::resolve_ref(json, r, spec_path, external_cache)
+                                        .unwrap_or_else(|| schema.clone())
+                                } else {
+                                    schema.clone()
+                                };
+
+                                // Handle oneOf/allOf/anyOf
+                                let schemas_to_process = if let Some(one_of) =
+                                    schema_obj.get("oneOf").and_then(|v| v.as_array())
+                                {
+                                    one_of.iter().collect::<Vec<_>>()
+                                } else if let Some(all_of) =
+                                    schema_obj.get("allOf").and_then(|v| v.as_array())
+                                {
+                                    all_of.iter().collect::<Vec<_>>()
+                                } else if let Some(any_of) =
+                                    schema_obj.get("anyOf").and_then(|v| v.as_array())
+                                {
+                                    any_of.iter().collect::<Vec<_>>()
+                                } else {
+                                    vec![&schema_obj]
+                                };
 
-                            // handle requestBody with support for multiple content types
-                            if let Some(rb) = details.get("requestBody") {
-                                // if it's a $ref, resolve it
-                                let rb_obj =
-                                    if let Some(r) = rb.get("$ref").and_then(|r| r.as_str()) {
+                                for sub_schema in schemas_to_process {
+                                    // Resolve nested $ref
+                                    let resolved_schema = if let Some(r) =
+                                        sub_schema.get("$ref").and_then(|r| r.as_str())
+                                    {
                                         OpenApiParser::resolve_ref(
-                                            &json,
+                                            json,
                                             r,
-                                            Some(spec_file_path),
-                                            &mut external_cache,
+                                            spec_path,
+                                            external_cache,
                                         )
-                                        .unwrap_or_else(|| rb.clone())
+                                        .unwrap_or_else(|| sub_schema.clone())
                                     } else {
-                                        rb.clone()
+                                        sub_schema.clone()
                                     };
-                                if let Some(content) = rb_obj.get("content") {
-                                    // Support multiple content types
-                                    let content_types = vec![
-                                        "application/json",
-                                        "application/x-www-form-urlencoded",
-                                        "multipart/form-data",
-                                        "application/xml",
-                                        "text/plain",
-                                    ];
-
-                                    for content_type in content_types {
-                                        if let Some(media_type_obj) = content.get(content_type) {
-                                            if let Some(schema) = media_type_obj.get("schema") {
-                                                // if schema is a $ref, resolve
-                                                let schema_obj = if let Some(r) =
-                                                    schema.get("$ref").and_then(|r| r.as_str())
+
+                                    // Names listed in the schema's `required` array
+                                    let required_props: Vec<&str> = resolved_schema
+                                        .get("required")
+                                        .and_then(|r| r.as_array())
+                                        .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
+                                        .unwrap_or_default();
+
+                                    if let Some(props) = resolved_schema.get("properties") {
+                                        if let Some(map_props) = props.as_object() {
+                                            for (pname, prop_val) in map_props {
+                                                // Handle nested schemas
+                                                let param_name = format!("body.{}", pname);
+                                                if !params.contains(&param_name) {
+                                                    params.push(param_name.clone());
+                                                }
+                                                OpenApiParser::push_structured(
+                                                    &mut structured,
+                                                    Some(Parameter {
+                                                        name: param_name.clone(),
+                                                        location: ParameterLocation::Body,
+                                                        required: required_props
+                                                            .contains(&pname.as_str()),
+                                                        schema_type: prop_val
+                                                            .get("type")
+                                                            .and_then(|t| t.as_str())
+                                                            .map(|t| t.to_string()),
+                                                        array_style: None,
+                                                        content_type: None,
+                                                    }),
+                                                );
+
+                                                // Seed value for non-target fields
+                                                let prop_schema = match prop_val
+                                                    .get("$ref")
+                                                    .and_then(|r| r.as_str())
                                                 {
-                                                    OpenApiParser::resolve_ref(
-                                                        &json,
+                                                    Some(r) => OpenApiParser::resolve_ref(
+                                                        json,
                                                         r,
-                                                        Some(spec_file_path),
-                                                        &mut external_cache,
+                                                        spec_path,
+                                                        external_cache,
                                                     )
-                                                    .unwrap_or_else(|| schema.clone())
-                                                } else {
-                                                    schema.clone()
+                                                    .unwrap_or_else(|| prop_val.clone()),
+                                                    None => prop_val.clone(),
                                                 };
-
-                                                // Handle oneOf/allOf/anyOf
-                                                let schemas_to_process = if let Some(one_of) =
-                                                    schema_obj
-                                                        .get("oneOf")
-                                                        .and_then(|v| v.as_array())
-                                                {
-                                                    one_of.iter().collect::<Vec<_>>()
-                                                } else if let Some(all_of) = schema_obj
-                                                    .get("allOf")
-                                                    .and_then(|v| v.as_array())
-                                                {
-                                                    all_of.iter().collect::<Vec<_>>()
-                                                } else if let Some(any_of) = schema_obj
-                                                    .get("anyOf")
-                                                    .and_then(|v| v.as_array())
+                                                if let Some(ex) =
+                                                    OpenApiParser::example_value(&prop_schema)
                                                 {
-                                                    any_of.iter().collect::<Vec<_>>()
-                                                } else {
-                                                    vec![&schema_obj]
-                                                };
+                                                    examples.entry(param_name).or_insert(ex);
+                                                }
 
-                                                for sub_schema in schemas_to_process {
-                                                    // Resolve nested $ref
-                                                    let resolved_schema = if let Some(r) =
-                                                        sub_schema
-                                                            .get("$ref")
-                                                            .and_then(|r| r.as_str())
-                                                    {
-                                                        OpenApiParser::resolve_ref(
-                                                            &json,
-                                                            r,
-                                                            Some(spec_file_path),
-                                                            &mut external_cache,
-                                                        )
-                                                        .unwrap_or_else(|| sub_schema.clone())
-                                                    } else {
-                                                        sub_schema.clone()
-                                                    };
-
-                                                    if let Some(props) =
-                                                        resolved_schema.get("properties")
-                                                    {
-                                                        if let Some(map_props) = props.as_object() {
-                                                            for (pname, prop_val) in map_props {
-                                                                // Handle nested schemas
-                                                                let param_name =
-                                                                    format!("body.{}", pname);
-                                                                if !params.contains(&param_name) {
-                                                                    params.push(param_name);
-                                                                }
-
-                                                                // Handle array types
-                                                                if let Some(prop_type) = prop_val
-                                                                    .get("type")
-                                                                    .and_then(|t| t.as_str())
-                                                                {
-                                                                    if prop_type == "array" {
-                                                                        let array_param = format!(
-                                                                            "body.{}[0]",
-                                                                            pname
-                                                                        );
-                                                                        if !params
-                                                                            .contains(&array_param)
-                                                                        {
-                                                                            params
-                                                                                .push(array_param);
-                                                                        }
-                                                                    }
-                                                                }
-                                                            }
-                                                        }
-                                                    } else if resolved_schema
-                                                        .get("type")
-                                                        .and_then(|t| t.as_str())
-                                                        == Some("array")
-                                                    {
-                                                        // Handle array body
-                                                        if !params
-                                                            .contains(&"__body__[0]".to_string())
-                                                        {
-                                                            params.push("__body__[0]".to_string());
-                                                        }
-                                                    } else {
-                                                        // generic body marker
-                                                        if !params.contains(&"__body__".to_string())
-                                                        {
-                                                            params.push("__body__".to_string());
+                                                // Handle array types
+                                                if let Some(prop_type) =
+                                                    prop_val.get("type").and_then(|t| t.as_str())
+                                                {
+                                                    if prop_type == "array" {
+                                                        let array_param =
+                                                            format!("body.{}[0]", pname);
+                                                        if !params.contains(&array_param) {
+                                                            params.push(array_param);
                                                         }
                                                     }
                                                 }
                                             }
-                                            break; // Use first available content type
+                                        }
+                                    } else if resolved_schema.get("type").and_then(|t| t.as_str())
+                                        == Some("array")
+                                    {
+                                        // Handle array body
+                                        if !params.contains(&"__body__[0]".to_string()) {
+                                            params.push("__body__[0]".to_string());
+                                        }
+                                    } else {
+                                        // generic body marker
+                                        if !params.contains(&"__body__".to_string()) {
+                                            params.push("__body__".to_string());
                                         }
                                     }
                                 }
                             }
-
-                            let full_path = if let Some(bp) = &base_url {
-                                format!("{}{}", bp, path)
-                            } else {
-                                path.clone()
-                            };
-
-                            endpoints.push(Endpoint::new(
-                                method_enum,
-                                full_path,
-                                details
-                                    .get("summary")
-                                    .and_then(|s| s.as_str())
-                                    .map(|s| s.to_string()),
-                                params,
-                            ));
+                            break; // Use first available content type
                         }
                     }
                 }
             }
+
+            // The most specific servers list wins: operation, then path item, then root
+            let server = OpenApiParser::first_server(details)
+                .or_else(|| OpenApiParser::first_server(methods))
+                .or_else(|| base_url.map(str::to_string));
+            let full_path = if let Some(bp) = &server {
+                format!("{}{}", bp, path)
+            } else {
+                path.to_string()
+            };
+
+            // operationId uniquely names the operation; fall back to summary
+            let mut endpoint = Endpoint::new(
+                method_enum,
+                full_path,
+                details
+                    .get("operationId")
+                    .or_else(|| details.get("summary"))
+                    .and_then(|s| s.as_str())
+                    .map(|s| s.to_string()),
+                params,
+            );
+            endpoint.example_values = examples;
+            endpoint.example_body = example_body;
+            endpoint.parameters = structured;
+            // Operation-level security replaces the spec-wide default
+            endpoint.security = details
+                .get("security")
+                .or_else(|| json.get("security"))
+                .and_then(|s| OpenApiParser::security_kinds(json, s));
+            endpoint.deprecated = details
+                .get("deprecated")
+                .and_then(|d| d.as_bool())
+                .unwrap_or(false);
+            endpoint.tags = details
+                .get("tags")
+                .and_then(|t| t.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|t| t.as_str())
+                        .map(|t| t.to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            endpoints.push(endpoint);
         }
-        Ok(endpoints)
+        endpoints
     }
 }
@@ -1,60 +1,235 @@
 // Postman collection parser for Doppel
 // Uses serde_json to recursively parse Postman Collection v2.1 exports
 
-use crate::models::{CollectionParser, Endpoint, Method};
+use super::{add_url_parameters, normalize_path_variables, parse_json};
+use crate::models::{CollectionParser, Endpoint, Method, Parameter, ParameterLocation, ParseError};
 use serde_json::Value;
+use std::collections::HashMap;
 
 pub struct PostmanParser;
 
+impl PostmanParser {
+    /// Bearer token the collection already authenticates with, for
+    /// `--attacker-token auto`: the collection's, else the first folder's or
+    /// request's bearer auth block, else an `Authorization: Bearer` header.
+    /// `{{var}}` references are resolved from the collection variables; a
+    /// token that still references an unknown variable (e.g. one that lives
+    /// in a Postman environment) gives `None`.
+    pub fn auth_token(&self, content: &str) -> Option<String> {
+        let json = parse_json(content).ok()?;
+        let variables: HashMap<&str, &str> = json
+            .get("variable")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| Some((v.get("key")?.as_str()?, v.get("value")?.as_str()?)))
+            .collect();
+
+        let token = find_bearer_token(&json, 0)?;
+        let mut resolved = token.trim().to_string();
+        for (name, value) in &variables {
+            resolved = resolved.replace(&format!("{{{{{}}}}}", name), value);
+        }
+        let resolved = resolved
+            .strip_prefix("Bearer ")
+            .unwrap_or(&resolved)
+            .trim()
+            .to_string();
+        (!resolved.is_empty() && !resolved.contains("{{")).then_some(resolved)
+    }
+}
+
+/// Token of a `{"type": "bearer", "bearer": [{"key": "token", ...}]}` auth block
+fn bearer_auth_token(auth: &Value) -> Option<&str> {
+    if auth.get("type").and_then(|t| t.as_str()) != Some("bearer") {
+        return None;
+    }
+    auth.get("bearer")?
+        .as_array()?
+        .iter()
+        .find(|entry| entry.get("key").and_then(|k| k.as_str()) == Some("token"))?
+        .get("value")?
+        .as_str()
+}
+
+/// First bearer token in a collection or folder, outermost first
+fn find_bearer_token(node: &Value, depth: usize) -> Option<&str> {
+    if depth > MAX_FOLDER_DEPTH {
+        return None;
+    }
+    if let Some(token) = node.get("auth").and_then(bearer_auth_token) {
+        return Some(token);
+    }
+    if let Some(request) = node.get("request") {
+        if let Some(token) = request.get("auth").and_then(bearer_auth_token) {
+            return Some(token);
+        }
+        let header = request
+            .get("header")
+            .and_then(|h| h.as_array())
+            .into_iter()
+            .flatten()
+            .filter(|h| h.get("disabled").and_then(|d| d.as_bool()) != Some(true))
+            .find(|h| {
+                h.get("key")
+                    .and_then(|k| k.as_str())
+                    .is_some_and(|k| k.eq_ignore_ascii_case("authorization"))
+            })
+            .and_then(|h| h.get("value")?.as_str())
+            .filter(|v| v.trim_start().starts_with("Bearer "));
+        if header.is_some() {
+            return header;
+        }
+    }
+    node.get("item")?
+        .as_array()?
+        .iter()
+        .find_map(|item| find_bearer_token(item, depth + 1))
+}
+
 impl CollectionParser for PostmanParser {
-    fn parse(&self, file_path: &str) -> Result<Vec<Endpoint>, String> {
-        let data = std::fs::read_to_string(file_path)
-            .map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
-        let json: Value =
-            serde_json::from_str(&data).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    fn parse(&self, file_path: &str) -> Result<Vec<Endpoint>, ParseError> {
+        let data = std::fs::read_to_string(file_path).map_err(|e| ParseError::io(file_path, e))?;
+        self.parse_content(&data)
+    }
+
+    fn parse_content(&self, content: &str) -> Result<Vec<Endpoint>, ParseError> {
+        let json = parse_json(content)?;
+        let Some(items) = json.get("item").filter(|i| i.is_array()) else {
+            return Err(ParseError::UnsupportedFormat(
+                "Not a Postman collection: no item array".to_string(),
+            ));
+        };
         let mut endpoints = Vec::new();
-        if let Some(items) = json.get("item") {
-            parse_items(items, &mut endpoints);
+        parse_items(items, &mut endpoints, 0);
+        if endpoints.is_empty() {
+            return Err(ParseError::EmptyCollection);
         }
         Ok(endpoints)
     }
 }
 
-fn parse_items(items: &Value, endpoints: &mut Vec<Endpoint>) {
+/// Deepest folder nesting that is traversed; anything below is skipped with a
+/// warning. Real collections are a handful of levels deep, so hitting this
+/// means the input is malformed.
+const MAX_FOLDER_DEPTH: usize = 32;
+
+fn parse_items(items: &Value, endpoints: &mut Vec<Endpoint>, depth: usize) {
+    if depth > MAX_FOLDER_DEPTH {
+        eprintln!(
+            "[WARN] Postman folders nested deeper than {} levels; skipping the rest",
+            MAX_FOLDER_DEPTH
+        );
+        return;
+    }
     if let Some(array) = items.as_array() {
         for item in array {
             if let Some(request) = item.get("request") {
                 if let Some(method) = request.get("method").and_then(|m| m.as_str()) {
                     if let Some(url) = request.get("url") {
-                        let path = if let Some(raw) = url.get("raw").and_then(|r| r.as_str()) {
-                            raw.to_string()
-                        } else {
+                        let Some(path) = url_string(url) else {
                             continue;
                         };
-                        let method = match method {
-                            "GET" => Method::GET,
-                            "POST" => Method::POST,
-                            "PUT" => Method::PUT,
-                            "DELETE" => Method::DELETE,
-                            "PATCH" => Method::PATCH,
-                            "OPTIONS" => Method::OPTIONS,
-                            "HEAD" => Method::HEAD,
-                            _ => continue,
+                        let Some(method) = Method::parse(method) else {
+                            continue;
                         };
-                        endpoints.push(Endpoint::new(
+                        let path = normalize_path_variables(&path);
+                        let mut endpoint = Endpoint::new(
                             method,
                             path,
                             item.get("name")
                                 .and_then(|n| n.as_str())
                                 .map(|s| s.to_string()),
                             vec![],
-                        ));
+                        );
+                        add_url_parameters(&mut endpoint);
+                        // Disabled query params are documented but not sent
+                        if let Some(query) = url.get("query").and_then(|q| q.as_array()) {
+                            for q in query {
+                                if let Some(key) = q.get("key").and_then(|k| k.as_str()) {
+                                    let mut param = Parameter::new(key, ParameterLocation::Query);
+                                    param.required =
+                                        q.get("disabled").and_then(|d| d.as_bool()) != Some(true);
+                                    endpoint.parameters.retain(|p| p.name != key);
+                                    endpoint.add_parameter(param);
+                                }
+                            }
+                        }
+                        for param in body_parameters(request) {
+                            endpoint.add_parameter(param);
+                        }
+                        endpoint.example_body = raw_json_body(request);
+                        endpoints.push(endpoint);
                     }
                 }
             }
             if let Some(sub_items) = item.get("item") {
-                parse_items(sub_items, endpoints);
+                parse_items(sub_items, endpoints, depth + 1);
             }
         }
     }
 }
+
+// URLs are a plain string or an object with `raw`; objects exported without
+// `raw` are rebuilt from their protocol/host/path parts
+fn url_string(url: &Value) -> Option<String> {
+    if let Some(raw) = url
+        .as_str()
+        .or_else(|| url.get("raw").and_then(|r| r.as_str()))
+    {
+        return Some(raw.to_string());
+    }
+    // host and path are arrays of segments, or already-joined strings
+    let joined = |key: &str, sep: &str| match url.get(key)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Array(parts) => Some(
+            parts
+                .iter()
+                .filter_map(|p| p.as_str())
+                .collect::<Vec<_>>()
+                .join(sep),
+        ),
+        _ => None,
+    };
+    let host = joined("host", ".");
+    let path = joined("path", "/").unwrap_or_default();
+    if host.is_none() && path.is_empty() {
+        return None;
+    }
+    let mut out = String::new();
+    if let Some(host) = host {
+        if let Some(protocol) = url.get("protocol").and_then(|p| p.as_str()) {
+            out.push_str(protocol);
+            out.push_str("://");
+        }
+        out.push_str(&host);
+        if let Some(port) = url.get("port").and_then(|p| p.as_str()) {
+            out.push(':');
+            out.push_str(port);
+        }
+    }
+    out.push('/');
+    out.push_str(path.trim_start_matches('/'));
+    Some(out)
+}
+
+// The request's raw body, if it is JSON
+fn raw_json_body(request: &Value) -> Option<Value> {
+    request
+        .get("body")
+        .filter(|b| b.get("mode").and_then(|m| m.as_str()) == Some("raw"))
+        .and_then(|b| b.get("raw"))
+        .and_then(|r| r.as_str())
+        .and_then(|r| serde_json::from_str::<Value>(r).ok())
+}
+
+// Top-level fields of a raw JSON body, as "body.<field>" parameters
+fn body_parameters(request: &Value) -> Vec<Parameter> {
+    let fields = raw_json_body(request)
+        .and_then(|v| v.as_object().cloned())
+        .unwrap_or_default();
+    fields
+        .keys()
+        .map(|k| Parameter::new(format!("body.{}", k), ParameterLocation::Body))
+        .collect()
+}
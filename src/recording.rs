@@ -0,0 +1,430 @@
+// Record-and-replay of scan traffic for Doppel
+// Captures every request/response as HAR 1.2 entries (one per JSONL line) and
+// re-runs the verdict logic over a capture without sending new requests
+
+use crate::models::ScanResult;
+use crate::redact::{redact, RedactionConfig, REDACTED};
+use crate::response_analysis::{default_analyzers, run_analyzers, ResponseContext};
+use crate::verdict::{decide_verdict_with_baseline, is_login_redirect, Baseline};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+/// Headers whose values are never written to a recording
+const REDACTED_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+];
+
+/// HAR name/value pair
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarHeader {
+    pub name: String,
+    pub value: String,
+}
+
+/// HAR request body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarPostData {
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+    pub text: String,
+}
+
+/// HAR request object
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: Vec<HarHeader>,
+    #[serde(rename = "postData", default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+}
+
+/// HAR response body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarContent {
+    #[serde(rename = "mimeType", default)]
+    pub mime_type: String,
+    #[serde(default)]
+    pub text: String,
+}
+
+/// HAR response object. A status of 0 records a request that failed to send;
+/// the error message is stored as the content text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<HarHeader>,
+    pub content: HarContent,
+    /// Final URL when the client followed a redirect, empty otherwise
+    #[serde(rename = "redirectURL", default)]
+    pub redirect_url: String,
+}
+
+/// Scan metadata needed to re-derive a verdict (HAR custom field `_doppel`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanContext {
+    /// Endpoint path template, used to match probes with their attacks
+    pub endpoint: String,
+    pub victim_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attacker_id: Option<String>,
+    /// Value injected into the target parameters
+    pub injected_value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    /// Highest BOLA risk score among the attacked parameters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk_score: Option<u8>,
+    /// Baseline calibration probe rather than an attack
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub probe: bool,
+}
+
+/// One recorded request/response pair (HAR `entries[]` item)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    #[serde(rename = "startedDateTime")]
+    pub started_date_time: String,
+    /// Total elapsed time in milliseconds
+    pub time: u64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    #[serde(rename = "_doppel")]
+    pub context: ScanContext,
+}
+
+/// Convert a header map to HAR pairs, redacting credentials
+pub fn har_headers(headers: &reqwest::header::HeaderMap) -> Vec<HarHeader> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let name = name.as_str().to_string();
+            let value = if REDACTED_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                value.to_str().unwrap_or_default().to_string()
+            };
+            HarHeader { name, value }
+        })
+        .collect()
+}
+
+/// Capture a built request in HAR form
+pub fn har_request(request: &reqwest::Request) -> HarRequest {
+    let post_data = request
+        .body()
+        .and_then(|b| b.as_bytes())
+        .map(|bytes| HarPostData {
+            mime_type: request
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_string(),
+            text: String::from_utf8_lossy(bytes).to_string(),
+        });
+    HarRequest {
+        method: request.method().to_string(),
+        url: request.url().to_string(),
+        headers: har_headers(request.headers()),
+        post_data,
+    }
+}
+
+/// Appends recorded exchanges to a JSONL file
+pub struct Recorder {
+    writer: BufWriter<File>,
+    redaction: RedactionConfig,
+}
+
+impl Recorder {
+    pub fn create(path: &str) -> Result<Self, String> {
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create recording {}: {}", path, e))?;
+        Ok(Recorder {
+            writer: BufWriter::new(file),
+            redaction: RedactionConfig::default(),
+        })
+    }
+
+    /// Mask these fields in recorded URLs and bodies instead of the defaults
+    pub fn with_redaction(mut self, redaction: RedactionConfig) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    /// Write one exchange and flush, so an interrupted scan keeps its capture.
+    /// URLs and bodies are redacted first.
+    pub fn record(&mut self, exchange: &RecordedExchange) -> Result<(), String> {
+        let mut exchange = exchange.clone();
+        redact_exchange(&mut exchange, &self.redaction);
+        let line = serde_json::to_string(&exchange)
+            .map_err(|e| format!("Failed to serialize exchange: {}", e))?;
+        writeln!(self.writer, "{}", line)
+            .and_then(|_| self.writer.flush())
+            .map_err(|e| format!("Failed to write recording: {}", e))
+    }
+}
+
+/// Mask secrets in an exchange's URLs and bodies; headers are already
+/// redacted when captured
+fn redact_exchange(exchange: &mut RecordedExchange, config: &RedactionConfig) {
+    let request = &mut exchange.request;
+    request.url = redact(&request.url, config);
+    if let Some(post_data) = &mut request.post_data {
+        post_data.text = redact(&post_data.text, config);
+    }
+    let response = &mut exchange.response;
+    response.content.text = redact(&response.content.text, config);
+    response.redirect_url = redact(&response.redirect_url, config);
+}
+
+/// Read a recording written by `--record`
+pub fn load_recording(path: &str) -> Result<Vec<RecordedExchange>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let mut exchanges = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let exchange = serde_json::from_str(&line)
+            .map_err(|e| format!("{} line {} is not a recorded exchange: {}", path, i + 1, e))?;
+        exchanges.push(exchange);
+    }
+    Ok(exchanges)
+}
+
+/// Re-run verdict analysis over recorded traffic. Baseline probes in the
+/// recording are used exactly as they were during the live scan. PII analysis
+/// is not replayed since it needs a running Ollama instance.
+pub fn replay(exchanges: &[RecordedExchange], soft_fail_analysis: bool) -> Vec<ScanResult> {
+    let analyzers = default_analyzers(soft_fail_analysis);
+    // Probes with the attacker's own id complete the missing-resource
+    // baseline recorded before them
+    let probes = exchanges
+        .iter()
+        .filter(|e| e.context.probe && e.response.status != 0);
+    let own_probe = |e: &&RecordedExchange| {
+        e.context.attacker_id.as_deref() == Some(e.context.injected_value.as_str())
+    };
+    let mut baselines: HashMap<(&str, &str), Baseline> = probes
+        .clone()
+        .filter(|e| !own_probe(e))
+        .map(|e| {
+            (
+                (e.request.method.as_str(), e.context.endpoint.as_str()),
+                Baseline::new(
+                    e.response.status,
+                    &e.response.content.text,
+                    &e.context.injected_value,
+                ),
+            )
+        })
+        .collect();
+    for e in probes.filter(own_probe) {
+        let key = (e.request.method.as_str(), e.context.endpoint.as_str());
+        if let Some(baseline) = baselines.remove(&key) {
+            let baseline = baseline.with_own_response(e.response.status, &e.response.content.text);
+            baselines.insert(key, baseline);
+        }
+    }
+
+    exchanges
+        .iter()
+        .filter(|e| !e.context.probe)
+        .map(|e| {
+            let ctx = &e.context;
+            let body = &e.response.content.text;
+            let verdict = if e.response.status == 0 {
+                format!("ERROR: {}", body)
+            } else if !e.response.redirect_url.is_empty()
+                && is_login_redirect(&e.response.redirect_url)
+            {
+                "SECURE".to_string()
+            } else {
+                let verdict = decide_verdict_with_baseline(
+                    &e.request.method,
+                    e.response.status,
+                    body,
+                    ctx.attacker_id.as_deref(),
+                    Some(ctx.victim_id.as_str()),
+                    baselines.get(&(e.request.method.as_str(), ctx.endpoint.as_str())),
+                );
+                let headers: Vec<(String, String)> = e
+                    .response
+                    .headers
+                    .iter()
+                    .map(|h| (h.name.clone(), h.value.clone()))
+                    .collect();
+                let request_origin = e
+                    .request
+                    .headers
+                    .iter()
+                    .find(|h| h.name.eq_ignore_ascii_case("origin"))
+                    .map(|h| h.value.as_str());
+                let response = ResponseContext {
+                    method: &e.request.method,
+                    url: &e.request.url,
+                    status: e.response.status,
+                    body,
+                    verdict,
+                    attacker_id: ctx.attacker_id.as_deref(),
+                    victim_id: Some(ctx.victim_id.as_str()),
+                    headers: &headers,
+                    request_origin,
+                };
+                let mut label = verdict.label().to_string();
+                for finding in run_analyzers(&analyzers, &response) {
+                    label.push_str(&format!(" | {}", finding));
+                }
+                label
+            };
+            // Live results report the URL without its query string
+            let url = e.request.url.split('?').next().unwrap_or_default();
+            // A recorded status of 0 means the request failed
+            let status = (e.response.status != 0).then_some(e.response.status);
+            ScanResult::new(e.request.method.clone(), url, verdict)
+                .with_status(status)
+                .with_value(ctx.injected_value.clone())
+                .with_latency(e.time)
+                .with_deprecated(ctx.deprecated)
+                .with_name(ctx.name.clone())
+                .with_risk_score(ctx.risk_score)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(status: u16, body: &str, injected: &str, probe: bool) -> RecordedExchange {
+        RecordedExchange {
+            started_date_time: "2024-01-01T00:00:00Z".to_string(),
+            time: 12,
+            request: HarRequest {
+                method: "GET".to_string(),
+                url: format!("http://api/users/{}", injected),
+                headers: Vec::new(),
+                post_data: None,
+            },
+            response: HarResponse {
+                status,
+                headers: Vec::new(),
+                content: HarContent {
+                    mime_type: "application/json".to_string(),
+                    text: body.to_string(),
+                },
+                redirect_url: String::new(),
+            },
+            context: ScanContext {
+                endpoint: "/users/{id}".to_string(),
+                victim_id: "victim_123".to_string(),
+                attacker_id: Some("attacker_456".to_string()),
+                injected_value: injected.to_string(),
+                name: None,
+                deprecated: false,
+                risk_score: None,
+                probe,
+            },
+        }
+    }
+
+    #[test]
+    fn test_replay_rederives_verdicts() {
+        let exchanges = vec![
+            exchange(
+                404,
+                r#"{"error":"User 900000000001 not found"}"#,
+                "900000000001",
+                true,
+            ),
+            exchange(200, r#"{"id":"victim_123"}"#, "victim_123", false),
+            exchange(
+                404,
+                r#"{"error":"User victim_123 not found"}"#,
+                "victim_123",
+                false,
+            ),
+            exchange(0, "connection refused", "victim_123", false),
+        ];
+
+        let results = replay(&exchanges, false);
+        assert_eq!(results.len(), 3, "probes are not reported");
+        assert_eq!(results[0].verdict, "VULNERABLE");
+        assert_eq!(
+            results[1].verdict, "SECURE",
+            "matches the missing-resource baseline"
+        );
+        assert_eq!(results[2].verdict, "ERROR: connection refused");
+        assert_eq!(results[0].latency_ms, Some(12));
+    }
+
+    #[test]
+    fn test_own_probe_completes_baseline() {
+        let exchanges = vec![
+            exchange(200, "[]", "900000000001", true),
+            exchange(200, r#"[{"sku":"a1"}]"#, "attacker_456", true),
+            exchange(200, r#"[{"sku":"v1"},{"sku":"v2"}]"#, "victim_123", false),
+        ];
+        let results = replay(&exchanges, false);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].verdict, "VULNERABLE");
+    }
+
+    #[test]
+    fn test_login_redirect_replays_as_secure() {
+        let mut e = exchange(200, "<html>Sign in</html>", "victim_123", false);
+        e.response.redirect_url = "http://api/login".to_string();
+        assert_eq!(replay(&[e], false)[0].verdict, "SECURE");
+    }
+
+    #[test]
+    fn test_credentials_are_redacted() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("Authorization", "Bearer secret".parse().unwrap());
+        headers.insert("Accept", "application/json".parse().unwrap());
+        let har = har_headers(&headers);
+        let auth = har.iter().find(|h| h.name == "authorization").unwrap();
+        assert_eq!(auth.value, "[REDACTED]");
+        assert!(har.iter().any(|h| h.value == "application/json"));
+    }
+
+    #[test]
+    fn test_recorded_bodies_are_redacted() {
+        let mut e = exchange(
+            200,
+            r#"{"id":"victim_123","password":"hunter2"}"#,
+            "victim_123",
+            false,
+        );
+        e.request.url = "http://api/users/victim_123?token=abc".to_string();
+        redact_exchange(&mut e, &RedactionConfig::default());
+        assert_eq!(
+            e.request.url,
+            "http://api/users/victim_123?token=[REDACTED]"
+        );
+        assert!(!e.response.content.text.contains("hunter2"));
+        // Identity fields survive, so the recording still replays
+        assert_eq!(replay(&[e], false)[0].verdict, "VULNERABLE");
+    }
+
+    #[test]
+    fn test_exchange_uses_har_field_names() {
+        let json = serde_json::to_value(exchange(200, "{}", "1", false)).unwrap();
+        assert!(json.get("startedDateTime").is_some());
+        assert!(json["response"].get("redirectURL").is_some());
+        assert!(json.get("_doppel").is_some());
+    }
+}
@@ -1,9 +1,86 @@
 // Reporting and output for Doppel
-// Supports CSV, Markdown, and PDF export (PDF stub)
+// Supports CSV, Markdown, JSON, and PDF export (PDF stub), plus report diffing
 
+use crate::models::{Endpoint, ScanResult};
+use crate::response_analysis::NOT_AUTHENTICATED_NOTE;
 use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Write;
+use std::path::PathBuf;
+
+/// Standard advice attached to VULNERABLE findings
+pub const DEFAULT_REMEDIATION: &str = "Enforce object-level authorization: verify the authenticated principal owns the requested resource id server-side";
+
+/// Standard advice attached to UNCERTAIN findings
+pub const DEFAULT_UNCERTAIN_REMEDIATION: &str = "Manually confirm whether this response exposes another user's data; if so, enforce object-level authorization server-side";
+
+/// Report configuration shared by all exporters
+#[derive(Debug, Clone, Default)]
+pub struct ReportConfig {
+    /// Replaces the standard remediation text for teams with their own language
+    pub remediation_override: Option<String>,
+    /// Directory report files are written to (default: current directory)
+    pub output_dir: Option<PathBuf>,
+    /// Summary of the whole scan; computed from the reported results when absent
+    pub summary: Option<ScanSummary>,
+    /// Fold path case in finding ids (--case-insensitive-paths)
+    pub case_insensitive_paths: bool,
+}
+
+impl ReportConfig {
+    /// Summary block for reports over `results`
+    pub fn summary_for(&self, results: &[ScanResult]) -> ScanSummary {
+        self.summary
+            .clone()
+            .unwrap_or_else(|| summarize(results, self.case_insensitive_paths))
+    }
+
+    /// Timestamped report path for `extension`, creating the output directory if needed
+    pub fn report_path(&self, extension: &str) -> Result<String, std::io::Error> {
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+        let filename = format!("doppel_report_{}.{}", timestamp, extension);
+        match &self.output_dir {
+            Some(dir) => {
+                std::fs::create_dir_all(dir)?;
+                Ok(dir.join(filename).to_string_lossy().into_owned())
+            }
+            None => Ok(filename),
+        }
+    }
+
+    /// Remediation text for a finding, honoring the configured override
+    pub fn remediation_for(&self, verdict: &str, endpoint: &str) -> Option<String> {
+        let standard = remediation_for(verdict, endpoint)?;
+        Some(self.remediation_override.clone().unwrap_or(standard))
+    }
+}
+
+/// Standard remediation advice for a finding.
+///
+/// Returns None for results that need no action (SECURE, ERROR).
+/// `endpoint` is the "METHOD URL" of the finding; state-changing methods
+/// get an extra note since the impact goes beyond data exposure.
+pub fn remediation_for(verdict: &str, endpoint: &str) -> Option<String> {
+    let base = if verdict.starts_with("VULNERABLE") {
+        DEFAULT_REMEDIATION
+    } else if verdict.starts_with("UNCERTAIN") {
+        DEFAULT_UNCERTAIN_REMEDIATION
+    } else {
+        return None;
+    };
+
+    let method = endpoint.split_whitespace().next().unwrap_or("");
+    if matches!(method, "PUT" | "PATCH" | "DELETE") {
+        Some(format!(
+            "{}. Perform the ownership check before applying any modification.",
+            base
+        ))
+    } else {
+        Some(base.to_string())
+    }
+}
 
 /// Escape CSV field to prevent formula injection attacks
 /// Cells starting with =, +, -, @, or tab are prefixed with single quote
@@ -29,38 +106,814 @@ fn escape_csv_field(field: &str) -> String {
     }
 }
 
-pub fn export_csv(results: &[(String, String, String)]) -> Result<String, std::io::Error> {
-    let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-    let filename = format!("doppel_report_{}.csv", timestamp);
+pub fn export_csv(results: &[ScanResult]) -> Result<String, std::io::Error> {
+    export_csv_with_config(results, &ReportConfig::default())
+}
+
+/// Write the CSV report and return its path
+pub fn export_csv_with_config(
+    results: &[ScanResult],
+    config: &ReportConfig,
+) -> Result<String, std::io::Error> {
+    let filename = config.report_path("csv")?;
     let mut file = File::create(&filename)?;
 
-    writeln!(file, "Method,URL,Result")?;
-    for (method, url, verdict) in results {
-        writeln!(
-            file,
-            "{},{},{}",
-            escape_csv_field(method),
-            escape_csv_field(url),
-            escape_csv_field(verdict)
-        )?;
+    writeln!(
+        file,
+        "Method,URL,Result,Status,Severity,Soft Fail,PII,Risk Score,Finding ID,Injected"
+    )?;
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    for r in results {
+        let fields = [
+            r.method.clone(),
+            r.url.clone(),
+            r.verdict.clone(),
+            optional(r.status.map(|s| s.to_string())),
+            severity_of(r).to_string(),
+            optional(r.soft_fail().map(str::to_string)),
+            optional(r.verdict_note("PII").map(str::to_string)),
+            optional(r.risk_score.map(|s| s.to_string())),
+            finding_id(r, config.case_insensitive_paths),
+            r.injected_label(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| escape_csv_field(f)).collect();
+        writeln!(file, "{}", row.join(","))?;
     }
 
     Ok(filename)
 }
 
-pub fn export_markdown(results: &[(String, String, String)]) -> Result<String, std::io::Error> {
-    let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-    let filename = format!("doppel_report_{}.md", timestamp);
+pub fn export_markdown(results: &[ScanResult]) -> Result<String, std::io::Error> {
+    export_markdown_with_config(results, &ReportConfig::default())
+}
+
+pub fn export_markdown_with_config(
+    results: &[ScanResult],
+    config: &ReportConfig,
+) -> Result<String, std::io::Error> {
+    let filename = config.report_path("md")?;
     let mut file = File::create(&filename)?;
+    file.write_all(render_markdown(results, config).as_bytes())?;
+
+    Ok(filename)
+}
+
+/// Render the Markdown report body
+pub fn render_markdown(results: &[ScanResult], config: &ReportConfig) -> String {
+    let mut out = String::from("# Doppel Report\n\n");
+    out.push_str("## Summary\n\n");
+    out.push_str(&config.summary_for(results).render_markdown());
+    out.push_str("\n## Findings\n\n");
+    for r in results {
+        match &r.name {
+            Some(name) => out.push_str(&format!(
+                "- **{}** {} ({}): {}",
+                r.method, r.url, name, r.verdict
+            )),
+            None => out.push_str(&format!("- **{}** {}: {}", r.method, r.url, r.verdict)),
+        }
+        if r.deprecated {
+            out.push_str(" [deprecated]");
+        }
+        let injected = r.injected_label();
+        if r.count > 1 {
+            out.push_str(&format!(" (x{}, values: {})", r.count, injected));
+        } else if !injected.is_empty() {
+            out.push_str(&format!(" (injected: {})", injected));
+        }
+        if let Some(ms) = r.latency_ms {
+            out.push_str(&format!(" ({} ms)", ms));
+        }
+        if let Some(confidence) = r.confidence {
+            out.push_str(&format!(" (confidence: {}%)", confidence));
+        }
+        out.push_str(&format!(
+            " (id: {})",
+            finding_id(r, config.case_insensitive_paths)
+        ));
+        out.push('\n');
+        let categories = owasp_categories(r);
+        if !categories.is_empty() {
+            out.push_str(&format!("  - OWASP: {}\n", categories.join("; ")));
+        }
+        if let Some(remediation) =
+            config.remediation_for(&r.verdict, &format!("{} {}", r.method, r.url))
+        {
+            out.push_str(&format!("  - Remediation: {}\n", remediation));
+        }
+    }
+    out
+}
+
+/// Collapse a concrete URL into a path template for grouping.
+///
+/// Path segments that are numeric, UUID-shaped, or equal to one of the
+/// injected values are replaced with "{id}", so "/users/122" and
+/// "/users/124" share the template "/users/{id}". The path is canonicalized
+/// with [`normalize_path`] first, unless the empty string was injected (its
+/// empty segment is the finding). `lowercase` folds the case of the path
+/// after injected values are matched, so an upper-case mutation still
+/// becomes "{id}"; use it for grouping keys, not for displayed URLs.
+pub fn path_template(url: &str, values: &[String], lowercase: bool) -> String {
+    let normalized;
+    let url = if values.iter().any(|v| v.is_empty()) {
+        url
+    } else {
+        normalized = normalize_path(url, false);
+        &normalized
+    };
+    let (path, query) = match url.split_once('?') {
+        Some((p, q)) => (p, Some(q)),
+        None => (url, None),
+    };
+    let (prefix, path) = match path.find("://") {
+        Some(i) => match path[i + 3..].find('/') {
+            Some(j) => path.split_at(i + 3 + j),
+            None => (path, ""),
+        },
+        None => ("", path),
+    };
+
+    let segments: Vec<&str> = path.split('/').collect();
+    let last = segments.len().saturating_sub(1);
+    let templated: Vec<&str> = segments
+        .iter()
+        .enumerate()
+        .map(|(i, seg)| {
+            // Leading "" is the root; an empty trailing segment is only an id
+            // when the empty string itself was injected
+            let injected = values.iter().any(|v| v == seg) && (i == last || !seg.is_empty());
+            if i > 0 && (injected || is_id_segment(seg)) {
+                "{id}"
+            } else {
+                seg
+            }
+        })
+        .collect();
+
+    let path = templated.join("/");
+    let mut out = if lowercase {
+        format!("{}{}", prefix, path.to_lowercase())
+    } else {
+        format!("{}{}", prefix, path)
+    };
+    if let Some(q) = query {
+        out.push('?');
+        out.push_str(q);
+    }
+    out
+}
+
+/// Canonical form of a URL's path: duplicate slashes collapsed and the
+/// trailing slash dropped, so `/users/1`, `/users/1/` and `//users/1` are one
+/// endpoint. `lowercase` also folds the path's case; it is opt-in because
+/// some APIs treat `/Users/1` and `/users/1` as different resources. Scheme,
+/// host and query string are left as they are.
+pub fn normalize_path(url: &str, lowercase: bool) -> String {
+    let (path, query) = match url.split_once('?') {
+        Some((p, q)) => (p, Some(q)),
+        None => (url, None),
+    };
+    let (prefix, path) = match path.find("://") {
+        Some(i) => match path[i + 3..].find('/') {
+            Some(j) => path.split_at(i + 3 + j),
+            None => (path, ""),
+        },
+        None => ("", path),
+    };
+
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let mut out = prefix.to_string();
+    if segments.is_empty() {
+        if path.starts_with('/') {
+            out.push('/');
+        }
+    } else {
+        let joined = segments.join("/");
+        if path.starts_with('/') {
+            out.push('/');
+        }
+        if lowercase {
+            out.push_str(&joined.to_lowercase());
+        } else {
+            out.push_str(&joined);
+        }
+    }
+    if let Some(q) = query {
+        out.push('?');
+        out.push_str(q);
+    }
+    out
+}
+
+/// Stable short identifier for a finding, for deduping across scans, diffing,
+/// and issue trackers.
+///
+/// Hashes the method, the path template (ids replaced, query dropped) and
+/// the verdict class, so rescans that inject different values into
+/// `/users/123` and `/users/124` produce the same id. `lowercase` folds the
+/// path's case (--case-insensitive-paths). FNV-1a keeps the value identical
+/// across platforms and Rust versions.
+pub fn finding_id(result: &ScanResult, lowercase: bool) -> String {
+    let template = path_template(&result.url, &result.triggering_values, lowercase);
+    let path = template.split('?').next().unwrap_or_default();
+    let key = format!(
+        "{} {} {}",
+        result.method.to_uppercase(),
+        path,
+        result.verdict_class()
+    );
+
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in key.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)[..12].to_string()
+}
+
+fn is_id_segment(seg: &str) -> bool {
+    let numeric = !seg.is_empty() && seg.chars().all(|c| c.is_ascii_digit());
+    let uuid_like = seg.len() == 36
+        && seg.chars().filter(|c| *c == '-').count() == 4
+        && seg.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+    numeric || uuid_like
+}
+
+/// Group equivalent results into single findings.
+///
+/// Results sharing (method, path template, verdict) are merged with a count
+/// and the set of values that triggered them. ERROR results are grouped by
+/// class only since their messages usually embed the concrete URL.
+/// `lowercase` groups paths differing only in case; the group keeps the
+/// first-seen spelling. First-seen order is preserved.
+pub fn dedupe_results(results: &[ScanResult], lowercase: bool) -> Vec<ScanResult> {
+    let mut grouped: Vec<ScanResult> = Vec::new();
+    let mut index: std::collections::HashMap<(String, String, String), usize> =
+        std::collections::HashMap::new();
+
+    for r in results {
+        let template = path_template(&r.url, &r.triggering_values, false);
+        let verdict_key = if r.verdict_class() == "ERROR" {
+            "ERROR".to_string()
+        } else {
+            r.verdict.clone()
+        };
+        let path_key = if lowercase {
+            path_template(&r.url, &r.triggering_values, true)
+        } else {
+            template.clone()
+        };
+        let key = (r.method.clone(), path_key, verdict_key);
+
+        match index.get(&key) {
+            Some(&i) => {
+                let group = &mut grouped[i];
+                group.count += r.count;
+                // Keep the slowest observation for the group
+                group.latency_ms = group.latency_ms.max(r.latency_ms);
+                // and the weakest evidence
+                group.confidence = match (group.confidence, r.confidence) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+                for v in &r.triggering_values {
+                    if !group.triggering_values.contains(v) {
+                        group.triggering_values.push(v.clone());
+                    }
+                }
+            }
+            None => {
+                let mut group = r.clone();
+                group.url = template;
+                index.insert(key, grouped.len());
+                grouped.push(group);
+            }
+        }
+    }
+
+    grouped
+}
+
+/// Scan outcome at a glance, shown at the top of every report
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScanSummary {
+    /// Distinct endpoints (method + operation or path template) that were attacked
+    pub endpoints_scanned: usize,
+    pub requests_sent: usize,
+    pub vulnerable: usize,
+    pub secure: usize,
+    pub uncertain: usize,
+    pub errors: usize,
+    /// Results whose PII analysis found personal data
+    pub with_pii: usize,
+    /// Requests answered 401: the attacker token was not accepted
+    #[serde(default)]
+    pub unauthenticated: usize,
+    /// Wall-clock scan time; not derivable from results, so set by the caller
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<f64>,
+    /// How much of the collection was exercised; needs the endpoint list, so
+    /// set by the caller
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<CoverageReport>,
+    /// The scan stopped early because --max-requests was reached
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub budget_truncated: bool,
+    /// Endpoints --preflight found missing for every id ("METHOD path")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unreachable: Vec<String>,
+    /// Ids an --enumerate sweep could read that are not the attacker's
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accessible_ids: Vec<String>,
+    /// Endpoints with no object reference, sent once but not fuzzed ("METHOD path")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub not_candidates: Vec<String>,
+}
+
+impl ScanSummary {
+    pub fn with_duration(mut self, duration: std::time::Duration) -> Self {
+        self.duration_secs = Some(duration.as_secs_f64());
+        self
+    }
+
+    /// Most requests got a 401: the attacker token is probably not working,
+    /// and the SECURE verdicts only show that unauthenticated calls fail
+    pub fn token_rejected(&self) -> bool {
+        self.unauthenticated > 0 && self.unauthenticated * 2 >= self.requests_sent
+    }
+
+    fn render_markdown(&self) -> String {
+        let mut rows = vec![
+            ("Endpoints scanned", self.endpoints_scanned.to_string()),
+            ("Requests sent", self.requests_sent.to_string()),
+            ("VULNERABLE", self.vulnerable.to_string()),
+            ("SECURE", self.secure.to_string()),
+            ("UNCERTAIN", self.uncertain.to_string()),
+            ("ERROR", self.errors.to_string()),
+            ("With PII", self.with_pii.to_string()),
+        ];
+        if self.unauthenticated > 0 {
+            rows.push(("Not authenticated (401)", self.unauthenticated.to_string()));
+        }
+        if let Some(secs) = self.duration_secs {
+            rows.push(("Duration", format!("{:.1} s", secs)));
+        }
+        if self.budget_truncated {
+            rows.push(("Request budget", "exhausted; scan truncated".to_string()));
+        }
+        if let Some(coverage) = &self.coverage {
+            rows.push(("Endpoints tested", coverage.tested_line()));
+            rows.push(("Conclusive endpoints", coverage.conclusive_line()));
+        }
+        if !self.unreachable.is_empty() {
+            rows.push(("Not reachable", self.unreachable.join(", ")));
+        }
+        if !self.not_candidates.is_empty() {
+            rows.push(("Not BOLA candidates", self.not_candidates.join(", ")));
+        }
+        if !self.accessible_ids.is_empty() {
+            rows.push(("Accessible ids", self.accessible_ids.join(", ")));
+        }
+        let mut out = String::from("| Metric | Value |\n|---|---|\n");
+        for (metric, value) in rows {
+            out.push_str(&format!("| {} | {} |\n", metric, value));
+        }
+        out
+    }
+}
+
+/// Count outcomes across a scan. Grouped results count once per request they
+/// represent; `lowercase` counts paths differing only in case as one endpoint.
+pub fn summarize(results: &[ScanResult], lowercase: bool) -> ScanSummary {
+    let mut summary = ScanSummary::default();
+    let mut endpoints = std::collections::HashSet::new();
+    for r in results {
+        let endpoint = match &r.name {
+            Some(name) => name.clone(),
+            None => path_template(&r.url, &r.triggering_values, lowercase),
+        };
+        endpoints.insert((r.method.clone(), endpoint));
+        summary.requests_sent += r.count;
+        match r.verdict_class() {
+            "VULNERABLE" => summary.vulnerable += r.count,
+            "SECURE" => summary.secure += r.count,
+            "UNCERTAIN" => summary.uncertain += r.count,
+            _ => summary.errors += r.count,
+        }
+        if r.verdict.contains("PII: YES") {
+            summary.with_pii += r.count;
+        }
+        if r.verdict.contains(NOT_AUTHENTICATED_NOTE) {
+            summary.unauthenticated += r.count;
+        }
+    }
+    summary.endpoints_scanned = endpoints.len();
+    summary
+}
+
+/// How thoroughly a scan exercised the collection. A clean scan only means
+/// "secure" when most endpoints were tested and reached a conclusive verdict.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoverageReport {
+    /// Endpoints in the (filtered) collection
+    pub endpoints_total: usize,
+    /// Endpoints with at least one parameter attacked
+    pub endpoints_tested: usize,
+    /// Endpoints never attacked: no parameter met the risk threshold, the
+    /// preflight found no route, or the scan was interrupted first
+    pub endpoints_skipped: usize,
+    /// Tested endpoints with at least one VULNERABLE or SECURE result
+    pub endpoints_conclusive: usize,
+}
+
+impl CoverageReport {
+    /// "tested/total (pct%)"
+    pub fn tested_line(&self) -> String {
+        ratio_line(self.endpoints_tested, self.endpoints_total)
+    }
+
+    /// "conclusive/tested (pct%)"
+    pub fn conclusive_line(&self) -> String {
+        ratio_line(self.endpoints_conclusive, self.endpoints_tested)
+    }
+}
+
+fn ratio_line(part: usize, whole: usize) -> String {
+    if whole == 0 {
+        return format!("{}/0", part);
+    }
+    format!(
+        "{}/{} ({:.0}%)",
+        part,
+        whole,
+        part as f64 * 100.0 / whole as f64
+    )
+}
 
-    writeln!(file, "# Doppel Report\n")?;
-    for (method, url, verdict) in results {
-        writeln!(file, "- **{}** {}: {}", method, url, verdict)?;
+/// Whether a result came from an endpoint: same method and a URL path whose
+/// trailing segments match the endpoint's path template. Operation names
+/// decide when both sides have one.
+fn result_matches_endpoint(result: &ScanResult, endpoint: &Endpoint) -> bool {
+    if !result
+        .method
+        .eq_ignore_ascii_case(&endpoint.method.to_string())
+    {
+        return false;
+    }
+    if let (Some(name), Some(description)) = (&result.name, &endpoint.description) {
+        return name == description;
     }
 
+    // Path segments without scheme, host, query or Postman {{variables}}
+    let segments = |url: &str| -> Vec<String> {
+        let path = url.split('?').next().unwrap_or_default();
+        let path = path
+            .split_once("://")
+            .map_or(path, |(_, rest)| rest.find('/').map_or("", |i| &rest[i..]));
+        path.split('/')
+            .filter(|s| !s.is_empty() && !s.starts_with("{{"))
+            .map(str::to_string)
+            .collect()
+    };
+    let template = segments(&endpoint.path);
+    let actual = segments(&result.url);
+    if actual.len() < template.len() {
+        return false;
+    }
+    actual[actual.len() - template.len()..]
+        .iter()
+        .zip(&template)
+        .all(|(a, t)| t.starts_with('{') || a == t)
+}
+
+/// Coverage of `endpoints` by a scan's `results`
+pub fn coverage(results: &[ScanResult], endpoints: &[Endpoint]) -> CoverageReport {
+    let mut report = CoverageReport {
+        endpoints_total: endpoints.len(),
+        ..Default::default()
+    };
+    for endpoint in endpoints {
+        let mut matched = results
+            .iter()
+            .filter(|r| result_matches_endpoint(r, endpoint))
+            .peekable();
+        if matched.peek().is_none() {
+            report.endpoints_skipped += 1;
+            continue;
+        }
+        report.endpoints_tested += 1;
+        if matched.any(|r| matches!(r.verdict_class(), "VULNERABLE" | "SECURE")) {
+            report.endpoints_conclusive += 1;
+        }
+    }
+    report
+}
+
+/// Triage priority of a finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Severity::Info => "Info",
+            Severity::Low => "Low",
+            Severity::Medium => "Medium",
+            Severity::High => "High",
+            Severity::Critical => "Critical",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Risk score at or above which an endpoint counts as high risk
+const HIGH_RISK_SCORE: u8 = 80;
+
+/// Severity from the verdict, PII detection, and the endpoint's BOLA risk score.
+///
+/// VULNERABLE + PII on a high-risk endpoint is Critical; either factor alone
+/// is High. A confirmed VULNERABLE with an unknown risk score is also High.
+/// UNCERTAIN results are at most Medium; SECURE and ERROR are Info.
+pub fn severity_of(result: &ScanResult) -> Severity {
+    let pii = result.verdict.contains("PII: YES");
+    let high_risk = result.risk_score.map(|s| s >= HIGH_RISK_SCORE);
+    match result.verdict_class() {
+        "VULNERABLE" => match (pii, high_risk) {
+            (true, Some(true)) => Severity::Critical,
+            (false, Some(false)) => Severity::Medium,
+            _ => Severity::High,
+        },
+        "UNCERTAIN" if pii || high_risk == Some(true) => Severity::Medium,
+        "UNCERTAIN" => Severity::Low,
+        _ => Severity::Info,
+    }
+}
+
+/// Kinds of issue a result can report, each mapping to one category of the
+/// OWASP API Security Top 10 (2023)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FindingType {
+    /// Another user's object is reachable by its id (BOLA/IDOR)
+    ObjectLevelAuthorization,
+    /// Credentials or tokens are accepted when they should not be
+    BrokenAuthentication,
+    /// Properties the caller may not read or write (mass assignment)
+    PropertyLevelAuthorization,
+    /// CORS misconfiguration, stack traces and other information disclosure
+    SecurityMisconfiguration,
+}
+
+/// OWASP API Security Top 10 (2023) category of a finding type
+pub fn owasp_category(finding_type: FindingType) -> &'static str {
+    match finding_type {
+        FindingType::ObjectLevelAuthorization => "API1:2023 Broken Object Level Authorization",
+        FindingType::BrokenAuthentication => "API2:2023 Broken Authentication",
+        FindingType::PropertyLevelAuthorization => {
+            "API3:2023 Broken Object Property Level Authorization"
+        }
+        FindingType::SecurityMisconfiguration => "API8:2023 Security Misconfiguration",
+    }
+}
+
+/// Finding types a result reports: its BOLA verdict when VULNERABLE or
+/// UNCERTAIN, plus analyzer notes that are issues of their own
+pub fn finding_types(result: &ScanResult) -> Vec<FindingType> {
+    let mut types = Vec::new();
+    if matches!(result.verdict_class(), "VULNERABLE" | "UNCERTAIN") {
+        types.push(FindingType::ObjectLevelAuthorization);
+    }
+    if result.verdict.contains("CORS misconfiguration")
+        || result.verdict.contains("Info disclosure")
+    {
+        types.push(FindingType::SecurityMisconfiguration);
+    }
+    types
+}
+
+/// OWASP categories of a result, for reports
+pub fn owasp_categories(result: &ScanResult) -> Vec<&'static str> {
+    finding_types(result)
+        .into_iter()
+        .map(owasp_category)
+        .collect()
+}
+
+/// Order results most severe first, keeping scan order within a severity
+pub fn sort_by_severity(results: &mut [ScanResult]) {
+    results.sort_by_key(|r| std::cmp::Reverse(severity_of(r)));
+}
+
+/// Min/median/max request latency across a scan
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LatencyStats {
+    pub min_ms: u64,
+    pub median_ms: u64,
+    pub max_ms: u64,
+}
+
+/// Compute latency statistics over results that carry a measurement
+pub fn latency_stats(results: &[ScanResult]) -> Option<LatencyStats> {
+    let mut samples: Vec<u64> = results.iter().filter_map(|r| r.latency_ms).collect();
+    if samples.is_empty() {
+        return None;
+    }
+    samples.sort_unstable();
+
+    let mid = samples.len() / 2;
+    let median_ms = if samples.len().is_multiple_of(2) {
+        (samples[mid - 1] + samples[mid]) / 2
+    } else {
+        samples[mid]
+    };
+
+    Some(LatencyStats {
+        min_ms: samples[0],
+        median_ms,
+        max_ms: samples[samples.len() - 1],
+    })
+}
+
+/// JSON report layout: summary block plus the ScanResult objects.
+/// This is the format consumed by `load_json_report` and `report_diff`.
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonReport {
+    summary: ScanSummary,
+    results: Vec<JsonFinding>,
+}
+
+/// A result as written to JSON, with its computed severity alongside
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonFinding {
+    #[serde(flatten)]
+    result: ScanResult,
+    #[serde(default, skip_deserializing)]
+    severity: Option<Severity>,
+    #[serde(default, skip_deserializing)]
+    finding_id: Option<String>,
+    /// OWASP API Top 10 categories, empty for results that report no issue
+    #[serde(default, skip_deserializing, skip_serializing_if = "Vec::is_empty")]
+    owasp: Vec<&'static str>,
+}
+
+/// Export results as a JSON report
+pub fn export_json(results: &[ScanResult]) -> Result<String, std::io::Error> {
+    export_json_with_config(results, &ReportConfig::default())
+}
+
+/// Write the JSON report and return its path
+pub fn export_json_with_config(
+    results: &[ScanResult],
+    config: &ReportConfig,
+) -> Result<String, std::io::Error> {
+    let filename = config.report_path("json")?;
+    let file = File::create(&filename)?;
+    let report = JsonReport {
+        summary: config.summary_for(results),
+        results: results
+            .iter()
+            .map(|r| JsonFinding {
+                result: r.clone(),
+                severity: Some(severity_of(r)),
+                finding_id: Some(finding_id(r, config.case_insensitive_paths)),
+                owasp: owasp_categories(r),
+            })
+            .collect(),
+    };
+    serde_json::to_writer_pretty(file, &report)?;
+
     Ok(filename)
 }
 
-pub fn export_pdf(_results: &[(String, String, String)]) {
+/// Load a report previously written by `export_json`
+pub fn load_json_report(path: &str) -> Result<Vec<ScanResult>, String> {
+    let data =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    // Reports written before the summary block are a bare array of results
+    serde_json::from_str::<JsonReport>(&data)
+        .map(|report| report.results.into_iter().map(|f| f.result).collect())
+        .or_else(|_| serde_json::from_str(&data))
+        .map_err(|e| format!("Failed to parse report {}: {}", path, e))
+}
+
+pub fn export_pdf(_results: &[ScanResult]) {
     // Stub: PDF export not implemented
 }
+
+/// A finding whose verdict differs between two reports
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VerdictChange {
+    pub method: String,
+    pub finding: String, // operation name when known, otherwise the URL
+    pub old_verdict: Option<String>, // None = endpoint not present in the old report
+    pub new_verdict: Option<String>, // None = endpoint not present in the new report
+}
+
+/// Differences between two scan reports, keyed by method + path template
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReportDiff {
+    /// Endpoints VULNERABLE in the new report but not in the old one
+    pub newly_vulnerable: Vec<VerdictChange>,
+    /// Endpoints VULNERABLE in the old report and rescanned with another verdict
+    pub fixed: Vec<VerdictChange>,
+    /// Any other verdict change (e.g., SECURE -> UNCERTAIN)
+    pub changed: Vec<VerdictChange>,
+}
+
+impl ReportDiff {
+    pub fn is_empty(&self) -> bool {
+        self.newly_vulnerable.is_empty() && self.fixed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Rank verdict classes so the worst outcome represents an endpoint
+fn verdict_rank(class: &str) -> u8 {
+    match class {
+        "VULNERABLE" => 3,
+        "UNCERTAIN" => 2,
+        "ERROR" => 1,
+        _ => 0,
+    }
+}
+
+/// Whether a verdict class is at least as severe as `min_class`
+/// (VULNERABLE > UNCERTAIN > ERROR > SECURE)
+pub fn meets_min_verdict(class: &str, min_class: &str) -> bool {
+    verdict_rank(class) >= verdict_rank(min_class)
+}
+
+/// Keep only results at least as severe as `min_class`, so every report
+/// format shows the same subset
+pub fn filter_min_verdict(results: &[ScanResult], min_class: &str) -> Vec<ScanResult> {
+    results
+        .iter()
+        .filter(|r| meets_min_verdict(r.verdict_class(), min_class))
+        .cloned()
+        .collect()
+}
+
+/// Collapse results to one (finding name, verdict class) per (method, path
+/// template), keeping the worst verdict. Operation names are only display
+/// text: two operations may share a summary, so they never key the diff.
+fn worst_verdicts(results: &[ScanResult]) -> BTreeMap<(String, String), (String, String)> {
+    let mut map: BTreeMap<(String, String), (String, String)> = BTreeMap::new();
+    for r in results {
+        let class = r.verdict_class().to_string();
+        let template = path_template(&r.url, &r.triggering_values, false);
+        let path = template.split('?').next().unwrap_or_default().to_string();
+        let entry = map
+            .entry((r.method.to_uppercase(), path))
+            .or_insert_with(|| (r.finding_name().to_string(), class.clone()));
+        if verdict_rank(&class) > verdict_rank(&entry.1) {
+            entry.1 = class;
+        }
+    }
+    map
+}
+
+/// Compare two reports and classify what changed between scans
+pub fn report_diff(old: &[ScanResult], new: &[ScanResult]) -> ReportDiff {
+    let old_map = worst_verdicts(old);
+    let new_map = worst_verdicts(new);
+    let mut diff = ReportDiff::default();
+
+    let mut keys: Vec<&(String, String)> = old_map.keys().chain(new_map.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    for key in keys {
+        let old_entry = old_map.get(key);
+        let new_entry = new_map.get(key);
+        let old_verdict = old_entry.map(|(_, class)| class);
+        let new_verdict = new_entry.map(|(_, class)| class);
+        if old_verdict == new_verdict {
+            continue;
+        }
+        let finding = new_entry.or(old_entry).map(|(name, _)| name.clone());
+
+        let change = VerdictChange {
+            method: key.0.clone(),
+            finding: finding.unwrap_or_default(),
+            old_verdict: old_verdict.cloned(),
+            new_verdict: new_verdict.cloned(),
+        };
+        let was_vulnerable = old_verdict.is_some_and(|v| v == "VULNERABLE");
+        let is_vulnerable = new_verdict.is_some_and(|v| v == "VULNERABLE");
+
+        if is_vulnerable {
+            diff.newly_vulnerable.push(change);
+        } else if was_vulnerable && new_verdict.is_some() {
+            diff.fixed.push(change);
+        } else {
+            diff.changed.push(change);
+        }
+    }
+
+    diff
+}
@@ -0,0 +1,540 @@
+// Attack request planning for Doppel
+// Decides where each parameter goes (path, query, header, cookie, body) and
+// what the request carries, without a client or a live server; the plan is
+// turned into a reqwest request as the last step
+
+use crate::auth::AuthStrategy;
+use crate::models::{ArrayStyle, Endpoint, Method, ParameterLocation};
+use crate::parameters::{
+    parameter_location, render_header, BodyTemplates, DetectedParameter, VICTIM_ID_PLACEHOLDER,
+};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// How attack requests are assembled
+pub struct RequestOptions {
+    /// Also fill optional (non-required) fields with spec example values
+    pub send_optional_fields: bool,
+    /// Methods that carry a JSON body; body fields of other methods go to the query string
+    pub body_methods: Vec<Method>,
+    /// Origin header sent with every attack request, to test CORS reflection
+    pub origin: Option<String>,
+    /// User-supplied bodies that replace the schema-derived body
+    pub body_templates: BodyTemplates,
+    /// Headers added to every attack request (--header); values may hold
+    /// `{{VICTIM_ID}}`, `{{ATTACKER_ID}}` and body template variables
+    pub headers: Vec<(String, String)>,
+}
+
+impl Default for RequestOptions {
+    fn default() -> Self {
+        Self {
+            send_optional_fields: false,
+            body_methods: vec![Method::POST, Method::PUT, Method::PATCH],
+            origin: None,
+            body_templates: BodyTemplates::default(),
+            headers: Vec::new(),
+        }
+    }
+}
+
+/// One attack request, fully decided but not yet sent
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestPlan {
+    pub method: Method,
+    /// Target URL with path parameters substituted, without the query string
+    pub url: String,
+    pub path_params: BTreeMap<String, String>,
+    /// Ordered pairs: a key may repeat (`?id=1&id=2`)
+    pub query_params: Vec<(String, String)>,
+    /// Extra headers, including Origin and a combined Cookie header
+    pub headers: Vec<(String, String)>,
+    /// JSON body, if the request carries one
+    pub body: Option<Value>,
+    /// Whether credentials are attached; false for public operations (`security: []`)
+    pub authenticated: bool,
+}
+
+/// Query string form of a JSON value
+pub fn scalar_string(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+/// Query values for a parameter: arrays expand per their declared style
+/// (repeated keys by default), scalars are a single value
+pub fn query_values(value: Value, style: Option<ArrayStyle>) -> Vec<String> {
+    let items: Vec<String> = match value {
+        Value::Array(items) => items.into_iter().map(scalar_string).collect(),
+        scalar => vec![scalar_string(scalar)],
+    };
+    match style {
+        Some(ArrayStyle::Delimited(sep)) => vec![items.join(&sep.to_string())],
+        Some(ArrayStyle::Repeated) | None => items,
+    }
+}
+
+/// Set a dotted field path ("address.id") in a JSON object, creating the
+/// objects along the way and keeping their other fields
+fn insert_nested(fields: &mut serde_json::Map<String, Value>, path: &str, value: Value) {
+    match path.split_once('.') {
+        Some((head, rest)) if !head.is_empty() && !rest.is_empty() => {
+            let child = fields
+                .entry(head.to_string())
+                .or_insert_with(|| Value::Object(serde_json::Map::new()));
+            if !child.is_object() {
+                *child = Value::Object(serde_json::Map::new());
+            }
+            if let Value::Object(child) = child {
+                insert_nested(child, rest, value);
+            }
+        }
+        _ => {
+            fields.insert(path.to_string(), value);
+        }
+    }
+}
+
+/// Query pairs in the collection's order, whatever order they were placed
+/// in: the literal URL's pair order when known, so interleaved repeated keys
+/// (`?id=1&sort=asc&id=2`) stay interleaved, then the declared parameter
+/// order for the rest. Each key's values keep their relative order.
+fn order_query_pairs(
+    mut pairs: Vec<(String, String)>,
+    endpoint: &Endpoint,
+) -> Vec<(String, String)> {
+    let names = endpoint.param_names();
+    pairs.sort_by_key(|(key, _)| {
+        names
+            .iter()
+            .position(|n| n == key || n.strip_prefix("body.") == Some(key.as_str()))
+            .unwrap_or(usize::MAX)
+    });
+    let mut ordered = Vec::with_capacity(pairs.len());
+    for key in &endpoint.query_order {
+        if let Some(i) = pairs.iter().position(|(k, _)| k == key) {
+            ordered.push(pairs.remove(i));
+        }
+    }
+    ordered.extend(pairs);
+    ordered
+}
+
+impl RequestPlan {
+    /// Plan the request for one endpoint with `value` injected into every target parameter
+    pub fn build(
+        endpoint: &Endpoint,
+        base_path: &str,
+        targets: &[DetectedParameter],
+        value: &str,
+        options: &RequestOptions,
+    ) -> RequestPlan {
+        // Place each parameter in its slot - only test high-risk ones
+        let mut path_params = BTreeMap::new();
+        let mut query_params: Vec<(String, String)> = Vec::new();
+        let mut header_params = BTreeMap::new();
+        let mut cookie_params = BTreeMap::new();
+        // A body template carries the id itself and is sent whatever the method
+        let body_template = options.body_templates.render(endpoint, value);
+        let takes_body = options.body_methods.contains(&endpoint.method);
+        // The collection's own example body already passes the server's
+        // validation, so it is the template; only target fields are replaced
+        let example_body = endpoint.example_body.as_ref().filter(|_| takes_body);
+        let mut body_params = match example_body {
+            Some(Value::Object(fields)) => fields.clone(),
+            _ => serde_json::Map::new(),
+        };
+        let mut place = |name: &str, value: Value| {
+            let location = parameter_location(endpoint, name);
+            // `content: application/json` parameters carry JSON text
+            let value = match endpoint.parameter(name) {
+                Some(p) if p.is_json_encoded() && location != ParameterLocation::Body => {
+                    Value::String(value.to_string())
+                }
+                _ => value,
+            };
+            if location == ParameterLocation::Body && body_template.is_some() {
+                return;
+            }
+            if location == ParameterLocation::Body && takes_body {
+                // Body fields are named "body.<field>", nested ones "body.<a>.<b>"
+                let field = name.strip_prefix("body.").unwrap_or(name);
+                insert_nested(&mut body_params, field, value);
+                return;
+            }
+            match location {
+                ParameterLocation::Query => {
+                    let style = endpoint.parameter(name).and_then(|p| p.array_style);
+                    for v in query_values(value, style) {
+                        query_params.push((name.to_string(), v));
+                    }
+                }
+                // Body fields of methods without a body go to the query string
+                ParameterLocation::Body => {
+                    let field = name.strip_prefix("body.").unwrap_or(name);
+                    query_params.push((field.to_string(), scalar_string(value)));
+                }
+                ParameterLocation::Path => {
+                    path_params.insert(name.to_string(), scalar_string(value));
+                }
+                ParameterLocation::Header => {
+                    header_params.insert(name.to_string(), scalar_string(value));
+                }
+                ParameterLocation::Cookie => {
+                    cookie_params.insert(name.to_string(), scalar_string(value));
+                }
+            }
+        };
+
+        // Only test high-risk parameters identified by smart detection
+        for detected_param in targets {
+            place(&detected_param.name, Value::String(value.to_string()));
+        }
+
+        // Fill non-target fields with plausible spec values so input
+        // validation doesn't reject the request before authorization runs
+        for (p, example) in &endpoint.example_values {
+            if targets.iter().any(|d| &d.name == p) {
+                continue;
+            }
+            // Fields the example body provides keep its value
+            if example_body.is_some() && parameter_location(endpoint, p) == ParameterLocation::Body
+            {
+                continue;
+            }
+            // Optional fields are left out by default to avoid their validation errors
+            if !options.send_optional_fields && !endpoint.is_param_required(p) {
+                continue;
+            }
+            place(p, example.clone());
+        }
+
+        let query_params = order_query_pairs(query_params, endpoint);
+
+        // Replace path parameters in URL
+        let mut url = base_path.to_string();
+        for (param_name, param_value) in &path_params {
+            url = url.replace(&format!("{{{}}}", param_name), param_value);
+        }
+
+        let mut headers: Vec<(String, String)> = Vec::new();
+        if let Some(origin) = &options.origin {
+            headers.push(("Origin".to_string(), origin.clone()));
+        }
+        if !options.headers.is_empty() {
+            let mut variables = options.body_templates.variables.clone();
+            variables.insert(VICTIM_ID_PLACEHOLDER.to_string(), value.to_string());
+            for (name, template) in &options.headers {
+                headers.push((name.clone(), render_header(template, &variables)));
+            }
+        }
+        headers.extend(header_params);
+        // Cookie parameters travel as a single Cookie header
+        if !cookie_params.is_empty() {
+            let cookie = cookie_params
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect::<Vec<_>>()
+                .join("; ");
+            headers.push(("Cookie".to_string(), cookie));
+        }
+
+        // A non-object example body is sent as is
+        let body = if body_template.is_some() {
+            body_template
+        } else if !body_params.is_empty() {
+            Some(Value::Object(body_params))
+        } else {
+            example_body.cloned()
+        };
+
+        RequestPlan {
+            method: endpoint.method.clone(),
+            url,
+            path_params,
+            query_params,
+            headers,
+            body,
+            authenticated: !endpoint.is_public(),
+        }
+    }
+
+    /// The reqwest request for this plan, with credentials applied unless the
+    /// operation is public
+    pub fn into_request(
+        &self,
+        client: &reqwest::Client,
+        auth: &dyn AuthStrategy,
+    ) -> Result<reqwest::RequestBuilder, String> {
+        let method = self.method.to_string();
+        let http_method = reqwest::Method::from_bytes(method.as_bytes())
+            .map_err(|e| format!("Invalid HTTP method '{}': {}", method, e))?;
+        let mut req = client.request(http_method, &self.url);
+        if self.authenticated {
+            req = auth.apply_auth(req);
+        }
+        // Array values repeat the key
+        if !self.query_params.is_empty() {
+            req = req.query(&self.query_params);
+        }
+        for (k, v) in &self.headers {
+            req = req.header(k.as_str(), v.as_str());
+        }
+        if let Some(body) = &self.body {
+            req = req.json(body);
+        }
+        Ok(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::StaticTokenAuth;
+    use crate::models::{CollectionParser, Parameter};
+    use crate::parameters::get_high_risk_params;
+    use crate::parsers::PostmanParser;
+    use serde_json::json;
+
+    fn sent_query(plan: &RequestPlan) -> Option<String> {
+        let auth = StaticTokenAuth {
+            token: "t".to_string(),
+        };
+        let request = plan
+            .into_request(&reqwest::Client::new(), &auth)
+            .unwrap()
+            .build()
+            .unwrap();
+        request.url().query().map(str::to_string)
+    }
+
+    #[test]
+    fn test_array_query_values_follow_style() {
+        let value = json!(["a", 2]);
+        assert_eq!(query_values(value.clone(), None), vec!["a", "2"]);
+        assert_eq!(
+            query_values(value, Some(ArrayStyle::Delimited(','))),
+            vec!["a,2"]
+        );
+        assert_eq!(
+            query_values(Value::String("42".to_string()), Some(ArrayStyle::Repeated)),
+            vec!["42"]
+        );
+    }
+
+    #[test]
+    fn test_array_query_params_repeat_the_key() {
+        let mut endpoint = Endpoint::new(Method::GET, "/api/orders".to_string(), None, vec![]);
+        endpoint.add_parameter(Parameter {
+            array_style: Some(ArrayStyle::Repeated),
+            ..Parameter::new("userIds", ParameterLocation::Query)
+        });
+        endpoint.add_parameter(Parameter {
+            array_style: Some(ArrayStyle::Repeated),
+            required: true,
+            ..Parameter::new("status", ParameterLocation::Query)
+        });
+        endpoint
+            .example_values
+            .insert("status".to_string(), json!(["open", "paid"]));
+        // Only userIds is targeted; status is filled from its example
+        let mut targets = get_high_risk_params(&endpoint, 0);
+        targets.retain(|p| p.name == "userIds");
+
+        let plan = RequestPlan::build(
+            &endpoint,
+            "http://api/api/orders",
+            &targets,
+            "42",
+            &RequestOptions::default(),
+        );
+        assert_eq!(
+            sent_query(&plan).as_deref(),
+            Some("userIds=42&status=open&status=paid")
+        );
+    }
+
+    #[test]
+    fn test_duplicate_query_params_keep_collection_order() {
+        let collection = r#"{"item": [{"request": {"method": "GET", "url": "http://api/api/orders?id=1&sort=asc&id=2"}}]}"#;
+        let endpoint = PostmanParser.parse_content(collection).unwrap().remove(0);
+        assert_eq!(endpoint.query_order, vec!["id", "sort", "id"]);
+
+        let plan = RequestPlan::build(
+            &endpoint,
+            "http://api/api/orders",
+            &[],
+            "42",
+            &RequestOptions::default(),
+        );
+        assert_eq!(sent_query(&plan).as_deref(), Some("id=1&sort=asc&id=2"));
+
+        // A targeted key takes the first slot of its pairs
+        let mut targets = get_high_risk_params(&endpoint, 0);
+        targets.retain(|p| p.name == "id");
+        let plan = RequestPlan::build(
+            &endpoint,
+            "http://api/api/orders",
+            &targets,
+            "42",
+            &RequestOptions::default(),
+        );
+        assert_eq!(sent_query(&plan).as_deref(), Some("id=42&sort=asc"));
+    }
+
+    #[test]
+    fn test_plan_places_each_parameter() {
+        let mut endpoint = Endpoint::new(
+            Method::POST,
+            "/orgs/{orgId}/invoices".to_string(),
+            None,
+            vec!["orgId".to_string(), "body.note".to_string()],
+        );
+        endpoint.example_body = Some(json!({"note": "hi", "amount": 5}));
+        let targets = get_high_risk_params(&endpoint, 50);
+        let options = RequestOptions {
+            origin: Some("https://evil.example".to_string()),
+            ..RequestOptions::default()
+        };
+
+        let plan = RequestPlan::build(&endpoint, &endpoint.path, &targets, "42", &options);
+        assert_eq!(plan.url, "/orgs/42/invoices");
+        assert_eq!(
+            plan.path_params.get("orgId").map(String::as_str),
+            Some("42")
+        );
+        assert!(plan.query_params.is_empty());
+        assert_eq!(plan.body, Some(json!({"note": "hi", "amount": 5})));
+        assert_eq!(
+            plan.headers,
+            vec![("Origin".to_string(), "https://evil.example".to_string())]
+        );
+        assert!(plan.authenticated);
+    }
+
+    #[test]
+    fn test_header_placeholders_are_filled_per_request() {
+        let endpoint = Endpoint::new(
+            Method::GET,
+            "/documents/{docId}".to_string(),
+            None,
+            vec!["docId".to_string()],
+        );
+        let targets = get_high_risk_params(&endpoint, 50);
+        let mut options = RequestOptions {
+            headers: vec![
+                ("X-Resource-Id".to_string(), "{{victim_id}}".to_string()),
+                (
+                    "X-Trace".to_string(),
+                    "{{ATTACKER_ID}}-{{unknown}}".to_string(),
+                ),
+            ],
+            ..RequestOptions::default()
+        };
+        options
+            .body_templates
+            .variables
+            .insert("ATTACKER_ID".to_string(), "7".to_string());
+
+        let plan = RequestPlan::build(&endpoint, &endpoint.path, &targets, "42", &options);
+        assert_eq!(
+            plan.headers,
+            vec![
+                ("X-Resource-Id".to_string(), "42".to_string()),
+                ("X-Trace".to_string(), "7-{{unknown}}".to_string()),
+            ]
+        );
+        let next = RequestPlan::build(&endpoint, &endpoint.path, &targets, "43", &options);
+        assert_eq!(next.headers[0].1, "43");
+    }
+
+    #[test]
+    fn test_json_content_parameters_are_encoded() {
+        let mut endpoint = Endpoint::new(Method::GET, "/orders".to_string(), None, vec![]);
+        endpoint.add_parameter(Parameter {
+            content_type: Some("application/json".to_string()),
+            required: true,
+            ..Parameter::new("filter", ParameterLocation::Query)
+        });
+        endpoint
+            .example_values
+            .insert("filter".to_string(), json!({"status": "open"}));
+        endpoint.add_parameter(Parameter::new("userId", ParameterLocation::Query));
+
+        let mut targets = get_high_risk_params(&endpoint, 50);
+        targets.retain(|p| p.name == "userId");
+        let plan = RequestPlan::build(
+            &endpoint,
+            &endpoint.path,
+            &targets,
+            "42",
+            &RequestOptions::default(),
+        );
+        assert_eq!(
+            plan.query_params,
+            vec![
+                ("filter".to_string(), r#"{"status":"open"}"#.to_string()),
+                ("userId".to_string(), "42".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_body_fields_build_the_object_tree() {
+        let mut endpoint = Endpoint::new(
+            Method::PUT,
+            "/orders".to_string(),
+            None,
+            vec!["body.address.id".to_string()],
+        );
+        endpoint.example_body = Some(json!({"address": {"street": "Main St"}, "note": "x"}));
+        let targets = get_high_risk_params(&endpoint, 0);
+
+        let plan = RequestPlan::build(
+            &endpoint,
+            &endpoint.path,
+            &targets,
+            "victim",
+            &RequestOptions::default(),
+        );
+        assert_eq!(
+            plan.body,
+            Some(json!({"address": {"id": "victim", "street": "Main St"}, "note": "x"}))
+        );
+
+        endpoint.example_body = None;
+        let plan = RequestPlan::build(
+            &endpoint,
+            &endpoint.path,
+            &targets,
+            "victim",
+            &RequestOptions::default(),
+        );
+        assert_eq!(plan.body, Some(json!({"address": {"id": "victim"}})));
+    }
+
+    #[test]
+    fn test_body_fields_of_get_go_to_the_query() {
+        let endpoint = Endpoint::new(
+            Method::GET,
+            "/search".to_string(),
+            None,
+            vec!["body.userId".to_string()],
+        );
+        let targets = get_high_risk_params(&endpoint, 50);
+        let plan = RequestPlan::build(
+            &endpoint,
+            "https://api.example.com/search",
+            &targets,
+            "7",
+            &RequestOptions::default(),
+        );
+        assert_eq!(
+            plan.query_params,
+            vec![("userId".to_string(), "7".to_string())]
+        );
+        assert_eq!(plan.body, None);
+    }
+}
@@ -5,6 +5,20 @@
 //
 // Soft fails: Server returns 200 OK but includes error message
 // Binary responses: Non-JSON data (images, files, etc.)
+// Info disclosure: stack traces and SQL errors, whatever the verdict
+// Auth status: a rejected token (401) versus an object-level denial (403)
+//
+// Analyses implement `ResponseAnalyzer` and run as a pipeline over every
+// response once its verdict is decided; each finding is appended to the
+// verdict as a " | note" annotation.
+
+use crate::cors::CorsAnalyzer;
+use crate::verdict::{is_request_shape_mismatch, Verdict};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fmt;
 
 /// Soft-fail detection keywords (case-insensitive)
 const SOFT_FAIL_KEYWORDS: &[&str] = &[
@@ -22,6 +36,206 @@ const SOFT_FAIL_KEYWORDS: &[&str] = &[
     "not permitted",
 ];
 
+/// Anti-XSSI guards some APIs put in front of JSON responses
+const XSSI_PREFIXES: &[&str] = &[")]}',", ")]}'", "while(1);", "for(;;);", "{} &&"];
+
+lazy_static! {
+    // JSONP: optional /**/ guard, callback name, then the payload in parentheses
+    static ref JSONP_WRAPPER: Regex =
+        Regex::new(r"(?s)^(?:/\*\*/)?\s*[A-Za-z_$][\w$.]*\s*\((.*)\)\s*;?$").unwrap();
+
+    // Stack traces and database errors, labelled by what they give away
+    static ref DISCLOSURE_SIGNATURES: Vec<(&'static str, Regex)> = vec![
+        ("Python traceback", Regex::new(r"Traceback \(most recent call last\)").unwrap()),
+        ("Java stack trace", Regex::new(r"Exception in thread|\bat (?:com|org|java|javax|sun)\.[\w$.]+\(").unwrap()),
+        (".NET stack trace", Regex::new(r"System\.\w+Exception|\bat \w+(?:\.\w+)+\(.*\) in .*:line \d+").unwrap()),
+        ("Node.js stack trace", Regex::new(r"\bat .+ \((?:/|[A-Za-z]:\\|node:)[^)]*:\d+:\d+\)").unwrap()),
+        ("PHP error", Regex::new(r"(?:Fatal error|Warning|Parse error): .* on line \d+").unwrap()),
+        ("SQL error", Regex::new(r"(?i)SQLSTATE\[|you have an error in your SQL syntax|ORA-\d{5}|PG::\w+Error|syntax error at or near|unclosed quotation mark|SQLite3?::|sqlite3\.OperationalError").unwrap()),
+    ];
+}
+
+/// Strip XSSI prefixes (`)]}',`, `while(1);`, ...) and JSONP callback
+/// wrappers so the JSON payload underneath can be parsed
+pub fn strip_json_guards(body: &str) -> &str {
+    let mut trimmed = body.trim();
+    for prefix in XSSI_PREFIXES {
+        if let Some(rest) = trimmed.strip_prefix(prefix) {
+            trimmed = rest.trim_start();
+            break;
+        }
+    }
+    match JSONP_WRAPPER.captures(trimmed).and_then(|c| c.get(1)) {
+        Some(payload) => payload.as_str().trim(),
+        None => trimmed,
+    }
+}
+
+/// Parse a response body as JSON, seeing through XSSI prefixes and JSONP.
+/// Newline-delimited JSON comes back as an array of its records.
+pub fn parse_json_body(body: &str) -> Option<Value> {
+    serde_json::from_str(body)
+        .ok()
+        .or_else(|| serde_json::from_str(strip_json_guards(body)).ok())
+        .or_else(|| parse_ndjson(body).map(Value::Array))
+}
+
+/// Records of a newline-delimited JSON (NDJSON) body: at least two lines,
+/// each a JSON object or array. Blank lines are skipped.
+pub fn parse_ndjson(body: &str) -> Option<Vec<Value>> {
+    let records = body
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str::<Value>(line)
+                .ok()
+                .filter(|v| v.is_object() || v.is_array())
+        })
+        .collect::<Option<Vec<Value>>>()?;
+    (records.len() >= 2).then_some(records)
+}
+
+/// Top-level field names of a JSON object response; two responses with the
+/// same shape are the same kind of resource. None for non-object bodies.
+pub fn json_shape(body: &str) -> Option<BTreeSet<String>> {
+    match parse_json_body(body)? {
+        Value::Object(map) => Some(map.keys().cloned().collect()),
+        _ => None,
+    }
+}
+
+/// Everything an analyzer may inspect about one attack response
+#[derive(Debug, Clone, Copy)]
+pub struct ResponseContext<'a> {
+    pub method: &'a str,
+    pub url: &'a str,
+    pub status: u16,
+    pub body: &'a str,
+    pub verdict: Verdict,
+    pub attacker_id: Option<&'a str>,
+    pub victim_id: Option<&'a str>,
+    /// Response headers as name/value pairs
+    pub headers: &'a [(String, String)],
+    /// Origin header the request was sent with, if any
+    pub request_origin: Option<&'a str>,
+}
+
+/// Annotation produced by an analyzer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub note: String,
+}
+
+impl Finding {
+    pub fn new(note: impl Into<String>) -> Self {
+        Finding { note: note.into() }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.note)
+    }
+}
+
+/// A pluggable analysis run over each response after its verdict is decided
+pub trait ResponseAnalyzer {
+    fn analyze(&self, ctx: &ResponseContext) -> Option<Finding>;
+}
+
+/// Flags error wording in successful-looking responses and binary bodies
+pub struct SoftFailAnalyzer;
+
+impl ResponseAnalyzer for SoftFailAnalyzer {
+    fn analyze(&self, ctx: &ResponseContext) -> Option<Finding> {
+        analyze_response_soft_fails(ctx.body).map(Finding::new)
+    }
+}
+
+/// Explains 405/415 responses: the scan's method or content type is wrong for
+/// the endpoint, so the result says nothing about authorization
+pub struct RequestShapeAnalyzer;
+
+impl ResponseAnalyzer for RequestShapeAnalyzer {
+    fn analyze(&self, ctx: &ResponseContext) -> Option<Finding> {
+        if !is_request_shape_mismatch(ctx.status) {
+            return None;
+        }
+        let reason = if ctx.status == 405 {
+            "method not allowed"
+        } else {
+            "unsupported content type"
+        };
+        Some(Finding::new(format!(
+            "Request-shape mismatch: HTTP {} {}, check the scan's method/content-type",
+            ctx.status, reason
+        )))
+    }
+}
+
+/// Note on 401 responses: the attacker token itself was refused, so the
+/// request never reached the object-level check and its SECURE proves nothing
+pub const NOT_AUTHENTICATED_NOTE: &str = "Not authenticated: HTTP 401, token not accepted";
+
+/// Tells a rejected token (401) apart from a genuine authorization denial
+/// (403); both are SECURE, but only the second says the check works. A 403
+/// gets no note: SECURE with status 403 already says so.
+pub struct AuthStatusAnalyzer;
+
+impl ResponseAnalyzer for AuthStatusAnalyzer {
+    fn analyze(&self, ctx: &ResponseContext) -> Option<Finding> {
+        match ctx.status {
+            401 => Some(Finding::new(NOT_AUTHENTICATED_NOTE)),
+            _ => None,
+        }
+    }
+}
+
+/// Flags responses that leak stack traces or database errors: an
+/// information-disclosure finding independent of the BOLA verdict
+pub struct InfoDisclosureAnalyzer;
+
+impl ResponseAnalyzer for InfoDisclosureAnalyzer {
+    fn analyze(&self, ctx: &ResponseContext) -> Option<Finding> {
+        detect_info_disclosure(ctx.body)
+            .map(|kind| Finding::new(format!("Info disclosure: {}", kind)))
+    }
+}
+
+/// Name of the first stack-trace or SQL-error signature found in a body
+pub fn detect_info_disclosure(body: &str) -> Option<&'static str> {
+    DISCLOSURE_SIGNATURES
+        .iter()
+        .find(|(_, pattern)| pattern.is_match(body))
+        .map(|(kind, _)| *kind)
+}
+
+/// Built-in analyzers, in the order their notes appear in the verdict.
+///
+/// PII analysis is not part of the pipeline: it needs an async round trip to
+/// Ollama and only runs on vulnerable responses, so the scan loop calls it
+/// after the pipeline.
+pub fn default_analyzers(soft_fail_analysis: bool) -> Vec<Box<dyn ResponseAnalyzer>> {
+    let mut analyzers: Vec<Box<dyn ResponseAnalyzer>> = vec![
+        Box::new(RequestShapeAnalyzer),
+        Box::new(AuthStatusAnalyzer),
+        Box::new(InfoDisclosureAnalyzer),
+        Box::new(CorsAnalyzer),
+    ];
+    if soft_fail_analysis {
+        analyzers.push(Box::new(SoftFailAnalyzer));
+    }
+    analyzers
+}
+
+/// Run every analyzer over a response and collect their findings
+pub fn run_analyzers(
+    analyzers: &[Box<dyn ResponseAnalyzer>],
+    ctx: &ResponseContext,
+) -> Vec<Finding> {
+    analyzers.iter().filter_map(|a| a.analyze(ctx)).collect()
+}
+
 /// Analyze the response body for soft-fail heuristics and binary detection.
 ///
 /// Returns Some(description) if a soft-fail or binary is detected, None otherwise.
@@ -72,7 +286,7 @@ fn is_likely_binary(body: &str) -> bool {
 
 /// Check if response body appears to be structured data (JSON, XML, HTML)
 fn is_structured_data(body: &str) -> bool {
-    let trimmed = body.trim();
+    let trimmed = strip_json_guards(body);
     if trimmed.is_empty() {
         return true; // Empty is technically structured (valid JSON: "")
     }
@@ -94,6 +308,15 @@ fn is_structured_data(body: &str) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_json_shape() {
+        let shape = json_shape(r#"{"id": 7, "email": "a@b.c"}"#).unwrap();
+        assert_eq!(shape, json_shape(r#"{"email": "x@y.z", "id": 8}"#).unwrap());
+        assert_ne!(Some(shape), json_shape(r#"{"error": "not found"}"#));
+        assert_eq!(json_shape("[1, 2]"), None);
+        assert_eq!(json_shape("<html></html>"), None);
+    }
+
     #[test]
     fn test_soft_fail_case_insensitive() {
         // Lowercase
@@ -160,6 +383,128 @@ mod tests {
         assert!(is_structured_data(""));
     }
 
+    fn context(body: &str) -> ResponseContext<'_> {
+        ResponseContext {
+            method: "GET",
+            url: "http://api/users/1",
+            status: 200,
+            body,
+            verdict: Verdict::Uncertain,
+            attacker_id: None,
+            victim_id: Some("1"),
+            headers: &[],
+            request_origin: None,
+        }
+    }
+
+    #[test]
+    fn test_pipeline_runs_registered_analyzers() {
+        struct StatusNote;
+        impl ResponseAnalyzer for StatusNote {
+            fn analyze(&self, ctx: &ResponseContext) -> Option<Finding> {
+                Some(Finding::new(format!("HTTP {}", ctx.status)))
+            }
+        }
+
+        let mut analyzers = default_analyzers(true);
+        analyzers.push(Box::new(StatusNote));
+        let findings = run_analyzers(&analyzers, &context(r#"{"error":"denied"}"#));
+        let notes: Vec<String> = findings.iter().map(|f| f.to_string()).collect();
+        assert_eq!(notes, vec!["Soft fail: 'error'", "HTTP 200"]);
+    }
+
+    #[test]
+    fn test_soft_fail_analyzer_can_be_disabled() {
+        let analyzers = default_analyzers(false);
+        assert!(run_analyzers(&analyzers, &context("access denied")).is_empty());
+    }
+
+    #[test]
+    fn test_request_shape_mismatch_note() {
+        let analyzers = default_analyzers(false);
+        let mut ctx = context("");
+        ctx.status = 415;
+        let findings = run_analyzers(&analyzers, &ctx);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0]
+            .note
+            .starts_with("Request-shape mismatch: HTTP 415"));
+    }
+
+    #[test]
+    fn test_auth_status_notes() {
+        let analyzers = default_analyzers(false);
+        let note = |status| {
+            let mut ctx = context("");
+            ctx.status = status;
+            ctx.verdict = Verdict::Secure;
+            run_analyzers(&analyzers, &ctx)
+                .into_iter()
+                .map(|f| f.note)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(note(401), vec![NOT_AUTHENTICATED_NOTE]);
+        assert!(note(403).is_empty());
+        assert!(note(404).is_empty());
+    }
+
+    #[test]
+    fn test_info_disclosure_signatures() {
+        let cases = [
+            (
+                "Traceback (most recent call last):\n  File \"app.py\", line 3",
+                "Python traceback",
+            ),
+            (
+                "java.lang.NullPointerException\n\tat com.acme.OrderService.find(OrderService.java:42)",
+                "Java stack trace",
+            ),
+            (
+                "Error: boom\n    at handler (/srv/app/routes.js:12:5)",
+                "Node.js stack trace",
+            ),
+            (
+                r#"{"error":"SQLSTATE[42000]: Syntax error or access violation"}"#,
+                "SQL error",
+            ),
+            ("ERROR: syntax error at or near \"'\"", "SQL error"),
+        ];
+        for (body, kind) in cases {
+            assert_eq!(detect_info_disclosure(body), Some(kind), "{}", body);
+        }
+        assert_eq!(
+            detect_info_disclosure(r#"{"error":"internal error"}"#),
+            None
+        );
+
+        let mut ctx = context("Traceback (most recent call last):");
+        ctx.status = 500;
+        let notes: Vec<String> = run_analyzers(&default_analyzers(false), &ctx)
+            .iter()
+            .map(|f| f.to_string())
+            .collect();
+        assert_eq!(notes, vec!["Info disclosure: Python traceback"]);
+    }
+
+    #[test]
+    fn test_guarded_json_parses() {
+        let expected = serde_json::json!({"id": "victim"});
+        for body in [
+            ")]}',\n{\"id\":\"victim\"}",
+            "while(1);{\"id\":\"victim\"}",
+            "callback({\"id\":\"victim\"});",
+            "/**/ jQuery.cb_1( {\"id\":\"victim\"} )",
+        ] {
+            assert_eq!(parse_json_body(body), Some(expected.clone()), "{}", body);
+        }
+        assert_eq!(parse_json_body("not json"), None);
+        assert_eq!(parse_ndjson("1\n2"), None);
+        assert_eq!(parse_ndjson("{\"id\":1}"), None);
+        // A guarded long payload is still structured, not a "possible file"
+        let long = format!(")]}}',\n{{\"items\":\"{}\"}}", "x".repeat(80));
+        assert!(analyze_response_soft_fails(&long).is_none());
+    }
+
     #[test]
     fn test_plain_text_detection() {
         // Long unstructured text should be flagged (possible file content)
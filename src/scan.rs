@@ -0,0 +1,1567 @@
+// Scan loop for Doppel
+// Sends the attack requests for each endpoint, decides their verdicts and
+// streams the results as they are decided. The CLI, `verify` and embedders
+// all drive scans through run_scan_stream / run_scan.
+
+use crate::auth::{AuthStrategy, StaticTokenAuth};
+use crate::discovery::{extract_created_ids, resource_type, IdStore, LinkStore};
+use crate::engine::{
+    read_body_capped, CappedBody, HostLimiter, HostRewriter, RequestBudget, RequestDelay,
+};
+use crate::filter::{filter_endpoints, EndpointFilter};
+use crate::models::{Endpoint, Method, ScanResult};
+use crate::mutator::{
+    cap_mutations, mutate_cursor, mutate_param_typed, mutate_slug, mutate_username, IdRange,
+};
+use crate::ollama::{OllamaAnalyzer, PiiOutcome};
+use crate::parameters::{
+    get_parameter_summary, get_target_params, has_object_reference, is_numeric_param,
+    DetectedParameter, ParamType, TargetOverrides,
+};
+use crate::recording::{
+    har_headers, har_request, HarContent, HarResponse, RecordedExchange, Recorder, ScanContext,
+};
+use crate::redact::{redact, RedactionConfig};
+use crate::reporting::meets_min_verdict;
+use crate::request_plan::{RequestOptions, RequestPlan};
+use crate::response_analysis::{
+    json_shape, parse_json_body, run_analyzers, ResponseAnalyzer, ResponseContext,
+};
+use crate::verdict::{
+    contains_id, contains_identity, decide_verdict_with_policy, is_login_redirect,
+    run_verdict_hooks, verdict_confidence, Baseline, FieldWeight, MatchInfo, Verdict,
+    VerdictContext, VerdictHook, VerdictPolicy,
+};
+use futures_util::{stream, Stream, StreamExt};
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Set by `--format json`: stdout carries only the final JSON document
+pub static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Color verdicts in per-request lines: stdout is a terminal and neither
+/// `--no-color` nor `NO_COLOR` is set
+pub static COLOR: AtomicBool = AtomicBool::new(false);
+
+/// A verdict label (with its notes) colored by class when color is on:
+/// red VULNERABLE, green SECURE, yellow UNCERTAIN. Unchanged otherwise.
+pub fn paint_verdict(label: &str) -> String {
+    if !COLOR.load(Ordering::Relaxed) {
+        return label.to_string();
+    }
+    let code = match label.split(['|', ':']).next().unwrap_or("").trim() {
+        "VULNERABLE" => "31",
+        "SECURE" => "32",
+        "UNCERTAIN" => "33",
+        _ => return label.to_string(),
+    };
+    format!("\x1b[{}m{}\x1b[0m", code, label)
+}
+
+/// say! for human-oriented scan output, silenced by `--format json`.
+/// Diagnostics go to stderr with eprintln! and are never silenced.
+#[macro_export]
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if !$crate::scan::QUIET.load(std::sync::atomic::Ordering::Relaxed) {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// Build the request for one endpoint with `value` injected into every target parameter
+fn build_attack_request(
+    client: &reqwest::Client,
+    auth: &dyn AuthStrategy,
+    endpoint: &Endpoint,
+    base_path: &str,
+    high_risk_params: &[DetectedParameter],
+    value: &str,
+    options: &RequestOptions,
+) -> Result<(reqwest::RequestBuilder, String), String> {
+    let plan = RequestPlan::build(endpoint, base_path, high_risk_params, value, options);
+    let req = plan.into_request(client, auth)?;
+    Ok((req, plan.url))
+}
+
+/// What the verdict logic needs from a completed request
+struct Exchange {
+    status: u16,
+    body: String,
+    /// Final URL when the client followed a redirect
+    redirected_to: Option<String>,
+    /// Response headers as name/value pairs
+    headers: Vec<(String, String)>,
+    /// The body was cut at --max-body-bytes; analysis saw only its prefix
+    truncated: bool,
+}
+
+/// Send a request and, when recording, append the exchange to the capture.
+/// Returns the outcome and the latency in milliseconds.
+async fn send_and_record(
+    env: &ScanEnv<'_>,
+    req: reqwest::RequestBuilder,
+    context: ScanContext,
+) -> (Result<Exchange, String>, u64) {
+    let request = match req.build() {
+        Ok(request) => request,
+        Err(e) => return (Err(redact(&e.to_string(), env.redaction)), 0),
+    };
+    let requested_url = request.url().to_string();
+    let har_req = env.recorder.map(|_| har_request(&request));
+
+    // Spaced out before queueing so the pause does not hold a limiter slot
+    env.delay.wait().await;
+
+    // Held until the body is read; latency excludes time spent queued
+    let _permit = env.limiter.acquire(request.url()).await;
+    let started_date_time = chrono::Utc::now().to_rfc3339();
+    let started = Instant::now();
+    let sent = env.client.execute(request).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let (outcome, har_resp) = match sent {
+        Ok(resp) => {
+            let status = resp.status().as_u16();
+            let headers = har_headers(resp.headers());
+            let response_headers = headers
+                .iter()
+                .map(|h| (h.name.clone(), h.value.clone()))
+                .collect();
+            let final_url = resp.url().to_string();
+            let mime_type = resp
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+            // Read response body text once, up to --max-body-bytes
+            let CappedBody {
+                text: body,
+                truncated,
+            } = read_body_capped(resp, env.max_body_bytes).await;
+            let redirected_to = (final_url != requested_url).then_some(final_url);
+            let har_resp = HarResponse {
+                status,
+                headers,
+                content: HarContent {
+                    mime_type,
+                    text: body.clone(),
+                },
+                redirect_url: redirected_to.clone().unwrap_or_default(),
+            };
+            (
+                Ok(Exchange {
+                    status,
+                    body,
+                    redirected_to,
+                    headers: response_headers,
+                    truncated,
+                }),
+                har_resp,
+            )
+        }
+        Err(e) => {
+            let har_resp = HarResponse {
+                status: 0,
+                headers: Vec::new(),
+                content: HarContent {
+                    mime_type: String::new(),
+                    text: e.to_string(),
+                },
+                redirect_url: String::new(),
+            };
+            // Client errors quote the request URL, secrets included
+            (Err(redact(&e.to_string(), env.redaction)), har_resp)
+        }
+    };
+
+    if let (Some(recorder), Some(request)) = (env.recorder, har_req) {
+        let exchange = RecordedExchange {
+            started_date_time,
+            time: latency_ms,
+            request,
+            response: har_resp,
+            context,
+        };
+        let recorded = recorder
+            .lock()
+            .expect("recorder poisoned")
+            .record(&exchange);
+        if let Err(e) = recorded {
+            eprintln!("[WARN] {}", e);
+        }
+    }
+
+    (outcome, latency_ms)
+}
+
+/// Interactive approval of state-changing requests (--confirm-destructive)
+pub struct DestructiveGuard {
+    /// Send without asking (--yes, or answered "all")
+    approve_all: AtomicBool,
+    /// Serializes prompts when endpoints are scanned concurrently
+    prompt: tokio::sync::Mutex<()>,
+}
+
+impl DestructiveGuard {
+    pub fn new(auto_approve: bool) -> Self {
+        Self {
+            approve_all: AtomicBool::new(auto_approve),
+            prompt: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Show a mutating request and ask whether to send it. Anything but
+    /// y/yes/a/all (including a closed stdin) declines.
+    async fn confirm(&self, request: &reqwest::Request) -> bool {
+        let _turn = self.prompt.lock().await;
+        // Prompts go to stderr so they survive --format json
+        eprintln!(
+            "\n⚠️  About to send: {} {}",
+            request.method(),
+            request.url()
+        );
+        if let Some(body) = request.body().and_then(|b| b.as_bytes()) {
+            eprintln!("   Body: {}", String::from_utf8_lossy(body));
+        }
+        if self.approve_all.load(Ordering::SeqCst) {
+            return true;
+        }
+        eprint!("   Send it? [y]es / [n]o / [a]ll remaining: ");
+        let _ = std::io::Write::flush(&mut std::io::stderr());
+        let answer = tokio::task::spawn_blocking(|| {
+            let mut line = String::new();
+            std::io::stdin().read_line(&mut line).map(|_| line)
+        })
+        .await
+        .ok()
+        .and_then(Result::ok)
+        .unwrap_or_default();
+        match answer.trim().to_lowercase().as_str() {
+            "y" | "yes" => true,
+            "a" | "all" => {
+                self.approve_all.store(true, Ordering::SeqCst);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether a request may be sent: non-mutating requests always are; mutating
+/// ones need approval when --confirm-destructive is on
+async fn confirm_send(env: &ScanEnv<'_>, req: &reqwest::RequestBuilder) -> bool {
+    let Some(guard) = env.destructive_guard else {
+        return true;
+    };
+    let Some(request) = req.try_clone().and_then(|r| r.build().ok()) else {
+        return true;
+    };
+    if !is_destructive(request.method()) {
+        return true;
+    }
+    guard.confirm(&request).await
+}
+
+/// Methods that can create, change or delete server-side state
+fn is_destructive(method: &reqwest::Method) -> bool {
+    matches!(method.as_str(), "POST" | "PUT" | "PATCH" | "DELETE")
+}
+
+/// URL to attack for an endpoint path. Paths that are already absolute (from
+/// OpenAPI `servers`) are used as-is unless `force_base_url` is set, in which
+/// case their scheme and host are replaced by `base_url`.
+fn target_url(path: &str, base_url: &str, force_base_url: bool) -> String {
+    let absolute = path.starts_with("http://") || path.starts_with("https://");
+    if !absolute {
+        return format!("{}{}", base_url, path);
+    }
+    if !force_base_url {
+        return path.to_string();
+    }
+    // Keep the server's base path (e.g. /v1) and everything after it
+    let after_scheme = &path[path.find("://").map_or(0, |i| i + 3)..];
+    let rest = after_scheme.find('/').map_or("", |i| &after_scheme[i..]);
+    format!("{}{}", base_url.trim_end_matches('/'), rest)
+}
+
+/// Settings and shared state for scanning endpoints, possibly concurrently
+pub struct ScanEnv<'a> {
+    pub client: &'a reqwest::Client,
+    pub auth: &'a StaticTokenAuth,
+    pub analyzers: &'a [Box<dyn ResponseAnalyzer>],
+    pub limiter: &'a HostLimiter,
+    pub recorder: Option<&'a Mutex<Recorder>>,
+    /// Asks before mutating requests, with --confirm-destructive
+    pub destructive_guard: Option<&'a DestructiveGuard>,
+    /// Ids harvested from create responses, with --discover-ids
+    pub discovered_ids: Option<&'a IdStore>,
+    /// Endpoints linked from responses, with --follow-links
+    pub links: Option<&'a LinkStore>,
+    /// Link hops followed after the first round (--max-link-depth)
+    pub max_link_depth: u64,
+    /// Filters linked endpoints must pass, like the collection's own
+    pub endpoint_filter: &'a EndpointFilter,
+    /// Endpoints scanned at once (--concurrency)
+    pub concurrency: usize,
+    /// Endpoints found missing before fuzzing, with --preflight
+    pub unreachable: Option<&'a Mutex<Vec<String>>>,
+    /// Id range swept instead of the mutation set, with --enumerate
+    pub enumerate: Option<IdRange>,
+    /// Ids the --enumerate sweep could read
+    pub accessible_ids: Option<&'a Mutex<Vec<String>>>,
+    pub interrupted: &'a AtomicBool,
+    /// Cap on requests sent, with --max-requests
+    pub budget: Option<&'a RequestBudget>,
+    pub request_options: &'a RequestOptions,
+    /// Secrets masked in printed and reported URLs
+    pub redaction: &'a RedactionConfig,
+    /// Response bytes read per request, with --max-body-bytes
+    pub max_body_bytes: usize,
+    /// Pause before each request, with --delay-ms/--jitter-ms
+    pub delay: RequestDelay,
+    pub base_url: &'a str,
+    /// Host retargeting, with --map-host
+    pub host_map: &'a HostRewriter,
+    pub victim_id: &'a str,
+    pub attacker_id: Option<&'a str>,
+    pub min_risk_score: u8,
+    /// --target-param / --target-params overrides of the fuzzed parameter
+    pub target_overrides: &'a TargetOverrides,
+    /// Target-specific privileged ids tried with the mutations (--privileged-ids)
+    pub privileged_ids: &'a [String],
+    /// Values tried per parameter, best guesses first (--max-mutations)
+    pub max_mutations: Option<usize>,
+    pub min_verdict: Option<&'a str>,
+    /// VULNERABLE verdicts weaker than this are reported as UNCERTAIN (--min-confidence)
+    pub min_confidence: Option<u8>,
+    pub verdict_policy: VerdictPolicy,
+    /// Custom rules refining each verdict, e.g. --secure-marker
+    pub verdict_hooks: &'a [Box<dyn VerdictHook>],
+    pub show_param_analysis: bool,
+    /// Print only the final summary (--summary-only)
+    pub summary_only: bool,
+    /// Print why each verdict was reached (`verify`)
+    pub explain: bool,
+    pub calibrate: bool,
+    /// Also probe mutating endpoints with a made-up id (--probe-mutating)
+    pub probe_mutating: bool,
+    pub mutational_fuzzing: bool,
+    pub force_base_url: bool,
+    /// PII analysis of VULNERABLE JSON responses, with --enable-pii-analysis
+    pub pii: Option<PiiSettings<'a>>,
+    /// High-risk parameters attacked so far, summed over endpoints
+    pub high_risk_params: AtomicUsize,
+}
+
+/// Where and how long to run PII analysis
+pub struct PiiSettings<'a> {
+    pub analyzer: &'a OllamaAnalyzer,
+    /// Per-response limit (--pii-timeout)
+    pub timeout: Duration,
+}
+
+impl ScanEnv<'_> {
+    /// Stop dispatching: Ctrl-C was pressed or the request budget ran out
+    pub fn stopped(&self) -> bool {
+        self.interrupted.load(Ordering::SeqCst) || self.budget.is_some_and(|b| b.truncated())
+    }
+
+    /// Full URL template for an endpoint path, after --map-host
+    fn target(&self, path: &str) -> String {
+        self.host_map
+            .rewrite(&target_url(path, self.base_url, self.force_base_url))
+    }
+
+    /// Claim one request from the budget; always granted without --max-requests
+    fn take_request(&self) -> bool {
+        self.budget.is_none_or(|b| b.try_take())
+    }
+
+    /// Whether a result of this class is printed; results below --min-verdict
+    /// (or all of them, with --summary-only) are hidden but still counted in
+    /// the summary
+    fn shown(&self, class: &str) -> bool {
+        !self.summary_only
+            && self
+                .min_verdict
+                .is_none_or(|min| meets_min_verdict(class, min))
+    }
+}
+
+/// GET endpoints tried by the --own-id positive control before giving up
+const MAX_CONTROL_ENDPOINTS: usize = 3;
+
+/// Positive control: request a resource the attacker owns, which must come
+/// back 200 with the attacker's data. Failure means auth or the base URL is
+/// misconfigured and every SECURE verdict would be meaningless. Returns the
+/// endpoint that passed, or why none did.
+pub async fn positive_control(
+    env: &ScanEnv<'_>,
+    endpoints: &[Endpoint],
+    own_id: &str,
+) -> Result<String, String> {
+    let candidates = endpoints
+        .iter()
+        .filter(|e| e.method == Method::GET)
+        .map(|e| {
+            (
+                e,
+                get_target_params(e, env.min_risk_score, env.target_overrides),
+            )
+        })
+        .filter(|(_, params)| !params.is_empty())
+        .take(MAX_CONTROL_ENDPOINTS);
+
+    let mut failures = Vec::new();
+    for (endpoint, params) in candidates {
+        let base_path = env.target(&endpoint.path);
+        // A candidate that cannot be built is one failure, not the end of the check
+        let (req, url) = match build_attack_request(
+            env.client,
+            env.auth,
+            endpoint,
+            &base_path,
+            &params,
+            own_id,
+            env.request_options,
+        ) {
+            Ok(built) => built,
+            Err(e) => {
+                failures.push(format!("GET {}: {}", base_path, e));
+                continue;
+            }
+        };
+        if !env.take_request() {
+            break;
+        }
+        let context = ScanContext {
+            endpoint: endpoint.path.clone(),
+            victim_id: env.victim_id.to_string(),
+            attacker_id: env.attacker_id.map(str::to_string),
+            injected_value: own_id.to_string(),
+            name: endpoint.description.clone(),
+            deprecated: endpoint.deprecated,
+            risk_score: params.iter().map(|p| p.bola_risk_score).max(),
+            probe: true,
+        };
+        match send_and_record(env, req, context).await.0 {
+            Ok(exchange) if exchange.status != 200 => {
+                failures.push(format!("GET {}: HTTP {}", url, exchange.status));
+            }
+            Ok(exchange) => {
+                let owned = exchange.body.contains(own_id)
+                    || env.attacker_id.is_some_and(|id| exchange.body.contains(id));
+                if owned {
+                    return Ok(format!("GET {}", redact(&url, env.redaction)));
+                }
+                failures.push(format!("GET {}: 200 without the attacker's data", url));
+            }
+            Err(e) => failures.push(format!("GET {}: {}", url, e)),
+        }
+    }
+    if failures.is_empty() {
+        return Err("no GET endpoint with a target parameter to request it from".to_string());
+    }
+    Err(redact(&failures.join("; "), env.redaction))
+}
+
+/// Check that an endpoint's route exists before fuzzing it: HEAD requests with
+/// the attacker's own id (or a made-up one) and the victim id. Returns the
+/// status when every probe is a hard 404/501, i.e. the route is missing
+/// whatever the id; errors and any other status count as reachable.
+async fn preflight(
+    env: &ScanEnv<'_>,
+    endpoint: &Endpoint,
+    base_path: &str,
+    params: &[DetectedParameter],
+) -> Option<u16> {
+    let own_id = env
+        .attacker_id
+        .map(str::to_string)
+        .unwrap_or_else(|| Baseline::probe_id_like(env.victim_id));
+    let mut ids = vec![own_id];
+    if ids[0] != env.victim_id {
+        ids.push(env.victim_id.to_string());
+    }
+
+    let mut missing = None;
+    for id in ids {
+        let (req, _) = build_attack_request(
+            env.client,
+            env.auth,
+            endpoint,
+            base_path,
+            params,
+            &id,
+            env.request_options,
+        )
+        .ok()?;
+        // HEAD reaches the same route without running the operation
+        let mut request = req.build().ok()?;
+        *request.method_mut() = reqwest::Method::HEAD;
+        *request.body_mut() = None;
+        if !env.take_request() {
+            return None;
+        }
+        let context = ScanContext {
+            endpoint: endpoint.path.clone(),
+            victim_id: env.victim_id.to_string(),
+            attacker_id: env.attacker_id.map(str::to_string),
+            injected_value: id,
+            name: endpoint.description.clone(),
+            deprecated: endpoint.deprecated,
+            risk_score: params.iter().map(|p| p.bola_risk_score).max(),
+            probe: true,
+        };
+        let req = reqwest::RequestBuilder::from_parts(env.client.clone(), request);
+        match send_and_record(env, req, context).await.0 {
+            Ok(exchange) if matches!(exchange.status, 404 | 501) => {
+                missing = Some(exchange.status);
+            }
+            _ => return None,
+        }
+    }
+    missing
+}
+
+/// Whether an --enumerate response for `id` exposes a resource the attacker
+/// can read: a success that is neither the missing-resource response, an SPA
+/// shell nor the attacker's own data. Short of a VULNERABLE verdict it must be
+/// shaped like the attacker's own resource, when that is known.
+fn enumerated_access(
+    baseline: Option<&Baseline>,
+    status: u16,
+    body: &str,
+    id: &str,
+    verdict: Verdict,
+) -> bool {
+    if !(200..300).contains(&status) || verdict == Verdict::Secure {
+        return false;
+    }
+    let Some(baseline) = baseline else {
+        return true;
+    };
+    if baseline.matches(status, body, id) || baseline.is_static_shell(status, body) {
+        return false;
+    }
+    if verdict == Verdict::Vulnerable {
+        return true;
+    }
+    match baseline
+        .own_response
+        .as_ref()
+        .and_then(|(_, own)| json_shape(own))
+    {
+        Some(own_shape) => json_shape(body).is_some_and(|shape| shape == own_shape),
+        None => true,
+    }
+}
+
+/// Send a request with no object reference once, unmodified, and print its status
+async fn send_original(env: &ScanEnv<'_>, endpoint: &Endpoint) {
+    let method = endpoint.method.to_string();
+    let base_path = env.target(&endpoint.path);
+    let (req, url) = match build_attack_request(
+        env.client,
+        env.auth,
+        endpoint,
+        &base_path,
+        &[],
+        env.victim_id,
+        env.request_options,
+    ) {
+        Ok(built) => built,
+        Err(e) => {
+            eprintln!("[ERROR] {}", redact(&e, env.redaction));
+            return;
+        }
+    };
+    let url = redact(&url, env.redaction);
+    if env.stopped() || !confirm_send(env, &req).await || !env.take_request() {
+        return;
+    }
+    let context = ScanContext {
+        endpoint: endpoint.path.clone(),
+        victim_id: env.victim_id.to_string(),
+        attacker_id: env.attacker_id.map(str::to_string),
+        injected_value: String::new(),
+        name: endpoint.description.clone(),
+        deprecated: endpoint.deprecated,
+        risk_score: None,
+        probe: false,
+    };
+    let outcome = match send_and_record(env, req, context).await.0 {
+        Ok(exchange) => format!("HTTP {}", exchange.status),
+        Err(e) => e,
+    };
+    if !env.summary_only {
+        say!(
+            "[NOT A CANDIDATE] {}: {}: {}, no object reference - not a BOLA candidate",
+            method,
+            url,
+            outcome
+        );
+    }
+}
+
+/// PII analyses sent to Ollama at once
+const PII_CONCURRENCY: usize = 4;
+
+/// Hands results to the stream as they are decided. VULNERABLE responses
+/// awaiting PII analysis are held back and sent once it finishes, so every
+/// streamed result is final.
+struct ResultSink {
+    tx: mpsc::UnboundedSender<ScanResult>,
+    pending_pii: Mutex<Vec<(ScanResult, Value)>>,
+}
+
+impl ResultSink {
+    fn emit(&self, result: ScanResult, pii_body: Option<Value>) {
+        match pii_body {
+            Some(body) => self
+                .pending_pii
+                .lock()
+                .expect("PII queue poisoned")
+                .push((result, body)),
+            // The receiver only goes away when the caller drops the stream
+            None => {
+                let _ = self.tx.send(result);
+            }
+        }
+    }
+
+    /// Label the held-back results with their PII outcome and send them
+    async fn finish_pii(&self, env: &ScanEnv<'_>) {
+        let pending = std::mem::take(&mut *self.pending_pii.lock().expect("PII queue poisoned"));
+        if pending.is_empty() {
+            return;
+        }
+        let Some(pii) = &env.pii else {
+            return;
+        };
+        // A stopped scan (Ctrl-C or --max-requests) should finish promptly
+        // instead of waiting on Ollama for every queued response
+        let outcomes = if env.stopped() {
+            eprintln!(
+                "[WARN] Scan stopped early: skipped PII analysis of {} response(s)",
+                pending.len()
+            );
+            vec![PiiOutcome::Skipped; pending.len()]
+        } else {
+            say!(
+                "\nAnalyzing {} vulnerable response(s) for PII...",
+                pending.len()
+            );
+            let bodies: Vec<Value> = pending.iter().map(|(_, body)| body.clone()).collect();
+            let outcomes = pii
+                .analyzer
+                .analyze_batch(&bodies, PII_CONCURRENCY, pii.timeout)
+                .await;
+            let skipped = outcomes
+                .iter()
+                .filter(|o| matches!(o, PiiOutcome::Skipped))
+                .count();
+            if skipped > 0 {
+                eprintln!(
+                    "[WARN] Skipped PII analysis of {} response(s) after repeated Ollama failures",
+                    skipped
+                );
+            }
+            outcomes
+        };
+        for ((mut result, _), outcome) in pending.into_iter().zip(outcomes) {
+            if let PiiOutcome::Failed(e) = &outcome {
+                eprintln!("[WARN] PII analysis failed for {}: {}", result.url, e);
+            }
+            result
+                .verdict
+                .push_str(&format!(" | PII: {}", outcome.label()));
+            self.emit(result, None);
+        }
+    }
+}
+
+/// Scan endpoints and yield each result as soon as it is decided.
+///
+/// Endpoints run concurrently up to `env.concurrency`; the limiter also caps
+/// requests per host. With `env.links`, endpoints linked from responses are
+/// scanned in further rounds, one per link hop, up to `env.max_link_depth`,
+/// and appended to `endpoints`. With `env.discovered_ids` each round scans
+/// its POST endpoints to completion before the rest, so every consumer sees
+/// all ids harvested by producers whatever the concurrency.
+pub fn run_scan_stream<'s>(
+    env: &'s ScanEnv<'s>,
+    endpoints: &'s mut Vec<Endpoint>,
+) -> impl Stream<Item = ScanResult> + 's {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let driver = async move {
+        let sink = ResultSink {
+            tx,
+            pending_pii: Mutex::new(Vec::new()),
+        };
+        let mut round_start = 0;
+        let mut depth = 0;
+        loop {
+            let (producers, consumers): (Vec<&Endpoint>, Vec<&Endpoint>) = endpoints[round_start..]
+                .iter()
+                .partition(|e| env.discovered_ids.is_some() && e.method == Method::POST);
+            for phase in [producers, consumers] {
+                stream::iter(phase)
+                    .map(|endpoint| scan_endpoint(env, &sink, endpoint))
+                    .buffer_unordered(env.concurrency.max(1))
+                    .collect::<()>()
+                    .await;
+            }
+
+            let Some(links) = env.links else {
+                break;
+            };
+            let found = filter_endpoints(links.take(), env.endpoint_filter);
+            if found.is_empty() || depth >= env.max_link_depth || env.stopped() {
+                break;
+            }
+            depth += 1;
+            say!(
+                "\n🔗 Following links (depth {}): {} new endpoint(s)",
+                depth,
+                found.len()
+            );
+            round_start = endpoints.len();
+            endpoints.extend(found);
+        }
+        sink.finish_pii(env).await;
+    };
+    let results = stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|result| (result, rx))
+    });
+    // The driver yields nothing itself; the stream ends once it has finished
+    // and every result it sent has been taken
+    stream::select(
+        stream::once(driver).filter_map(|()| async { None }),
+        results,
+    )
+}
+
+/// Scan endpoints and return every result once the scan is done; the
+/// collected form of [`run_scan_stream`]
+pub async fn run_scan(env: &ScanEnv<'_>, endpoints: &mut Vec<Endpoint>) -> Vec<ScanResult> {
+    run_scan_stream(env, endpoints).collect().await
+}
+
+/// Scan one endpoint, handing its results to the sink
+async fn scan_endpoint(env: &ScanEnv<'_>, sink: &ResultSink, endpoint: &Endpoint) {
+    if env.stopped() {
+        return;
+    }
+    // Analyze parameters using smart detection
+    if env.show_param_analysis {
+        say!("\n{}", get_parameter_summary(endpoint));
+    }
+
+    // Nothing in the request names an object, so there is no id to swap:
+    // send it once as is rather than fuzzing it with the victim id
+    if !has_object_reference(endpoint) {
+        send_original(env, endpoint).await;
+        return;
+    }
+
+    // Get high-risk parameters only (saves time and reduces false positives),
+    // unless the user named the parameter to target
+    let high_risk_params = get_target_params(endpoint, env.min_risk_score, env.target_overrides);
+
+    if high_risk_params.is_empty() {
+        if env.show_param_analysis {
+            match env.target_overrides.target_for(endpoint) {
+                Some(target) => say!(
+                    "  ⏭️  Skipping endpoint - it has no parameter named '{}'",
+                    target
+                ),
+                None => say!(
+                    "  ⏭️  Skipping endpoint - no parameters meet minimum risk score of {}",
+                    env.min_risk_score
+                ),
+            }
+        }
+        return;
+    }
+
+    if env.show_param_analysis {
+        say!(
+            "  ✓ Testing {} high-risk parameter(s)",
+            high_risk_params.len()
+        );
+    }
+    let base_path = env.target(&endpoint.path);
+
+    let method = endpoint.method.to_string();
+    if let Some(unreachable) = env.unreachable {
+        if let Some(status) = preflight(env, endpoint, &base_path, &high_risk_params).await {
+            say!(
+                "[NOT REACHABLE] {} {}: HTTP {} for every id, skipping",
+                method,
+                base_path,
+                status
+            );
+            unreachable
+                .lock()
+                .expect("unreachable list poisoned")
+                .push(format!("{} {}", method, endpoint.path));
+            return;
+        }
+    }
+    env.high_risk_params
+        .fetch_add(high_risk_params.len(), Ordering::Relaxed);
+    // Missing-resource response learned for this endpoint
+    let mut baseline = None;
+
+    let risk_score = high_risk_params.iter().map(|p| p.bola_risk_score).max();
+    let scan_context = |injected: &str| ScanContext {
+        endpoint: endpoint.path.clone(),
+        victim_id: env.victim_id.to_string(),
+        attacker_id: env.attacker_id.map(str::to_string),
+        injected_value: injected.to_string(),
+        name: endpoint.description.clone(),
+        deprecated: endpoint.deprecated,
+        risk_score,
+        probe: false,
+    };
+
+    // Calibrate: learn what this endpoint returns for an id that does not exist.
+    // The probe uses the endpoint's own method, so on mutating endpoints it is
+    // an extra state-changing request and only sent when asked for.
+    if env.calibrate && (endpoint.method.is_safe() || env.probe_mutating) {
+        let probe_id = Baseline::probe_id_like(env.victim_id);
+        let probe = build_attack_request(
+            env.client,
+            env.auth,
+            endpoint,
+            &base_path,
+            &high_risk_params,
+            &probe_id,
+            env.request_options,
+        );
+        let approved = match &probe {
+            Ok((req, _)) => confirm_send(env, req).await && env.take_request(),
+            Err(_) => false,
+        };
+        if let (true, Ok((req, _))) = (approved, probe) {
+            let context = ScanContext {
+                probe: true,
+                ..scan_context(&probe_id)
+            };
+            match send_and_record(env, req, context).await.0 {
+                Ok(exchange) => {
+                    baseline = Some(Baseline::new(exchange.status, &exchange.body, &probe_id));
+                }
+                Err(e) => {
+                    eprintln!("[WARN] Baseline probe failed for {}: {}", base_path, e);
+                }
+            }
+        }
+    }
+    // What the attacker's own id returns: tells an SPA shell served for every
+    // route from real data, sizes the attacker's own list on collection
+    // endpoints, and is the shape of a readable resource for --enumerate.
+    // Read-only, so a mutating request never touches the attacker's data.
+    if let (Some(_), Some(own_id)) = (&baseline, env.attacker_id) {
+        if endpoint.method == Method::GET {
+            if let Ok((req, _)) = build_attack_request(
+                env.client,
+                env.auth,
+                endpoint,
+                &base_path,
+                &high_risk_params,
+                own_id,
+                env.request_options,
+            ) {
+                if confirm_send(env, &req).await && env.take_request() {
+                    let context = ScanContext {
+                        probe: true,
+                        ..scan_context(own_id)
+                    };
+                    if let Ok(exchange) = send_and_record(env, req, context).await.0 {
+                        baseline =
+                            baseline.map(|b| b.with_own_response(exchange.status, &exchange.body));
+                    }
+                }
+            }
+        }
+    }
+
+    // Cursors with a known value are attacked on their own with shifted
+    // variants of that value; every other target gets the id mutations
+    let cursor_value = |p: &DetectedParameter| {
+        endpoint
+            .example_values
+            .get(&p.name)
+            .and_then(Value::as_str)
+            .filter(|_| env.mutational_fuzzing && p.param_type == ParamType::Cursor)
+    };
+    let (cursor_params, id_params): (Vec<_>, Vec<_>) = high_risk_params
+        .iter()
+        .cloned()
+        .partition(|p| cursor_value(p).is_some());
+
+    // --enumerate sweeps every id in the range instead of the mutation set;
+    // ids are generated as they are sent, so a wide range allocates nothing
+    // up front and --max-requests stops it early
+    let sweep = env
+        .enumerate
+        .into_iter()
+        .flat_map(|range| range.ids())
+        .filter(|id| Some(id.as_str()) != env.attacker_id)
+        .map(|id| (high_risk_params.as_slice(), id));
+    let mut attacks: Vec<(&[DetectedParameter], String)> = Vec::new();
+    let mut fuzz_inputs = if env.enumerate.is_some() {
+        Vec::new()
+    } else if env.mutational_fuzzing {
+        // Numeric edge cases only make sense when a target parameter is numeric
+        let numeric = id_params.iter().any(|p| is_numeric_param(endpoint, p));
+        let mut inputs = mutate_param_typed(env.victim_id, numeric);
+        // Name-addressed resources also get guessed names
+        let has_type = |t: ParamType| id_params.iter().any(|p| p.param_type == t);
+        if has_type(ParamType::Username) {
+            inputs.extend(mutate_username(env.victim_id));
+        }
+        if has_type(ParamType::Slug) {
+            inputs.extend(mutate_slug(env.victim_id));
+        }
+        inputs.extend(env.privileged_ids.iter().cloned());
+        inputs.sort();
+        inputs.dedup();
+        inputs
+    } else {
+        vec![env.victim_id.to_string()]
+    };
+    // Real ids created earlier in the scan, for the same kind of resource
+    let resource = resource_type(&endpoint.path);
+    if let (Some(store), Some(resource)) = (env.discovered_ids, &resource) {
+        if endpoint.method != Method::POST {
+            for id in store.candidates(resource) {
+                if !fuzz_inputs.contains(&id) {
+                    fuzz_inputs.push(id);
+                }
+            }
+        }
+    }
+    let max_mutations = env.max_mutations.unwrap_or(usize::MAX);
+    cap_mutations(&mut fuzz_inputs, env.victim_id, max_mutations);
+    if !id_params.is_empty() {
+        attacks.extend(fuzz_inputs.into_iter().map(|v| (id_params.as_slice(), v)));
+    }
+    for param in cursor_params.iter().filter(|_| env.enumerate.is_none()) {
+        let value = cursor_value(param).unwrap_or_default();
+        attacks.extend(
+            mutate_cursor(value)
+                .into_iter()
+                .take(max_mutations)
+                .map(|v| (std::slice::from_ref(param), v)),
+        );
+    }
+    for (targets, mutated) in sweep.chain(attacks) {
+        if env.stopped() {
+            break;
+        }
+        let (req, url) = match build_attack_request(
+            env.client,
+            env.auth,
+            endpoint,
+            &base_path,
+            targets,
+            &mutated,
+            env.request_options,
+        ) {
+            Ok(built) => built,
+            Err(e) => {
+                eprintln!("[ERROR] {}", redact(&e, env.redaction));
+                continue;
+            }
+        };
+        // Everything printed or reported shows the URL with secrets masked
+        let url = redact(&url, env.redaction);
+        // Every target parameter of this request received the same value
+        let substitutions: Vec<(String, String)> = targets
+            .iter()
+            .map(|p| (p.name.clone(), mutated.clone()))
+            .collect();
+
+        if !confirm_send(env, &req).await {
+            if !env.summary_only {
+                say!("[SKIPPED] {}: {}", method, url);
+            }
+            continue;
+        }
+        if !env.take_request() {
+            break;
+        }
+        let (sent, latency_ms) = send_and_record(env, req, scan_context(&mutated)).await;
+        match sent {
+            Ok(exchange) => {
+                let body_text = exchange.body;
+                if let (Some(store), Some(resource)) = (env.discovered_ids, &resource) {
+                    if endpoint.method == Method::POST && matches!(exchange.status, 200 | 201) {
+                        store.record(resource, extract_created_ids(&body_text));
+                    }
+                }
+                if let (Some(links), Ok(request_url)) = (env.links, reqwest::Url::parse(&url)) {
+                    if (200..300).contains(&exchange.status) {
+                        links.record(&body_text, &request_url);
+                    }
+                }
+                // reqwest follows redirects; a final URL on a login page means we were bounced
+                let redirected_to_login = exchange
+                    .redirected_to
+                    .as_deref()
+                    .is_some_and(is_login_redirect);
+                // Every id of an --enumerate sweep is a potential victim
+                let victim_id = if env.enumerate.is_some() {
+                    mutated.as_str()
+                } else {
+                    env.victim_id
+                };
+                let verdict = if redirected_to_login {
+                    Verdict::Secure
+                } else {
+                    decide_verdict_with_policy(
+                        &method,
+                        exchange.status,
+                        &body_text,
+                        env.attacker_id,
+                        Some(victim_id),
+                        baseline.as_ref(),
+                        &env.verdict_policy,
+                    )
+                };
+                let hook_context = VerdictContext {
+                    method: &method,
+                    url: &url,
+                    status: exchange.status,
+                    headers: &exchange.headers,
+                    body: &body_text,
+                    attacker_id: env.attacker_id,
+                    victim_id: Some(victim_id),
+                };
+                let (verdict, hook_notes) =
+                    run_verdict_hooks(env.verdict_hooks, &hook_context, verdict);
+                let confidence = if redirected_to_login {
+                    90
+                } else {
+                    verdict_confidence(
+                        verdict,
+                        exchange.status,
+                        &body_text,
+                        env.attacker_id,
+                        Some(victim_id),
+                        baseline.as_ref(),
+                    )
+                };
+                // Weak findings are downgraded here, so the console, every
+                // report format and the exit code all see the same verdict
+                let low_confidence = verdict == Verdict::Vulnerable
+                    && env.min_confidence.is_some_and(|min| confidence < min);
+                let verdict = if low_confidence {
+                    Verdict::Uncertain
+                } else {
+                    verdict
+                };
+                if let Some(accessible) = env.accessible_ids {
+                    if enumerated_access(
+                        baseline.as_ref(),
+                        exchange.status,
+                        &body_text,
+                        &mutated,
+                        verdict,
+                    ) {
+                        accessible
+                            .lock()
+                            .expect("accessible id list poisoned")
+                            .push(mutated.clone());
+                    }
+                }
+                let mut result_str = verdict.label().to_string();
+                for note in hook_notes {
+                    result_str.push_str(&format!(" | {}", note));
+                }
+                if low_confidence {
+                    result_str.push_str(&format!(
+                        " | Low confidence: {}% is below --min-confidence",
+                        confidence
+                    ));
+                }
+                if let (Verdict::Vulnerable, Some((victim_len, own_len))) = (
+                    verdict,
+                    baseline
+                        .as_ref()
+                        .and_then(|b| b.list_leak(exchange.status, &body_text)),
+                ) {
+                    result_str.push_str(&format!(
+                        " | List scope leak: {} items vs {} in the attacker's own list",
+                        victim_len, own_len
+                    ));
+                }
+                if verdict == Verdict::Uncertain
+                    && baseline
+                        .as_ref()
+                        .is_some_and(|b| b.is_static_shell(exchange.status, &body_text))
+                {
+                    result_str.push_str(" | Static shell response: same page for every id");
+                }
+                let response = ResponseContext {
+                    method: &method,
+                    url: &url,
+                    status: exchange.status,
+                    body: &body_text,
+                    verdict,
+                    attacker_id: env.attacker_id,
+                    victim_id: Some(victim_id),
+                    headers: &exchange.headers,
+                    request_origin: env.request_options.origin.as_deref(),
+                };
+                for finding in run_analyzers(env.analyzers, &response) {
+                    result_str.push_str(&format!(" | {}", finding));
+                }
+                if exchange.truncated {
+                    result_str.push_str(&format!(
+                        " | Body truncated at {} bytes",
+                        env.max_body_bytes
+                    ));
+                }
+                // PII analysis of vulnerable JSON responses runs after the
+                // scan so a slow model does not hold up requests
+                let pii_body = (env.pii.is_some() && verdict == Verdict::Vulnerable)
+                    .then(|| parse_json_body(&body_text))
+                    .flatten();
+                // Results below --min-verdict are hidden but still counted in the summary
+                if env.shown(verdict.label()) {
+                    if endpoint.deprecated {
+                        say!(
+                            "[{}] {}: {} (deprecated)",
+                            paint_verdict(&result_str),
+                            method,
+                            url
+                        );
+                    } else {
+                        say!("[{}] {}: {}", paint_verdict(&result_str), method, url);
+                    }
+                    if env.explain {
+                        let reasons = verdict_reasons(
+                            exchange.status,
+                            redirected_to_login,
+                            &body_text,
+                            env.attacker_id,
+                            victim_id,
+                            baseline.as_ref(),
+                        );
+                        for reason in reasons {
+                            say!("    - {}", reason);
+                        }
+                    }
+                }
+                sink.emit(
+                    ScanResult::new(method.clone(), url.clone(), result_str)
+                        .with_status(Some(exchange.status))
+                        .with_confidence((confidence > 0).then_some(confidence))
+                        .with_value(mutated.clone())
+                        .with_substitutions(substitutions.clone())
+                        .with_latency(latency_ms)
+                        .with_deprecated(endpoint.deprecated)
+                        .with_name(endpoint.description.clone())
+                        .with_risk_score(risk_score),
+                    pii_body,
+                );
+            }
+            Err(e) => {
+                if env.shown("ERROR") {
+                    say!("[ERROR] {}: {}: {}", method, url, e);
+                }
+                sink.emit(
+                    ScanResult::new(method.clone(), url.clone(), format!("ERROR: {}", e))
+                        .with_value(mutated.clone())
+                        .with_substitutions(substitutions.clone())
+                        .with_latency(latency_ms)
+                        .with_deprecated(endpoint.deprecated)
+                        .with_name(endpoint.description.clone())
+                        .with_risk_score(risk_score),
+                    None,
+                );
+            }
+        }
+    }
+}
+
+/// Evidence behind a verdict, for `verify`: the status, whether the response
+/// matches the missing-resource baseline, and whose id it carries
+fn verdict_reasons(
+    status: u16,
+    redirected_to_login: bool,
+    body: &str,
+    attacker_id: Option<&str>,
+    victim_id: &str,
+    baseline: Option<&Baseline>,
+) -> Vec<String> {
+    let mut reasons = vec![if redirected_to_login {
+        format!("HTTP {} after a redirect to a login page", status)
+    } else {
+        format!("HTTP {}", status)
+    }];
+    match baseline {
+        Some(b) if b.matches(status, body, victim_id) => {
+            reasons.push("Same response as for an id that does not exist".to_string())
+        }
+        Some(b) => reasons.push(format!(
+            "Differs from the missing-resource response (HTTP {})",
+            b.status
+        )),
+        None => reasons.push("No missing-resource baseline to compare with".to_string()),
+    }
+    let mut ids = vec![("victim", victim_id)];
+    ids.extend(attacker_id.map(|id| ("attacker", id)));
+    for (whose, id) in ids {
+        let found = match contains_identity(body, id) {
+            Some(MatchInfo { field, weight }) => format!(
+                "names the {} id in '{}' ({} field)",
+                whose,
+                field,
+                match weight {
+                    FieldWeight::Critical => "ownership",
+                    FieldWeight::Metadata => "metadata",
+                }
+            ),
+            None if contains_id(body, id) => format!("mentions the {} id", whose),
+            None => format!("does not name the {} id", whose),
+        };
+        reasons.push(format!("Response {}", found));
+    }
+    reasons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Parameter, ParameterLocation};
+    use crate::parameters::{get_high_risk_params, BodyTemplates};
+    use std::io::{Read, Write};
+
+    #[test]
+    fn verdicts_are_painted_only_with_color() {
+        assert_eq!(
+            paint_verdict("VULNERABLE | PII: YES"),
+            "VULNERABLE | PII: YES"
+        );
+        COLOR.store(true, Ordering::Relaxed);
+        let painted = paint_verdict("VULNERABLE | PII: YES");
+        let secure = paint_verdict("SECURE");
+        let error = paint_verdict("ERROR: timeout");
+        COLOR.store(false, Ordering::Relaxed);
+        assert_eq!(painted, "\x1b[31mVULNERABLE | PII: YES\x1b[0m");
+        assert_eq!(secure, "\x1b[32mSECURE\x1b[0m");
+        assert_eq!(error, "ERROR: timeout");
+    }
+
+    fn build(endpoint: &Endpoint, value: &str) -> reqwest::Request {
+        let auth = StaticTokenAuth {
+            token: "t".to_string(),
+        };
+        let params = get_high_risk_params(endpoint, 0);
+        let (req, _) = build_attack_request(
+            &reqwest::Client::new(),
+            &auth,
+            endpoint,
+            &format!("http://api{}", endpoint.path),
+            &params,
+            value,
+            &RequestOptions::default(),
+        )
+        .expect("request should build");
+        req.build().expect("request should be valid")
+    }
+
+    #[test]
+    fn force_base_url_rewrites_spec_servers() {
+        let spec_path = "https://api.example.com/v1/users/{id}";
+        assert_eq!(
+            target_url(spec_path, "http://localhost:3000", false),
+            spec_path
+        );
+        assert_eq!(
+            target_url(spec_path, "http://localhost:3000/", true),
+            "http://localhost:3000/v1/users/{id}"
+        );
+        assert_eq!(
+            target_url("/users/{id}", "http://localhost:3000", true),
+            "http://localhost:3000/users/{id}"
+        );
+    }
+
+    #[test]
+    fn get_never_gets_a_json_body() {
+        let endpoint = Endpoint::new(
+            Method::GET,
+            "/api/orders".to_string(),
+            None,
+            vec!["body.userId".to_string()],
+        );
+        let request = build(&endpoint, "42");
+        assert!(request.body().is_none());
+        assert_eq!(request.url().query(), Some("userId=42"));
+    }
+
+    #[test]
+    fn post_body_fields_stay_in_body() {
+        let endpoint = Endpoint::new(
+            Method::POST,
+            "/api/orders".to_string(),
+            None,
+            vec!["body.userId".to_string()],
+        );
+        let request = build(&endpoint, "42");
+        let body = request.body().and_then(|b| b.as_bytes()).unwrap();
+        assert_eq!(body, br#"{"userId":"42"}"#);
+        assert_eq!(request.url().query(), None);
+    }
+
+    #[test]
+    fn example_body_is_the_template() {
+        let mut endpoint = Endpoint::new(
+            Method::POST,
+            "/api/orders".to_string(),
+            None,
+            vec!["body.userId".to_string()],
+        );
+        endpoint.example_body = Some(serde_json::json!({"userId": "7", "sku": "A-1", "qty": 2}));
+        endpoint
+            .example_values
+            .insert("body.sku".to_string(), serde_json::json!("string"));
+        let request = build(&endpoint, "42");
+        let body: Value =
+            serde_json::from_slice(request.body().and_then(|b| b.as_bytes()).unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"userId": "42", "sku": "A-1", "qty": 2})
+        );
+    }
+
+    #[test]
+    fn body_template_replaces_schema_body() {
+        let mut endpoint = Endpoint::new(
+            Method::POST,
+            "/api/orders/{orderId}".to_string(),
+            None,
+            vec!["orderId".to_string(), "body.userId".to_string()],
+        );
+        endpoint.example_body = Some(serde_json::json!({"userId": "7"}));
+        let options = RequestOptions {
+            body_templates: BodyTemplates::parse(
+                r#"{"templates": {"/api/orders/{orderId}": {"order": {"owner": "{{VICTIM_ID}}"}}}}"#,
+            )
+            .unwrap(),
+            ..RequestOptions::default()
+        };
+        let (req, _) = build_attack_request(
+            &reqwest::Client::new(),
+            &StaticTokenAuth {
+                token: "t".to_string(),
+            },
+            &endpoint,
+            "http://api/api/orders/{orderId}",
+            &get_high_risk_params(&endpoint, 0),
+            "42",
+            &options,
+        )
+        .unwrap();
+        let request = req.build().unwrap();
+        // Path parameters are still injected; the body is the template alone
+        assert_eq!(request.url().path(), "/api/orders/42");
+        let body: Value =
+            serde_json::from_slice(request.body().and_then(|b| b.as_bytes()).unwrap()).unwrap();
+        assert_eq!(body, serde_json::json!({"order": {"owner": "42"}}));
+    }
+
+    #[test]
+    fn enumeration_counts_only_readable_resources() {
+        let own = r#"{"id": 3, "owner": "attacker", "total": 10}"#;
+        let baseline = Baseline::new(404, r#"{"error": "not found"}"#, "900000000001")
+            .with_own_response(200, own);
+        let access =
+            |status, body, verdict| enumerated_access(Some(&baseline), status, body, "4", verdict);
+        let other = r#"{"id": 4, "owner": "victim", "total": 99}"#;
+        assert!(access(200, other, Verdict::Uncertain));
+        assert!(access(
+            200,
+            r#"{"id": 4, "_links": {}}"#,
+            Verdict::Vulnerable
+        ));
+        assert!(!access(
+            404,
+            r#"{"error": "not found"}"#,
+            Verdict::Uncertain
+        ));
+        assert!(!access(403, "", Verdict::Secure));
+        // The attacker's own data, and success bodies of another shape
+        assert!(!access(200, own, Verdict::Secure));
+        assert!(!access(200, r#"{"status": "ok"}"#, Verdict::Uncertain));
+    }
+
+    #[test]
+    fn custom_methods_are_sent_verbatim() {
+        let endpoint = Endpoint::new(
+            Method::Other("PROPFIND".to_string()),
+            "/dav/files/{fileId}".to_string(),
+            None,
+            vec!["fileId".to_string()],
+        );
+        let request = build(&endpoint, "42");
+        assert_eq!(request.method().as_str(), "PROPFIND");
+        assert_eq!(request.url().path(), "/dav/files/42");
+    }
+
+    #[test]
+    fn public_operations_get_no_credentials() {
+        let mut endpoint = Endpoint::new(
+            Method::GET,
+            "/api/catalog/{itemId}".to_string(),
+            None,
+            vec!["itemId".to_string()],
+        );
+        assert!(build(&endpoint, "42")
+            .headers()
+            .contains_key(reqwest::header::AUTHORIZATION));
+        endpoint.security = Some(Vec::new());
+        assert!(!build(&endpoint, "42")
+            .headers()
+            .contains_key(reqwest::header::AUTHORIZATION));
+    }
+
+    #[test]
+    fn cookie_params_go_in_cookie_header() {
+        let mut endpoint = Endpoint::new(Method::GET, "/api/cart".to_string(), None, vec![]);
+        endpoint.add_parameter(Parameter::new("cartId", ParameterLocation::Cookie));
+        let request = build(&endpoint, "42");
+        assert_eq!(
+            request.headers().get(reqwest::header::COOKIE).unwrap(),
+            "cartId=42"
+        );
+        assert_eq!(request.url().query(), None);
+    }
+
+    /// Serves `{"id": <last path segment>}` for every request, so every
+    /// mutated id reads back as found
+    fn echo_server() -> std::net::SocketAddr {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0u8; 4096];
+                let n = stream.read(&mut request).unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..n]);
+                let path = request.split(' ').nth(1).unwrap_or("/");
+                let id = path.rsplit('/').next().unwrap_or("");
+                let body = serde_json::json!({ "id": id }).to_string();
+                let _ = stream.write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                    .as_bytes(),
+                );
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn streamed_results_match_the_batch() {
+        let base_url = format!("http://{}", echo_server());
+        let client = reqwest::Client::new();
+        let auth = StaticTokenAuth {
+            token: "t".to_string(),
+        };
+        let limiter = HostLimiter::new(4, None);
+        let interrupted = AtomicBool::new(false);
+        let target_overrides = TargetOverrides::default();
+        let endpoint_filter = EndpointFilter::default();
+        let request_options = RequestOptions::default();
+        let redaction = RedactionConfig::default();
+        let host_map = HostRewriter::new();
+        let env = ScanEnv {
+            client: &client,
+            auth: &auth,
+            analyzers: &[],
+            limiter: &limiter,
+            recorder: None,
+            destructive_guard: None,
+            discovered_ids: None,
+            links: None,
+            max_link_depth: 0,
+            endpoint_filter: &endpoint_filter,
+            concurrency: 1,
+            unreachable: None,
+            enumerate: None,
+            accessible_ids: None,
+            interrupted: &interrupted,
+            budget: None,
+            request_options: &request_options,
+            redaction: &redaction,
+            max_body_bytes: 4096,
+            delay: RequestDelay::default(),
+            base_url: &base_url,
+            host_map: &host_map,
+            victim_id: "123",
+            attacker_id: Some("42"),
+            min_risk_score: 0,
+            target_overrides: &target_overrides,
+            privileged_ids: &[],
+            max_mutations: Some(3),
+            min_verdict: None,
+            min_confidence: None,
+            verdict_policy: VerdictPolicy::default(),
+            verdict_hooks: &[],
+            show_param_analysis: false,
+            summary_only: true,
+            explain: false,
+            calibrate: false,
+            probe_mutating: false,
+            mutational_fuzzing: true,
+            force_base_url: true,
+            pii: None,
+            high_risk_params: AtomicUsize::new(0),
+        };
+        let endpoints = vec![
+            Endpoint::new(
+                Method::GET,
+                "/users/{userId}".to_string(),
+                None,
+                vec!["userId".to_string()],
+            ),
+            Endpoint::new(
+                Method::GET,
+                "/orders/{orderId}".to_string(),
+                None,
+                vec!["orderId".to_string()],
+            ),
+        ];
+        let without_latency = |results: Vec<ScanResult>| -> Vec<ScanResult> {
+            results
+                .into_iter()
+                .map(|r| ScanResult {
+                    latency_ms: None,
+                    ..r
+                })
+                .collect()
+        };
+
+        let streamed: Vec<ScanResult> = run_scan_stream(&env, &mut endpoints.clone())
+            .collect()
+            .await;
+        let batch = run_scan(&env, &mut endpoints.clone()).await;
+
+        assert!(streamed.iter().any(|r| r.verdict_class() == "VULNERABLE"));
+        assert_eq!(without_latency(streamed), without_latency(batch));
+    }
+}
@@ -1,22 +1,37 @@
 // Verdict engine for Doppel
 // Decides if a response is vulnerable, secure, or uncertain
 
+use crate::response_analysis::parse_json_body;
 use serde_json::Value;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Verdict {
     Vulnerable,
     Secure,
     Uncertain,
 }
 
+impl Verdict {
+    /// Label used in console output and reports
+    pub fn label(&self) -> &'static str {
+        match self {
+            Verdict::Vulnerable => "VULNERABLE",
+            Verdict::Secure => "SECURE",
+            Verdict::Uncertain => "UNCERTAIN",
+        }
+    }
+}
+
 /// Decide verdict from HTTP status and response body.
 ///
 /// A proper BOLA check requires:
 /// 1. 200 OK with victim's data = VULNERABLE
 /// 2. 200 OK with attacker's own data = SECURE (proper authorization)
-/// 3. 403/401 = SECURE (proper access control)
+/// 3. 403/401 = SECURE (access denied; a 401 is annotated as a rejected token)
 /// 4. 400 = SECURE (proper input validation)
 /// 5. 404 = Context-dependent (could be authorization or missing resource)
+/// 6. 3xx to a login page = SECURE (unauthenticated redirect)
+/// 7. 405/415 = UNCERTAIN (wrong method or content type, not an authorization outcome)
 ///
 /// This function analyzes the response to determine if victim data is leaked.
 pub fn decide_verdict(
@@ -24,6 +39,30 @@ pub fn decide_verdict(
     body: &str,
     attacker_id: Option<&str>,
     victim_id: Option<&str>,
+) -> Verdict {
+    status_verdict(
+        status,
+        body,
+        attacker_id,
+        victim_id,
+        &VerdictPolicy::default(),
+    )
+}
+
+/// Tunable parts of the verdict logic; the default is the standard weighting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VerdictPolicy {
+    /// Count an id in a metadata field (created_by, author_id, ...) as
+    /// ownership, like a critical field (--treat-metadata-as-critical)
+    pub metadata_is_critical: bool,
+}
+
+fn status_verdict(
+    status: u16,
+    body: &str,
+    attacker_id: Option<&str>,
+    victim_id: Option<&str>,
+    policy: &VerdictPolicy,
 ) -> Verdict {
     match status {
         // Access denied - properly secured
@@ -36,28 +75,398 @@ pub fn decide_verdict(
         200 | 201 => {
             // If we have both attacker and victim IDs, analyze the response
             if let (Some(attacker), Some(victim)) = (attacker_id, victim_id) {
-                analyze_response_ownership(body, attacker, victim)
+                ownership_verdict(body, attacker, victim, policy)
             } else {
                 // Fallback to old behavior if IDs not provided
                 Verdict::Uncertain
             }
         }
 
+        // No Content - success without a body; only meaningful with the request
+        // method, see decide_verdict_for_method
+        204 => Verdict::Uncertain,
+
+        // Redirects - a bounce to a login page means the request was rejected
+        300..=399 if is_login_redirect(body) => Verdict::Secure,
+
         // Resource not found - context-dependent
         404 => analyze_404_context(body),
 
+        // Wrong method or content type - the request never reached the
+        // authorization check, so nothing is known either way
+        405 | 415 => Verdict::Uncertain,
+
         // Other status codes (5xx, etc.)
         _ => Verdict::Uncertain,
     }
 }
 
+/// The server's response to a resource id that certainly does not exist.
+///
+/// Learned once per endpoint before attacking. A victim response with the same
+/// status and body shape is indistinguishable from "missing resource" and
+/// leaks nothing; one that differs is more likely to contain real data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Baseline {
+    pub status: u16,
+    /// Response body with the probe id normalized away
+    pub body_template: String,
+    /// Probe response body exactly as received
+    pub body: String,
+    /// Status and body the attacker's own id returned, when probed
+    pub own_response: Option<(u16, String)>,
+}
+
+impl Baseline {
+    /// Record the response to a probe request for `probe_id`
+    pub fn new(status: u16, body: &str, probe_id: &str) -> Self {
+        Baseline {
+            status,
+            body_template: body_template(body, probe_id),
+            body: body.to_string(),
+            own_response: None,
+        }
+    }
+
+    /// Also record the response to the attacker's own id
+    pub fn with_own_response(mut self, status: u16, body: &str) -> Self {
+        self.own_response = Some((status, body.to_string()));
+        self
+    }
+
+    /// Generate a non-existent id shaped like `victim_id` so the probe passes
+    /// the same input validation as the real attack
+    pub fn probe_id_like(victim_id: &str) -> String {
+        let nanos = chrono::Utc::now().timestamp_subsec_nanos() as u64;
+        let digits = format!("{:012}", 900_000_000_000 + nanos);
+        if victim_id.len() == 36 && victim_id.matches('-').count() == 4 {
+            format!("00000000-0000-0000-0000-{}", digits)
+        } else {
+            digits
+        }
+    }
+
+    /// Whether a response for `requested_id` looks like the missing-resource response
+    pub fn matches(&self, status: u16, body: &str, requested_id: &str) -> bool {
+        self.status == status && self.body_template == body_template(body, requested_id)
+    }
+
+    /// Primary list lengths (victim, own) when a success response carries a
+    /// non-empty list that differs from the attacker's own list and is
+    /// comparably long (at least half its length): the id reached another
+    /// user's collection rather than an empty or own-only scope. Items must
+    /// carry data: a list of `{}` placeholders identifies nobody.
+    pub fn list_leak(&self, status: u16, body: &str) -> Option<(usize, usize)> {
+        if !(200..300).contains(&status) {
+            return None;
+        }
+        let (own_status, own_body) = self.own_response.as_ref()?;
+        if !(200..300).contains(own_status) {
+            return None;
+        }
+        let own_json = parse_json_body(own_body)?;
+        let victim_json = parse_json_body(body)?;
+        let own = primary_array(&own_json)?;
+        let victim = primary_array(&victim_json)?;
+        (victim.iter().any(identifies_something) && victim != own && victim.len() * 2 >= own.len())
+            .then_some((victim.len(), own.len()))
+    }
+
+    /// Whether a success response is byte-identical to both the missing-id and
+    /// the attacker's own response: an SPA shell served for every route, which
+    /// says nothing about whose data the id reaches
+    pub fn is_static_shell(&self, status: u16, body: &str) -> bool {
+        (200..300).contains(&status)
+            && self.status == status
+            && self.body == body
+            && self
+                .own_response
+                .as_ref()
+                .is_some_and(|(own_status, own_body)| *own_status == status && own_body == body)
+    }
+}
+
+/// Only the requested id is masked: other numbers (balances, counts, owner
+/// ids) are exactly what tells a real record apart from the not-found body
+fn body_template(body: &str, id: &str) -> String {
+    let without_id = if id.is_empty() {
+        body.to_string()
+    } else {
+        body.replace(id, "{id}")
+    };
+    without_id.trim().to_string()
+}
+
+/// Whether a list item holds at least one non-empty value: a scalar id, or
+/// an object with a field that is not null, empty or itself empty
+fn identifies_something(item: &Value) -> bool {
+    match item {
+        Value::Null => false,
+        Value::String(s) => !s.is_empty(),
+        Value::Object(fields) => fields.values().any(identifies_something),
+        Value::Array(items) => items.iter().any(identifies_something),
+        Value::Bool(_) | Value::Number(_) => true,
+    }
+}
+
+/// The list a collection response carries: the root array, or the array
+/// under a `data`, `items` or `results` field
+pub fn primary_array(value: &Value) -> Option<&Vec<Value>> {
+    match value {
+        Value::Array(items) => Some(items),
+        Value::Object(obj) => ["data", "items", "results"]
+            .iter()
+            .find_map(|key| obj.get(*key).and_then(Value::as_array)),
+        _ => None,
+    }
+}
+
+/// Decide verdict, consulting the endpoint's missing-resource baseline when known.
+///
+/// A response matching the baseline is treated as SECURE: the server answered
+/// exactly as it does for an id that does not exist, so no victim data leaked.
+pub fn decide_verdict_with_baseline(
+    method: &str,
+    status: u16,
+    body: &str,
+    attacker_id: Option<&str>,
+    victim_id: Option<&str>,
+    baseline: Option<&Baseline>,
+) -> Verdict {
+    decide_verdict_with_policy(
+        method,
+        status,
+        body,
+        attacker_id,
+        victim_id,
+        baseline,
+        &VerdictPolicy::default(),
+    )
+}
+
+/// [`decide_verdict_with_baseline`] under a non-default [`VerdictPolicy`]
+pub fn decide_verdict_with_policy(
+    method: &str,
+    status: u16,
+    body: &str,
+    attacker_id: Option<&str>,
+    victim_id: Option<&str>,
+    baseline: Option<&Baseline>,
+    policy: &VerdictPolicy,
+) -> Verdict {
+    if baseline.is_some_and(|b| b.is_static_shell(status, body)) {
+        return Verdict::Uncertain;
+    }
+    if let (Some(baseline), Some(victim)) = (baseline, victim_id) {
+        // The probe hits the same shape mismatch, which says nothing about access
+        if baseline.matches(status, body, victim) && !is_request_shape_mismatch(status) {
+            return Verdict::Secure;
+        }
+    }
+    // Past the baseline check, a known baseline answered differently for
+    // the missing id, which is what makes a bare 204 mean something
+    let verdict = method_verdict(
+        method,
+        status,
+        body,
+        attacker_id,
+        victim_id,
+        baseline.is_some(),
+        policy,
+    );
+    // Lists rarely carry the victim's id in an identity field; a full list of
+    // someone else's items is the signal instead
+    if verdict == Verdict::Uncertain
+        && baseline.is_some_and(|b| b.list_leak(status, body).is_some())
+    {
+        return Verdict::Vulnerable;
+    }
+    verdict
+}
+
+/// Decide verdict with knowledge of the request method.
+///
+/// A 204 No Content on a mutating request that targeted the victim's id means
+/// the change (or deletion) went through, but only if the server does not
+/// answer 204 for every id. Without a baseline to show that, it is
+/// UNCERTAIN; see [`decide_verdict_with_baseline`].
+pub fn decide_verdict_for_method(
+    method: &str,
+    status: u16,
+    body: &str,
+    attacker_id: Option<&str>,
+    victim_id: Option<&str>,
+) -> Verdict {
+    method_verdict(
+        method,
+        status,
+        body,
+        attacker_id,
+        victim_id,
+        false,
+        &VerdictPolicy::default(),
+    )
+}
+
+/// `corroborated`: the endpoint's missing-id probe got a different answer,
+/// so a 204 is specific to the victim's object
+fn method_verdict(
+    method: &str,
+    status: u16,
+    body: &str,
+    attacker_id: Option<&str>,
+    victim_id: Option<&str>,
+    corroborated: bool,
+    policy: &VerdictPolicy,
+) -> Verdict {
+    let mutating = matches!(
+        method.to_uppercase().as_str(),
+        "POST" | "PUT" | "PATCH" | "DELETE"
+    );
+    if status == 204 && mutating && victim_id.is_some() && corroborated {
+        return Verdict::Vulnerable;
+    }
+    status_verdict(status, body, attacker_id, victim_id, policy)
+}
+
+/// How strongly a response supports its verdict, as a percentage.
+///
+/// Direct evidence scores high: the victim's id in a critical identity field,
+/// an explicit 401/403, the endpoint's missing-resource response. Indirect
+/// signals score lower: an id in a metadata field or plain text, a list that
+/// merely differs from the attacker's own, a 204 backed only by the baseline.
+/// UNCERTAIN claims nothing either way and scores 0.
+pub fn verdict_confidence(
+    verdict: Verdict,
+    status: u16,
+    body: &str,
+    attacker_id: Option<&str>,
+    victim_id: Option<&str>,
+    baseline: Option<&Baseline>,
+) -> u8 {
+    let identity = |id: Option<&str>| id.and_then(|id| contains_identity(body, id));
+    match verdict {
+        Verdict::Uncertain => 0,
+        Verdict::Vulnerable => match identity(victim_id) {
+            Some(m) if m.weight == FieldWeight::Critical => 90,
+            Some(_) => 60,
+            None if baseline.is_some_and(|b| b.list_leak(status, body).is_some()) => 70,
+            None if parse_json_body(body).is_none()
+                && victim_id.is_some_and(|id| contains_id(body, id)) =>
+            {
+                60
+            }
+            // Only a baseline that answered differently makes a 204 VULNERABLE
+            None if status == 204 => 60,
+            // Decided by a custom rule rather than by evidence in the response
+            None => 50,
+        },
+        Verdict::Secure => match status {
+            401 | 403 => 95,
+            _ if victim_id
+                .is_some_and(|id| baseline.is_some_and(|b| b.matches(status, body, id))) =>
+            {
+                85
+            }
+            200..=299 if identity(attacker_id).is_some() => 90,
+            300..=399 => 90,
+            400 | 404 => 70,
+            _ => 60,
+        },
+    }
+}
+
+/// Everything a [`VerdictHook`] may inspect about one attack response
+#[derive(Debug, Clone, Copy)]
+pub struct VerdictContext<'a> {
+    pub method: &'a str,
+    pub url: &'a str,
+    pub status: u16,
+    /// Response headers as name/value pairs
+    pub headers: &'a [(String, String)],
+    pub body: &'a str,
+    pub attacker_id: Option<&'a str>,
+    pub victim_id: Option<&'a str>,
+}
+
+/// Domain-specific verdict rules run after the built-in logic, e.g. "a body
+/// carrying our tenant marker is always SECURE". Hooks run in registration
+/// order, each seeing the verdict left by the one before.
+pub trait VerdictHook {
+    /// The verdict to report; return `verdict` unchanged to keep it
+    fn refine(&self, ctx: &VerdictContext, verdict: Verdict) -> Verdict;
+
+    /// Note appended to the reported verdict, given the refined verdict
+    fn annotate(&self, _ctx: &VerdictContext, _verdict: Verdict) -> Option<String> {
+        None
+    }
+}
+
+/// Run every hook over a built-in verdict; returns the final verdict and
+/// the hooks' notes
+pub fn run_verdict_hooks(
+    hooks: &[Box<dyn VerdictHook>],
+    ctx: &VerdictContext,
+    verdict: Verdict,
+) -> (Verdict, Vec<String>) {
+    let mut notes = Vec::new();
+    let verdict = hooks.iter().fold(verdict, |verdict, hook| {
+        let refined = hook.refine(ctx, verdict);
+        notes.extend(hook.annotate(ctx, refined));
+        refined
+    });
+    (verdict, notes)
+}
+
+/// SECURE whenever a success body contains a marker only the attacker's own
+/// data carries, such as the attacker tenant's id (--secure-marker)
+pub struct SecureMarkerHook {
+    pub marker: String,
+}
+
+impl VerdictHook for SecureMarkerHook {
+    fn refine(&self, ctx: &VerdictContext, verdict: Verdict) -> Verdict {
+        if self.matches(ctx) {
+            Verdict::Secure
+        } else {
+            verdict
+        }
+    }
+
+    fn annotate(&self, ctx: &VerdictContext, _verdict: Verdict) -> Option<String> {
+        self.matches(ctx)
+            .then(|| format!("Secure marker '{}' found", self.marker))
+    }
+}
+
+impl SecureMarkerHook {
+    fn matches(&self, ctx: &VerdictContext) -> bool {
+        (200..300).contains(&ctx.status) && ctx.body.contains(&self.marker)
+    }
+}
+
+/// Whether a status means the request itself was malformed for the endpoint
+/// (405 Method Not Allowed, 415 Unsupported Media Type) rather than refused
+pub fn is_request_shape_mismatch(status: u16) -> bool {
+    matches!(status, 405 | 415)
+}
+
+/// Whether a redirect target (Location header, final URL, or redirect body)
+/// points at a login page
+pub fn is_login_redirect(location: &str) -> bool {
+    let location = location.to_lowercase();
+    ["login", "signin", "sign-in", "sign_in", "/sso", "oauth"]
+        .iter()
+        .any(|keyword| location.contains(keyword))
+}
+
 /// Analyze 404 responses for context clues about authorization.
 ///
 /// A 404 can mean:
 /// 1. Resource truly doesn't exist → Uncertain
 /// 2. Resource exists but is hidden due to authorization → Secure
 /// 3. Generic "not found" message → Uncertain
-fn analyze_404_context(body: &str) -> Verdict {
+pub fn analyze_404_context(body: &str) -> Verdict {
     let body_lower = body.to_lowercase();
 
     // Check for authorization-related messages in 404 response
@@ -82,11 +491,24 @@ fn analyze_404_context(body: &str) -> Verdict {
 }
 
 /// Analyze response body to determine if it contains victim or attacker data.
-fn analyze_response_ownership(body: &str, attacker_id: &str, victim_id: &str) -> Verdict {
-    // Try to parse as JSON
-    let json: Value = match serde_json::from_str(body) {
-        Ok(v) => v,
-        Err(_) => {
+///
+/// This is the success-response half of [`decide_verdict`]: VULNERABLE when the
+/// victim's id is in an identity field, SECURE for the attacker's id or an error
+/// body, UNCERTAIN otherwise. Non-JSON bodies fall back to whole-id text matching.
+pub fn analyze_response_ownership(body: &str, attacker_id: &str, victim_id: &str) -> Verdict {
+    ownership_verdict(body, attacker_id, victim_id, &VerdictPolicy::default())
+}
+
+fn ownership_verdict(
+    body: &str,
+    attacker_id: &str,
+    victim_id: &str,
+    policy: &VerdictPolicy,
+) -> Verdict {
+    // Try to parse as JSON, including XSSI-guarded and JSONP bodies
+    let json: Value = match parse_json_body(body) {
+        Some(v) => v,
+        None => {
             // If not JSON, do string-based analysis
             return analyze_text_ownership(body, attacker_id, victim_id);
         }
@@ -94,8 +516,8 @@ fn analyze_response_ownership(body: &str, attacker_id: &str, victim_id: &str) ->
 
     // First check identity fields (id, userId, user_id, etc.)
     // These are the critical fields that indicate resource ownership
-    let has_victim_identity = contains_identifier_in_identity_fields(&json, victim_id);
-    let has_attacker_identity = contains_identifier_in_identity_fields(&json, attacker_id);
+    let has_victim_identity = contains_identifier_in_identity_fields(&json, victim_id, policy);
+    let has_attacker_identity = contains_identifier_in_identity_fields(&json, attacker_id, policy);
 
     if has_victim_identity {
         // Found victim's ID in identity fields - VULNERABLE!
@@ -115,8 +537,8 @@ fn analyze_response_ownership(body: &str, attacker_id: &str, victim_id: &str) ->
 }
 
 /// Field classification for weighted identity matching
-#[derive(Debug, PartialEq)]
-enum FieldWeight {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldWeight {
     Critical, // id, userId, user_id - direct resource ownership
     Metadata, // created_by, updated_by - metadata fields (could be public)
 }
@@ -156,27 +578,38 @@ fn classify_identity_field(field_name: &str) -> Option<FieldWeight> {
     }
 }
 
-/// Result of identity field search with weight information
-#[derive(Debug)]
-struct IdentityMatch {
-    found: bool,
-    weight: Option<FieldWeight>,
+/// Where an id was found in a response: the identity field holding it and
+/// how strongly that field indicates ownership
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchInfo {
+    pub field: String,
+    pub weight: FieldWeight,
+}
+
+/// Find `id` in the identity fields (id, userId, created_by, ...) of a JSON
+/// response body, preferring a critical field over a metadata one.
+///
+/// Ids inside user-editable fields (name, email, notes, ...) are ignored.
+/// Returns None when the id is not in an identity field or the body is not
+/// JSON; use [`contains_id`] for plain-text bodies.
+pub fn contains_identity(body: &str, id: &str) -> Option<MatchInfo> {
+    find_identifier_with_weight(&parse_json_body(body)?, id)
 }
 
 /// Check for identifier in identity-specific fields with weighting.
 /// This prevents false positives where the victim ID appears in user-editable data.
-fn contains_identifier_in_identity_fields(value: &Value, identifier: &str) -> bool {
-    matches!(
-        find_identifier_with_weight(value, identifier),
-        Some(IdentityMatch {
-            found: true,
-            weight: Some(FieldWeight::Critical),
-        })
-    )
+/// Metadata fields only count when the policy promotes them.
+fn contains_identifier_in_identity_fields(
+    value: &Value,
+    identifier: &str,
+    policy: &VerdictPolicy,
+) -> bool {
+    find_identifier_with_weight(value, identifier)
+        .is_some_and(|m| m.weight == FieldWeight::Critical || policy.metadata_is_critical)
 }
 
 /// Find identifier and return its field weight for nuanced verdict
-fn find_identifier_with_weight(value: &Value, identifier: &str) -> Option<IdentityMatch> {
+fn find_identifier_with_weight(value: &Value, identifier: &str) -> Option<MatchInfo> {
     // All identity fields (critical + metadata)
     const ALL_IDENTITY_FIELDS: &[&str] = &[
         "id",
@@ -204,17 +637,19 @@ fn find_identifier_with_weight(value: &Value, identifier: &str) -> Option<Identi
                 if let Some(field_value) = obj.get(*field_name) {
                     if let Some(s) = field_value.as_str() {
                         if s == identifier {
-                            let weight = classify_identity_field(field_name);
-                            return Some(IdentityMatch {
-                                found: true,
-                                weight,
-                            });
+                            if let Some(weight) = classify_identity_field(field_name) {
+                                return Some(MatchInfo {
+                                    field: field_name.to_string(),
+                                    weight,
+                                });
+                            }
                         }
                     }
                 }
             }
 
             // Recursively check nested objects and arrays
+            let mut metadata_match = None;
             for (key, val) in obj {
                 // Skip user-editable fields that might contain arbitrary data
                 let is_editable_field = matches!(
@@ -240,36 +675,47 @@ fn find_identifier_with_weight(value: &Value, identifier: &str) -> Option<Identi
                         | "date_of_birth"
                 );
 
-                if !is_editable_field {
-                    match val {
-                        Value::Object(_) | Value::Array(_) => {
-                            if let Some(match_result) = find_identifier_with_weight(val, identifier)
-                            {
-                                if match_result.found {
-                                    return Some(match_result);
-                                }
-                            }
-                        }
-                        _ => {}
+                if !is_editable_field && (val.is_object() || val.is_array()) {
+                    let found = find_identifier_with_weight(val, identifier);
+                    if prefer_critical(&mut metadata_match, found) {
+                        return metadata_match;
                     }
                 }
             }
-            None
+            metadata_match
         }
         Value::Array(arr) => {
+            // A metadata match in one element must not hide a critical match in
+            // a later one (list endpoints return root arrays of records)
+            let mut metadata_match = None;
             for item in arr {
-                if let Some(match_result) = find_identifier_with_weight(item, identifier) {
-                    if match_result.found {
-                        return Some(match_result);
-                    }
+                let found = find_identifier_with_weight(item, identifier);
+                if prefer_critical(&mut metadata_match, found) {
+                    return metadata_match;
                 }
             }
-            None
+            metadata_match
         }
         _ => None,
     }
 }
 
+/// Keep the best match seen so far in `best`; true once it is a critical
+/// match and the search can stop
+fn prefer_critical(best: &mut Option<MatchInfo>, found: Option<MatchInfo>) -> bool {
+    match found {
+        Some(m) if m.weight == FieldWeight::Critical => {
+            *best = Some(m);
+            true
+        }
+        Some(m) if best.is_none() => {
+            *best = Some(m);
+            false
+        }
+        _ => false,
+    }
+}
+
 /// Recursively search for an identifier in JSON value
 #[allow(dead_code)]
 fn contains_identifier(value: &Value, identifier: &str) -> bool {
@@ -281,8 +727,9 @@ fn contains_identifier(value: &Value, identifier: &str) -> bool {
     }
 }
 
-/// Check if JSON response is an error response
-fn is_error_response(value: &Value) -> bool {
+/// Check if JSON response is an error response (`"success": false`, or an
+/// `error` or `message` field)
+pub fn is_error_response(value: &Value) -> bool {
     if let Value::Object(obj) = value {
         // Check for common error indicators
         if let Some(success) = obj.get("success") {
@@ -297,10 +744,22 @@ fn is_error_response(value: &Value) -> bool {
     false
 }
 
+/// Whether `id` occurs in `text` as a whole token: the characters around it
+/// can't extend it, so victim "12" is not found in "12345" or "user_12"
+pub fn contains_id(text: &str, id: &str) -> bool {
+    let is_id_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    !id.is_empty()
+        && text.match_indices(id).any(|(start, _)| {
+            let before = text[..start].chars().next_back();
+            let after = text[start + id.len()..].chars().next();
+            !before.is_some_and(is_id_char) && !after.is_some_and(is_id_char)
+        })
+}
+
 /// Fallback text-based analysis for non-JSON responses
 fn analyze_text_ownership(body: &str, attacker_id: &str, victim_id: &str) -> Verdict {
-    let has_victim = body.contains(victim_id);
-    let has_attacker = body.contains(attacker_id);
+    let has_victim = contains_id(body, victim_id);
+    let has_attacker = contains_id(body, attacker_id);
 
     if has_victim {
         Verdict::Vulnerable
@@ -417,6 +876,37 @@ mod tests {
         assert!(matches!(verdict, Verdict::Uncertain));
     }
 
+    #[test]
+    fn test_metadata_as_critical_policy() {
+        let body = r#"{"postId":"123","created_by":"victim_123","title":"Public Post"}"#;
+        let policy = VerdictPolicy {
+            metadata_is_critical: true,
+        };
+        let verdict = decide_verdict_with_policy(
+            "GET",
+            200,
+            body,
+            Some("attacker"),
+            Some("victim_123"),
+            None,
+            &policy,
+        );
+        assert_eq!(verdict, Verdict::Vulnerable);
+
+        // The attacker's own metadata match is SECURE under the same policy
+        let own = r#"{"postId":"123","created_by":"attacker"}"#;
+        let verdict = decide_verdict_with_policy(
+            "GET",
+            200,
+            own,
+            Some("attacker"),
+            Some("victim_123"),
+            None,
+            &policy,
+        );
+        assert_eq!(verdict, Verdict::Secure);
+    }
+
     #[test]
     fn test_verdict_200_with_critical_id_field() {
         // Critical field (id) - definitive ownership indicator
@@ -443,6 +933,35 @@ mod tests {
         assert!(matches!(verdict, Verdict::Vulnerable));
     }
 
+    #[test]
+    fn test_verdict_root_array_with_victim_id() {
+        let body = r#"[{"id":"other_user","name":"x"},{"id":"victim_123"}]"#;
+        let verdict = decide_verdict(200, body, Some("attacker"), Some("victim_123"));
+        assert_eq!(verdict, Verdict::Vulnerable);
+    }
+
+    #[test]
+    fn test_verdict_root_array_metadata_then_critical() {
+        // The first element only mentions the victim as its creator; the
+        // second is the victim's own record
+        let body =
+            r#"[{"id":"doc_1","created_by":"victim_123"},{"id":"doc_2","ownerId":"victim_123"}]"#;
+        let verdict = decide_verdict(200, body, Some("attacker"), Some("victim_123"));
+        assert_eq!(verdict, Verdict::Vulnerable);
+    }
+
+    #[test]
+    fn test_verdict_root_array_secure_and_empty() {
+        let body = r#"[{"id":"attacker"},{"id":"other_user"}]"#;
+        let verdict = decide_verdict(200, body, Some("attacker"), Some("victim_123"));
+        assert_eq!(verdict, Verdict::Secure);
+
+        let verdict = decide_verdict(200, "[]", Some("attacker"), Some("victim_123"));
+        assert_eq!(verdict, Verdict::Uncertain);
+        let verdict = decide_verdict(200, "[[]]", Some("attacker"), Some("victim_123"));
+        assert_eq!(verdict, Verdict::Uncertain);
+    }
+
     #[test]
     fn test_verdict_deeply_nested() {
         let body = r#"{"level1":{"level2":{"level3":{"userId":"victim_123"}}}}"#;
@@ -635,8 +1154,30 @@ mod tests {
     #[test]
     fn test_contains_identifier_in_identity_fields() {
         let json: Value = serde_json::from_str(r#"{"id":"user_123","name":"Test"}"#).unwrap();
-        assert!(contains_identifier_in_identity_fields(&json, "user_123"));
-        assert!(!contains_identifier_in_identity_fields(&json, "other_id"));
+        assert!(contains_identifier_in_identity_fields(
+            &json,
+            "user_123",
+            &VerdictPolicy::default()
+        ));
+        assert!(!contains_identifier_in_identity_fields(
+            &json,
+            "other_id",
+            &VerdictPolicy::default()
+        ));
+    }
+
+    #[test]
+    fn test_ndjson_records_are_checked_for_ownership() {
+        let body = "{\"id\":\"attacker\",\"name\":\"A\"}\n{\"id\":\"victim\",\"name\":\"V\"}\n";
+        assert_eq!(
+            analyze_response_ownership(body, "attacker", "victim"),
+            Verdict::Vulnerable
+        );
+        let own = "{\"id\":\"attacker\"}\n{\"userId\":\"attacker\"}";
+        assert_eq!(
+            analyze_response_ownership(own, "attacker", "victim"),
+            Verdict::Secure
+        );
     }
 
     #[test]
@@ -656,4 +1197,346 @@ mod tests {
         let verdict = analyze_text_ownership("User other_user", "attacker", "victim");
         assert!(matches!(verdict, Verdict::Uncertain));
     }
+
+    // ============================================
+    // Baseline Calibration Tests
+    // ============================================
+
+    #[test]
+    fn test_baseline_match_is_secure() {
+        let baseline = Baseline::new(404, r#"{"error":"User 999999 not found"}"#, "999999");
+        let verdict = decide_verdict_with_baseline(
+            "GET",
+            404,
+            r#"{"error":"User 42 not found"}"#,
+            Some("attacker"),
+            Some("42"),
+            Some(&baseline),
+        );
+        assert!(matches!(verdict, Verdict::Secure));
+    }
+
+    #[test]
+    fn test_baseline_masks_only_the_requested_id() {
+        let baseline = Baseline::new(200, r#"{"id":"999999","balance":0}"#, "999999");
+        assert!(baseline.matches(200, r#"{"id":"42","balance":0}"#, "42"));
+        // Same shape, different numbers: a real record, not the empty default
+        assert!(!baseline.matches(200, r#"{"id":"42","balance":1250}"#, "42"));
+    }
+
+    #[test]
+    fn test_baseline_mismatch_falls_back_to_normal_verdict() {
+        let baseline = Baseline::new(404, "Not found", "999999");
+        let body = r#"{"id":"victim_123","name":"Victim User"}"#;
+        let verdict = decide_verdict_with_baseline(
+            "GET",
+            200,
+            body,
+            Some("attacker_456"),
+            Some("victim_123"),
+            Some(&baseline),
+        );
+        assert!(matches!(verdict, Verdict::Vulnerable));
+
+        let verdict = decide_verdict_with_baseline(
+            "GET",
+            404,
+            "Gone",
+            Some("a"),
+            Some("42"),
+            Some(&baseline),
+        );
+        assert!(matches!(verdict, Verdict::Uncertain));
+    }
+
+    #[test]
+    fn test_static_shell_is_uncertain() {
+        let shell = "<!doctype html><div id=\"root\"></div><script src=\"/app.js\"></script>";
+        let baseline = Baseline::new(200, shell, "900000000001").with_own_response(200, shell);
+        assert!(baseline.is_static_shell(200, shell));
+        let verdict =
+            decide_verdict_with_baseline("GET", 200, shell, Some("a"), Some("42"), Some(&baseline));
+        assert_eq!(verdict, Verdict::Uncertain);
+
+        // Real data for the victim differs from the shell
+        assert!(!baseline.is_static_shell(200, r#"{"id":"42","owner":"victim"}"#));
+        // Without the attacker's own response there is no proof every id looks alike
+        assert!(!Baseline::new(200, shell, "900000000001").is_static_shell(200, shell));
+        // Identical error pages are the ordinary missing-resource case
+        let not_found = Baseline::new(404, "Not found", "9").with_own_response(404, "Not found");
+        assert!(!not_found.is_static_shell(404, "Not found"));
+    }
+
+    #[test]
+    fn test_list_length_leak() {
+        let own = r#"{"data":[{"sku":"a1"},{"sku":"a2"}]}"#;
+        let baseline =
+            Baseline::new(200, r#"{"data":[]}"#, "900000000001").with_own_response(200, own);
+        let victim = r#"{"data":[{"sku":"v1"},{"sku":"v2"},{"sku":"v3"}]}"#;
+        assert_eq!(baseline.list_leak(200, victim), Some((3, 2)));
+        let verdict = decide_verdict_with_baseline(
+            "GET",
+            200,
+            victim,
+            Some("attacker"),
+            Some("42"),
+            Some(&baseline),
+        );
+        assert_eq!(verdict, Verdict::Vulnerable);
+
+        // Empty and own-only scopes are not leaks
+        assert_eq!(baseline.list_leak(200, r#"{"data":[]}"#), None);
+        assert_eq!(baseline.list_leak(200, own), None);
+        // Placeholder items without a single value are not data
+        assert_eq!(baseline.list_leak(200, r#"{"data":[{},{},{}]}"#), None);
+        assert_eq!(
+            baseline.list_leak(200, r#"{"data":[{"sku":null},{"tags":[]}]}"#),
+            None
+        );
+        // Nor is a far shorter list
+        let big_own = Baseline::new(404, "", "9")
+            .with_own_response(200, &serde_json::json!(vec![1; 10]).to_string());
+        assert_eq!(big_own.list_leak(200, "[7]"), None);
+        assert_eq!(big_own.list_leak(200, "[7,8,9,10,11]"), Some((5, 10)));
+    }
+
+    #[test]
+    fn test_verdict_confidence() {
+        let critical = r#"{"id":"42","name":"Victim"}"#;
+        let metadata = r#"{"sku":"a1","created_by":"42"}"#;
+        assert_eq!(
+            verdict_confidence(Verdict::Vulnerable, 200, critical, None, Some("42"), None),
+            90
+        );
+        assert_eq!(
+            verdict_confidence(Verdict::Vulnerable, 200, metadata, None, Some("42"), None),
+            60
+        );
+        assert_eq!(
+            verdict_confidence(Verdict::Vulnerable, 200, "owner=42", None, Some("42"), None),
+            60
+        );
+        assert_eq!(
+            verdict_confidence(Verdict::Vulnerable, 204, "", None, Some("42"), None),
+            60
+        );
+
+        assert_eq!(
+            verdict_confidence(Verdict::Secure, 403, "", None, Some("42"), None),
+            95
+        );
+        let baseline = Baseline::new(404, "Not found", "900000000001");
+        assert_eq!(
+            verdict_confidence(
+                Verdict::Secure,
+                404,
+                "Not found",
+                None,
+                Some("42"),
+                Some(&baseline)
+            ),
+            85
+        );
+        let own = r#"{"id":"7"}"#;
+        assert_eq!(
+            verdict_confidence(Verdict::Secure, 200, own, Some("7"), Some("42"), None),
+            90
+        );
+        assert_eq!(
+            verdict_confidence(Verdict::Uncertain, 500, "", None, Some("42"), None),
+            0
+        );
+    }
+
+    #[test]
+    fn test_primary_array() {
+        let root = serde_json::json!([1, 2]);
+        assert_eq!(primary_array(&root).map(Vec::len), Some(2));
+        let wrapped = serde_json::json!({"total": 3, "items": [1, 2, 3]});
+        assert_eq!(primary_array(&wrapped).map(Vec::len), Some(3));
+        assert_eq!(primary_array(&serde_json::json!({"id": 1})), None);
+    }
+
+    #[test]
+    fn test_verdict_hooks() {
+        /// Turns UNCERTAIN on one status into VULNERABLE
+        struct Strict(u16);
+        impl VerdictHook for Strict {
+            fn refine(&self, ctx: &VerdictContext, verdict: Verdict) -> Verdict {
+                if ctx.status == self.0 && verdict == Verdict::Uncertain {
+                    Verdict::Vulnerable
+                } else {
+                    verdict
+                }
+            }
+        }
+
+        let ctx = |status, body| VerdictContext {
+            method: "GET",
+            url: "http://api/docs/42",
+            status,
+            headers: &[],
+            body,
+            attacker_id: Some("attacker"),
+            victim_id: Some("42"),
+        };
+        let hooks: Vec<Box<dyn VerdictHook>> = vec![
+            Box::new(Strict(200)),
+            Box::new(SecureMarkerHook {
+                marker: "tenant-a".to_string(),
+            }),
+        ];
+        let (verdict, notes) = run_verdict_hooks(&hooks, &ctx(200, "{}"), Verdict::Uncertain);
+        assert_eq!(verdict, Verdict::Vulnerable);
+        assert!(notes.is_empty());
+
+        // Later hooks see the earlier hooks' verdict
+        let (verdict, notes) = run_verdict_hooks(
+            &hooks,
+            &ctx(200, r#"{"tenant":"tenant-a"}"#),
+            Verdict::Uncertain,
+        );
+        assert_eq!(verdict, Verdict::Secure);
+        assert_eq!(notes, vec!["Secure marker 'tenant-a' found"]);
+
+        let (verdict, _) = run_verdict_hooks(&[], &ctx(500, ""), Verdict::Uncertain);
+        assert_eq!(verdict, Verdict::Uncertain);
+    }
+
+    #[test]
+    fn test_empty_200_baseline() {
+        // Servers that answer 200 {} for missing resources no longer look uncertain
+        let baseline = Baseline::new(200, "{}", "999999");
+        let verdict =
+            decide_verdict_with_baseline("GET", 200, "{}", Some("a"), Some("42"), Some(&baseline));
+        assert!(matches!(verdict, Verdict::Secure));
+    }
+
+    #[test]
+    fn test_probe_id_shape() {
+        assert!(Baseline::probe_id_like("42")
+            .chars()
+            .all(|c| c.is_ascii_digit()));
+        let uuid = Baseline::probe_id_like("123e4567-e89b-12d3-a456-426614174000");
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(uuid.matches('-').count(), 4);
+    }
+
+    // ============================================
+    // No Content and Redirect Tests
+    // ============================================
+
+    #[test]
+    fn test_verdict_204_on_delete_needs_corroboration() {
+        // An endpoint may answer 204 whoever's object it is
+        let verdict =
+            decide_verdict_for_method("DELETE", 204, "", Some("attacker"), Some("victim"));
+        assert!(matches!(verdict, Verdict::Uncertain));
+
+        // The missing-id probe was refused, so the 204 is specific to the victim's object
+        let baseline = Baseline::new(404, "Not found", "999999");
+        let verdict = decide_verdict_with_baseline(
+            "DELETE",
+            204,
+            "",
+            Some("attacker"),
+            Some("42"),
+            Some(&baseline),
+        );
+        assert!(matches!(verdict, Verdict::Vulnerable));
+    }
+
+    #[test]
+    fn test_verdict_204_without_victim_target() {
+        let verdict = decide_verdict_for_method("DELETE", 204, "", Some("attacker"), None);
+        assert!(matches!(verdict, Verdict::Uncertain));
+    }
+
+    #[test]
+    fn test_verdict_204_on_get_is_uncertain() {
+        let verdict = decide_verdict_for_method("GET", 204, "", Some("attacker"), Some("victim"));
+        assert!(matches!(verdict, Verdict::Uncertain));
+    }
+
+    #[test]
+    fn test_verdict_204_matching_baseline_is_secure() {
+        // Server answers 204 even for ids that do not exist
+        let baseline = Baseline::new(204, "", "999999");
+        let verdict = decide_verdict_with_baseline(
+            "DELETE",
+            204,
+            "",
+            Some("attacker"),
+            Some("42"),
+            Some(&baseline),
+        );
+        assert!(matches!(verdict, Verdict::Secure));
+    }
+
+    #[test]
+    fn test_verdict_redirect_to_login() {
+        let verdict = decide_verdict(
+            302,
+            "Redirecting to /login?next=/api/users/42",
+            Some("attacker"),
+            Some("victim"),
+        );
+        assert!(matches!(verdict, Verdict::Secure));
+
+        let verdict = decide_verdict(301, "Moved to /v2/users/42", Some("a"), Some("v"));
+        assert!(matches!(verdict, Verdict::Uncertain));
+    }
+
+    #[test]
+    fn test_is_login_redirect() {
+        assert!(is_login_redirect("https://example.com/Account/SignIn"));
+        assert!(is_login_redirect("/oauth/authorize?client_id=x"));
+        assert!(!is_login_redirect("https://example.com/api/users/42"));
+    }
+
+    #[test]
+    fn test_request_shape_mismatch_is_uncertain() {
+        for status in [405, 415] {
+            assert!(matches!(
+                decide_verdict(status, "", Some("attacker_456"), Some("victim_123")),
+                Verdict::Uncertain
+            ));
+            // Even when the baseline probe was rejected the same way
+            let baseline = Baseline::new(status, "", "900000000001");
+            assert!(matches!(
+                decide_verdict_with_baseline(
+                    "POST",
+                    status,
+                    "",
+                    None,
+                    Some("victim_123"),
+                    Some(&baseline)
+                ),
+                Verdict::Uncertain
+            ));
+        }
+    }
+
+    #[test]
+    fn test_verdict_200_with_xssi_prefix() {
+        let body = ")]}',\n{\"id\":\"victim_123\"}";
+        let verdict = decide_verdict(200, body, Some("attacker_456"), Some("victim_123"));
+        assert!(matches!(verdict, Verdict::Vulnerable));
+    }
+
+    #[test]
+    fn test_text_fallback_matches_whole_ids() {
+        // Victim "12" must not match a longer id that merely contains it
+        let verdict = analyze_text_ownership("order 123 for user 9", "9", "12");
+        assert!(matches!(verdict, Verdict::Secure));
+        let verdict = analyze_text_ownership("id=12345", "attacker", "12");
+        assert!(matches!(verdict, Verdict::Uncertain));
+        let verdict = analyze_text_ownership("owner: user_12", "attacker", "12");
+        assert!(matches!(verdict, Verdict::Uncertain));
+
+        let verdict = analyze_text_ownership("<td>12</td>", "attacker", "12");
+        assert!(matches!(verdict, Verdict::Vulnerable));
+        let verdict = analyze_text_ownership("owner=12,next=123", "attacker", "12");
+        assert!(matches!(verdict, Verdict::Vulnerable));
+    }
 }
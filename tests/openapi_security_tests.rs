@@ -242,21 +242,20 @@ fn test_malicious_symlink_traversal() {
         if symlink_result.is_ok() {
             // Create spec that tries to use the symlink
             let spec_file = format!("{}/openapi.json", test_dir);
-            let spec = format!(
-                r#"{{
+            let spec = r#"{
                 "openapi": "3.0.0",
-                "info": {{"title": "Test", "version": "1.0.0"}},
-                "paths": {{
-                    "/test": {{
-                        "get": {{
-                            "parameters": [{{
+                "info": {"title": "Test", "version": "1.0.0"},
+                "paths": {
+                    "/test": {
+                        "get": {
+                            "parameters": [{
                                 "$ref": "evil_link/passwd#/User"
-                            }}]
-                        }}
-                    }}
-                }}
-            }}"#
-            );
+                            }]
+                        }
+                    }
+                }
+            }"#
+            .to_string();
             fs::write(&spec_file, spec).expect("Should write spec file");
 
             let parser = OpenApiParser;
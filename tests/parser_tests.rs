@@ -1,6 +1,8 @@
 /// Integration tests for API collection parsers
 /// Tests OpenAPI, Postman, and Bruno parsers
-use doppel::models::CollectionParser;
+use doppel::models::{
+    ArrayStyle, CollectionParser, Method, ParameterLocation, ParseError, SecurityKind,
+};
 use doppel::parsers::bruno::BrunoParser;
 use doppel::parsers::openapi::OpenApiParser;
 use doppel::parsers::postman::PostmanParser;
@@ -251,11 +253,80 @@ fn test_postman_basic_parsing() {
     assert!(result.is_ok(), "Postman parsing should succeed");
     let endpoints = result.unwrap();
 
-    assert!(endpoints.len() >= 1, "Should parse at least 1 endpoint");
+    // Object URLs ("Get Users") and plain string URLs ("Create User") both count
+    assert_eq!(
+        endpoints.len(),
+        2,
+        "Both URL forms should yield an endpoint"
+    );
+    for method in ["GET", "POST"] {
+        let endpoint = endpoints.iter().find(|e| e.method.to_string() == method);
+        assert_eq!(
+            endpoint.map(|e| e.path.as_str()),
+            Some("https://api.example.com/users"),
+            "Should have {} endpoint",
+            method
+        );
+    }
+}
+
+#[test]
+fn test_postman_auth_token() {
+    let collection = r##"{
+        "info": {"name": "Test", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"},
+        "auth": {"type": "bearer", "bearer": [{"key": "token", "value": "{{bearerToken}}", "type": "string"}]},
+        "variable": [{"key": "bearerToken", "value": "eyJhbGciOiJIUzI1NiJ9.e30.sig"}],
+        "item": []
+    }"##;
+    assert_eq!(
+        PostmanParser.auth_token(collection).as_deref(),
+        Some("eyJhbGciOiJIUzI1NiJ9.e30.sig")
+    );
+
+    // A request-level Authorization header is used when no auth block exists
+    let header_only = r##"{
+        "item": [{"name": "Me", "request": {
+            "method": "GET",
+            "url": "https://api.example.com/me",
+            "header": [{"key": "Authorization", "value": "Bearer abc123"}]
+        }}]
+    }"##;
+    assert_eq!(
+        PostmanParser.auth_token(header_only).as_deref(),
+        Some("abc123")
+    );
+
+    // Variables defined only in a Postman environment can't be resolved
+    let environment_var = r##"{
+        "auth": {"type": "bearer", "bearer": [{"key": "token", "value": "{{envToken}}"}]},
+        "item": []
+    }"##;
+    assert_eq!(PostmanParser.auth_token(environment_var), None);
+}
+
+#[test]
+fn test_postman_url_object_without_raw() {
+    let collection = r##"{"item": [
+        {"name": "Get Order", "request": {"method": "GET", "url": {
+            "protocol": "https",
+            "host": ["api", "example", "com"],
+            "path": ["orders", ":orderId"]
+        }}},
+        {"name": "Relative", "request": {"method": "GET", "url": {"host": ["{{baseUrl}}"], "path": "users"}}},
+        {"name": "Empty", "request": {"method": "GET", "url": {}}}
+    ]}"##;
 
-    // Verify we have a GET endpoint
-    let get_endpoint = endpoints.iter().find(|e| e.method.to_string() == "GET");
-    assert!(get_endpoint.is_some(), "Should have GET endpoint");
+    let endpoints = PostmanParser
+        .parse_content(collection)
+        .expect("Should parse successfully");
+    let paths: Vec<&str> = endpoints.iter().map(|e| e.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "https://api.example.com/orders/{orderId}",
+            "{{baseUrl}}/users"
+        ]
+    );
 }
 
 #[test]
@@ -384,11 +455,49 @@ fn test_missing_file_handling() {
     let result = openapi_parser.parse(nonexistent_file);
     assert!(result.is_err(), "Should return error for missing file");
     assert!(
-        result.unwrap_err().contains("Failed to read"),
+        result.unwrap_err().to_string().contains("Failed to read"),
         "Error should mention file read failure"
     );
 }
 
+#[test]
+fn test_parse_error_variants() {
+    assert!(matches!(
+        OpenApiParser.parse("this_file_does_not_exist_12345.json"),
+        Err(ParseError::IoError { .. })
+    ));
+    assert!(matches!(
+        BrunoParser.parse("this_dir_does_not_exist_12345"),
+        Err(ParseError::IoError { .. })
+    ));
+
+    match OpenApiParser.parse_content("{\n  \"openapi\": \"3.0.0\",\n  oops\n}") {
+        Err(ParseError::JsonError { line, column, .. }) => {
+            assert_eq!((line, column), (3, 3));
+        }
+        other => panic!("expected a JSON error, got {:?}", other),
+    }
+
+    // A Postman collection is not an OpenAPI spec, and vice versa
+    let postman = r#"{"info": {"name": "c"}, "item": []}"#;
+    assert!(matches!(
+        OpenApiParser.parse_content(postman),
+        Err(ParseError::UnsupportedFormat(_))
+    ));
+    assert!(matches!(
+        PostmanParser.parse_content(r#"{"openapi": "3.0.0", "paths": {}}"#),
+        Err(ParseError::UnsupportedFormat(_))
+    ));
+    assert!(matches!(
+        PostmanParser.parse_content(postman),
+        Err(ParseError::EmptyCollection)
+    ));
+    assert!(matches!(
+        OpenApiParser.parse_content(r#"{"openapi": "3.0.0", "paths": {}}"#),
+        Err(ParseError::EmptyCollection)
+    ));
+}
+
 #[test]
 fn test_openapi_with_refs() {
     // Test OpenAPI with internal $ref references
@@ -452,3 +561,728 @@ fn test_openapi_with_refs() {
         "Should resolve ref and extract email parameter"
     );
 }
+
+#[test]
+fn test_openapi_example_values() {
+    // example, default, and enum values are captured to seed non-target fields
+    let spec = r##"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test API", "version": "1.0.0"},
+        "components": {
+            "schemas": {
+                "Currency": {"type": "string", "enum": ["USD", "EUR"]}
+            }
+        },
+        "paths": {
+            "/orders": {
+                "post": {
+                    "parameters": [
+                        {"name": "dryRun", "in": "query", "schema": {"type": "boolean", "default": false}},
+                        {"name": "region", "in": "query", "example": "eu-west-1", "schema": {"type": "string"}}
+                    ],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "userId": {"type": "string"},
+                                        "email": {"type": "string", "example": "jane@example.com"},
+                                        "quantity": {"type": "integer", "default": 1},
+                                        "currency": {"$ref": "#/components/schemas/Currency"}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"##;
+
+    let test_file = "test_openapi_examples.json";
+    fs::write(test_file, spec).expect("Should write test file");
+    let result = OpenApiParser.parse(test_file);
+    let _ = fs::remove_file(test_file);
+
+    let endpoints = result.expect("Should parse successfully");
+    let examples = &endpoints[0].example_values;
+    assert_eq!(examples["body.email"], "jane@example.com");
+    assert_eq!(examples["body.quantity"], 1);
+    assert_eq!(examples["body.currency"], "USD");
+    assert_eq!(examples["dryRun"], false);
+    assert_eq!(examples["region"], "eu-west-1");
+    assert!(!examples.contains_key("body.userId"));
+}
+
+#[test]
+fn test_openapi_request_body_example() {
+    // The media type's whole-body example is kept as the attack body template
+    let spec = r##"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test API", "version": "1.0.0"},
+        "paths": {
+            "/orders": {
+                "post": {
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {"type": "object", "properties": {"userId": {"type": "string"}}},
+                                "examples": {"basic": {"value": {"userId": "u-1", "items": [{"sku": "A-1"}]}}}
+                            }
+                        }
+                    }
+                }
+            },
+            "/notes": {
+                "post": {
+                    "requestBody": {
+                        "content": {"application/json": {"schema": {"type": "object"}}}
+                    }
+                }
+            }
+        }
+    }"##;
+
+    let endpoints = OpenApiParser
+        .parse_content(spec)
+        .expect("Should parse successfully");
+    let body = |path: &str| {
+        endpoints
+            .iter()
+            .find(|e| e.path == path)
+            .and_then(|e| e.example_body.clone())
+    };
+    assert_eq!(
+        body("/orders"),
+        Some(serde_json::json!({"userId": "u-1", "items": [{"sku": "A-1"}]}))
+    );
+    assert_eq!(body("/notes"), None);
+}
+
+#[test]
+fn test_openapi_required_flags() {
+    let spec = r##"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test API", "version": "1.0.0"},
+        "paths": {
+            "/users/{id}": {
+                "put": {
+                    "parameters": [
+                        {"name": "id", "in": "path", "schema": {"type": "integer"}},
+                        {"name": "notify", "in": "query", "schema": {"type": "boolean"}}
+                    ],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "required": ["email"],
+                                    "properties": {
+                                        "email": {"type": "string"},
+                                        "nickname": {"type": "string"}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"##;
+
+    let test_file = "test_openapi_required.json";
+    fs::write(test_file, spec).expect("Should write test file");
+    let result = OpenApiParser.parse(test_file);
+    let _ = fs::remove_file(test_file);
+
+    let endpoints = result.expect("Should parse successfully");
+    let endpoint = &endpoints[0];
+    assert_eq!(endpoint.parameters.len(), 4);
+    assert!(
+        endpoint.is_param_required("id"),
+        "path params are always required"
+    );
+    assert!(!endpoint.is_param_required("notify"));
+    assert!(endpoint.is_param_required("body.email"));
+    assert!(!endpoint.is_param_required("body.nickname"));
+
+    let id = endpoint.parameters.iter().find(|p| p.name == "id").unwrap();
+    assert_eq!(id.schema_type.as_deref(), Some("integer"));
+}
+
+#[test]
+fn test_openapi_tags_and_deprecated() {
+    let spec = r##"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test API", "version": "1.0.0"},
+        "paths": {
+            "/admin/users": {"get": {"tags": ["admin", "users"]}},
+            "/health": {"get": {"deprecated": true}}
+        }
+    }"##;
+
+    let test_file = "test_openapi_tags.json";
+    fs::write(test_file, spec).expect("Should write test file");
+    let result = OpenApiParser.parse(test_file);
+    let _ = fs::remove_file(test_file);
+
+    let endpoints = result.expect("Should parse successfully");
+    let admin = endpoints.iter().find(|e| e.path == "/admin/users").unwrap();
+    let health = endpoints.iter().find(|e| e.path == "/health").unwrap();
+    assert_eq!(admin.tags, vec!["admin", "users"]);
+    assert!(health.tags.is_empty());
+    assert!(health.deprecated);
+    assert!(!admin.deprecated);
+}
+
+#[test]
+fn test_parse_content_for_remote_specs() {
+    // Remote specs are parsed from memory; external file refs are skipped, local refs work
+    let spec = r##"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test API", "version": "1.0.0"},
+        "components": {
+            "parameters": {"UserId": {"name": "userId", "in": "path", "required": true}}
+        },
+        "paths": {
+            "/users/{userId}": {
+                "get": {"parameters": [{"$ref": "#/components/parameters/UserId"}]}
+            },
+            "/secrets": {
+                "get": {"parameters": [{"$ref": "../../etc/passwd#/name"}]}
+            }
+        }
+    }"##;
+
+    let endpoints = OpenApiParser
+        .parse_content(spec)
+        .expect("Should parse from memory");
+    assert_eq!(endpoints.len(), 2);
+    let users = endpoints
+        .iter()
+        .find(|e| e.path == "/users/{userId}")
+        .unwrap();
+    assert_eq!(users.params, vec!["userId"]);
+    let secrets = endpoints.iter().find(|e| e.path == "/secrets").unwrap();
+    assert!(secrets.params.is_empty());
+
+    let collection = r##"{"item": [{"name": "List", "request": {"method": "GET", "url": {"raw": "https://api.example.com/items"}}}]}"##;
+    let endpoints = PostmanParser
+        .parse_content(collection)
+        .expect("Should parse from memory");
+    assert_eq!(endpoints.len(), 1);
+
+    assert!(BrunoParser.parse_content("meta {}").is_err());
+}
+
+#[test]
+fn test_openapi_operation_id_as_description() {
+    let spec = r##"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test API", "version": "1.0.0"},
+        "paths": {
+            "/users": {"get": {"operationId": "listUsers", "summary": "List users"}},
+            "/orders": {"get": {"summary": "List orders"}}
+        }
+    }"##;
+
+    let endpoints = OpenApiParser
+        .parse_content(spec)
+        .expect("Should parse successfully");
+    let users = endpoints.iter().find(|e| e.path == "/users").unwrap();
+    let orders = endpoints.iter().find(|e| e.path == "/orders").unwrap();
+    assert_eq!(users.description.as_deref(), Some("listUsers"));
+    assert_eq!(orders.description.as_deref(), Some("List orders"));
+}
+
+#[test]
+fn test_postman_structured_parameters() {
+    let collection = r##"{
+        "info": {"name": "Test", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"},
+        "item": [
+            {
+                "name": "Update Order",
+                "request": {
+                    "method": "PUT",
+                    "url": {
+                        "raw": "{{baseUrl}}/users/:userId/orders/:orderId?expand=items",
+                        "query": [
+                            {"key": "expand", "value": "items"},
+                            {"key": "debug", "value": "1", "disabled": true}
+                        ]
+                    },
+                    "body": {"mode": "raw", "raw": "{\"ownerId\": \"42\", \"note\": \"x\"}"}
+                }
+            }
+        ]
+    }"##;
+
+    let endpoints = PostmanParser
+        .parse_content(collection)
+        .expect("Should parse successfully");
+    let endpoint = &endpoints[0];
+    assert_eq!(endpoint.path, "{{baseUrl}}/users/{userId}/orders/{orderId}");
+    assert_eq!(
+        endpoint.example_values.get("expand"),
+        Some(&serde_json::json!("items"))
+    );
+
+    let location = |name: &str| endpoint.parameter(name).map(|p| p.location.clone());
+    assert_eq!(location("userId"), Some(ParameterLocation::Path));
+    assert_eq!(location("orderId"), Some(ParameterLocation::Path));
+    assert_eq!(location("expand"), Some(ParameterLocation::Query));
+    assert_eq!(location("body.ownerId"), Some(ParameterLocation::Body));
+    assert!(!endpoint.is_param_required("debug"));
+    assert!(endpoint.params.contains(&"body.note".to_string()));
+    assert_eq!(
+        endpoint.example_body,
+        Some(serde_json::json!({"ownerId": "42", "note": "x"}))
+    );
+}
+
+#[test]
+fn test_bruno_structured_parameters() {
+    let test_dir = "test_bruno_structured";
+    fs::create_dir_all(test_dir).expect("Should create test directory");
+    let bruno_file = format!("{}/get-order.bru", test_dir);
+    let bruno_content = r##"{
+  "method": "GET",
+  "url": "https://api.example.com/orders/:orderId?accountId=7"
+}
+"##;
+    fs::write(&bruno_file, bruno_content).expect("Should write Bruno file");
+
+    let result = BrunoParser.parse(test_dir);
+    let _ = fs::remove_file(&bruno_file);
+    let _ = fs::remove_dir(test_dir);
+
+    let endpoints = result.expect("Should parse successfully");
+    assert_eq!(
+        endpoints[0].path,
+        "https://api.example.com/orders/{orderId}"
+    );
+    assert_eq!(
+        endpoints[0]
+            .parameter("orderId")
+            .map(|p| p.location.clone()),
+        Some(ParameterLocation::Path)
+    );
+    assert_eq!(
+        endpoints[0]
+            .parameter("accountId")
+            .map(|p| p.location.clone()),
+        Some(ParameterLocation::Query)
+    );
+}
+
+#[test]
+fn test_bruno_json_body() {
+    let test_dir = "test_bruno_body";
+    fs::create_dir_all(test_dir).expect("Should create test directory");
+    let bruno_file = format!("{}/create-order.bru", test_dir);
+    let bruno_content = r##"{
+  "method": "POST",
+  "url": "https://api.example.com/orders"
+}
+
+body:json {
+  {
+    "ownerId": "42",
+    "lines": [{"sku": "A-1"}]
+  }
+}
+"##;
+    fs::write(&bruno_file, bruno_content).expect("Should write Bruno file");
+
+    let result = BrunoParser.parse(test_dir);
+    let _ = fs::remove_file(&bruno_file);
+    let _ = fs::remove_dir(test_dir);
+
+    let endpoints = result.expect("Should parse successfully");
+    assert_eq!(
+        endpoints[0].example_body,
+        Some(serde_json::json!({"ownerId": "42", "lines": [{"sku": "A-1"}]}))
+    );
+    assert_eq!(
+        endpoints[0]
+            .parameter("body.ownerId")
+            .map(|p| p.location.clone()),
+        Some(ParameterLocation::Body)
+    );
+}
+
+#[test]
+fn test_bruno_collection_settings() {
+    let test_dir = "test_bruno_settings";
+    let folder = format!("{}/orders", test_dir);
+    fs::create_dir_all(&folder).expect("Should create test directory");
+    fs::write(
+        format!("{}/bruno.json", test_dir),
+        r#"{"version": "1", "name": "shop", "presets": {"requestUrl": "https://api.example.com/v1/"}}"#,
+    )
+    .expect("Should write bruno.json");
+    fs::write(
+        format!("{}/collection.bru", test_dir),
+        "vars:pre-request {\n  host: https://collection.example.com\n  ~unused: x\n}\n",
+    )
+    .expect("Should write collection.bru");
+    fs::write(
+        format!("{}/folder.bru", folder),
+        "vars:pre-request {\n  host: https://orders.example.com\n}\n",
+    )
+    .expect("Should write folder.bru");
+    fs::write(
+        format!("{}/get-order.bru", folder),
+        r#"{
+  "method": "GET",
+  "url": "{{host}}/orders/:orderId"
+}
+"#,
+    )
+    .expect("Should write Bruno file");
+    fs::write(
+        format!("{}/list-users.bru", test_dir),
+        r#"{
+  "method": "GET",
+  "url": "/users"
+}
+"#,
+    )
+    .expect("Should write Bruno file");
+
+    let result = BrunoParser.parse(test_dir);
+    let _ = fs::remove_dir_all(test_dir);
+
+    let mut paths: Vec<String> = result
+        .expect("Should parse successfully")
+        .into_iter()
+        .map(|e| e.path)
+        .collect();
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            "https://api.example.com/v1/users",
+            "https://orders.example.com/orders/{orderId}",
+        ]
+    );
+}
+
+#[test]
+fn test_postman_string_url_query_params() {
+    let collection = r##"{
+        "info": {"name": "Test", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"},
+        "item": [
+            {
+                "name": "Search Users",
+                "request": {
+                    "method": "GET",
+                    "url": "https://api.example.com/users?role=admin&active=true&owner%20id=42"
+                }
+            }
+        ]
+    }"##;
+
+    let endpoints = PostmanParser
+        .parse_content(collection)
+        .expect("Should parse successfully");
+    assert_eq!(endpoints.len(), 1, "string URLs should not be skipped");
+    let endpoint = &endpoints[0];
+    assert_eq!(endpoint.path, "https://api.example.com/users");
+    for key in ["role", "active", "owner id"] {
+        assert_eq!(
+            endpoint.parameter(key).map(|p| p.location.clone()),
+            Some(ParameterLocation::Query),
+            "{} should be a query param",
+            key
+        );
+    }
+    assert_eq!(
+        endpoint.example_values.get("owner id"),
+        Some(&serde_json::json!("42"))
+    );
+}
+
+#[test]
+fn test_commented_json_with_trailing_commas_parses() {
+    let collection = r##"{
+        // Exported by hand, then edited
+        "item": [
+            {
+                "name": "Get User", /* the one we care about */
+                "request": {
+                    "method": "GET",
+                    "url": "https://api.example.com/users/{userId}?note=a//b,}",
+                },
+            },
+        ],
+    }"##;
+    let endpoints = PostmanParser
+        .parse_content(collection)
+        .expect("comments and trailing commas should be tolerated");
+    assert_eq!(endpoints.len(), 1);
+    // Comment markers and commas inside strings are left alone
+    assert_eq!(
+        endpoints[0].example_values.get("note"),
+        Some(&serde_json::json!("a//b,}"))
+    );
+
+    // Input that is broken beyond that still reports the strict error
+    let err = PostmanParser
+        .parse_content("{\n  \"item\": [,\n}")
+        .unwrap_err();
+    assert!(
+        matches!(err, ParseError::JsonError { line: 2, .. }),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn test_repeated_query_keys_keep_every_value() {
+    let collection = r##"{"item": [
+        {"request": {"method": "GET", "url": "https://api.example.com/orders?id=1&id=2&page=3"}}
+    ]}"##;
+    let endpoints = PostmanParser
+        .parse_content(collection)
+        .expect("Should parse successfully");
+    let endpoint = &endpoints[0];
+    assert_eq!(
+        endpoint.example_values.get("id"),
+        Some(&serde_json::json!(["1", "2"]))
+    );
+    assert_eq!(
+        endpoint.example_values.get("page"),
+        Some(&serde_json::json!("3"))
+    );
+}
+
+#[test]
+fn test_postman_custom_methods() {
+    // WebDAV verbs are kept instead of silently dropped
+    let collection = r##"{"item": [
+        {"name": "List", "request": {"method": "PROPFIND", "url": "https://dav.example.com/files/:fileId"}},
+        {"name": "Bad", "request": {"method": "NOT A VERB", "url": "https://dav.example.com/x"}}
+    ]}"##;
+
+    let endpoints = PostmanParser
+        .parse_content(collection)
+        .expect("Should parse successfully");
+    assert_eq!(endpoints.len(), 1);
+    assert_eq!(endpoints[0].method, Method::Other("PROPFIND".to_string()));
+    assert_eq!(endpoints[0].method.to_string(), "PROPFIND");
+}
+
+#[test]
+fn test_openapi_security_requirements() {
+    let spec = r##"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test API", "version": "1.0.0"},
+        "components": {"securitySchemes": {
+            "bearerAuth": {"type": "http", "scheme": "bearer"},
+            "apiKeyAuth": {"type": "apiKey", "in": "header", "name": "X-API-Key"}
+        }},
+        "security": [{"bearerAuth": []}],
+        "paths": {
+            "/users/{id}": {"get": {}},
+            "/health": {"get": {"security": []}},
+            "/reports/{id}": {"get": {"security": [{"apiKeyAuth": []}]}},
+            "/orders/{id}": {"get": {"security": [{"apiKeyAuth": []}, {"bearerAuth": []}]}}
+        }
+    }"##;
+
+    let endpoints = OpenApiParser
+        .parse_content(spec)
+        .expect("Should parse successfully");
+    let endpoint = |path: &str| endpoints.iter().find(|e| e.path == path).unwrap();
+
+    assert_eq!(
+        endpoint("/users/{id}").security,
+        Some(vec![SecurityKind::Bearer])
+    );
+    assert!(endpoint("/health").is_public());
+    assert!(endpoint("/reports/{id}").rejects_bearer());
+    // Either scheme satisfies the operation, so the bearer token fits
+    assert!(!endpoint("/orders/{id}").rejects_bearer());
+    assert!(!endpoint("/orders/{id}").is_public());
+}
+
+#[test]
+fn test_openapi_cookie_parameters() {
+    let spec = r##"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test API", "version": "1.0.0"},
+        "paths": {
+            "/cart": {
+                "get": {"parameters": [{"name": "cartId", "in": "cookie", "required": true}]}
+            }
+        }
+    }"##;
+
+    let endpoints = OpenApiParser
+        .parse_content(spec)
+        .expect("Should parse successfully");
+    assert_eq!(
+        endpoints[0].parameter("cartId").map(|p| p.location.clone()),
+        Some(ParameterLocation::Cookie)
+    );
+}
+
+#[test]
+fn test_openapi_array_query_styles() {
+    let spec = r##"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test API", "version": "1.0.0"},
+        "paths": {
+            "/orders": {
+                "get": {"parameters": [
+                    {"name": "ids", "in": "query", "schema": {"type": "array", "items": {"type": "string"}}},
+                    {"name": "tags", "in": "query", "explode": false, "schema": {"type": "array"}},
+                    {"name": "refs", "in": "query", "style": "pipeDelimited", "explode": false, "schema": {"type": "array"}},
+                    {"name": "limit", "in": "query", "schema": {"type": "integer"}}
+                ]}
+            }
+        }
+    }"##;
+
+    let endpoints = OpenApiParser
+        .parse_content(spec)
+        .expect("Should parse successfully");
+    let style = |name: &str| endpoints[0].parameter(name).and_then(|p| p.array_style);
+    assert_eq!(style("ids"), Some(ArrayStyle::Repeated));
+    assert_eq!(style("tags"), Some(ArrayStyle::Delimited(',')));
+    assert_eq!(style("refs"), Some(ArrayStyle::Delimited('|')));
+    assert_eq!(style("limit"), None);
+}
+
+#[test]
+fn test_openapi_path_and_operation_servers() {
+    let spec = r##"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test API", "version": "1.0.0"},
+        "servers": [{"url": "https://api.example.com/v1/"}],
+        "paths": {
+            "/users/{id}": {
+                "get": {},
+                "delete": {"servers": [{"url": "https://admin.example.com"}]}
+            },
+            "/files/{id}": {
+                "servers": [{"url": "https://{region}.files.example.com", "variables": {"region": {"default": "eu"}}}],
+                "get": {}
+            }
+        }
+    }"##;
+
+    let endpoints = OpenApiParser
+        .parse_content(spec)
+        .expect("Should parse successfully");
+    let path_of = |method: Method, suffix: &str| {
+        endpoints
+            .iter()
+            .find(|e| e.method == method && e.path.ends_with(suffix))
+            .map(|e| e.path.clone())
+            .unwrap()
+    };
+    assert_eq!(
+        path_of(Method::GET, "/users/{id}"),
+        "https://api.example.com/v1/users/{id}"
+    );
+    assert_eq!(
+        path_of(Method::DELETE, "/users/{id}"),
+        "https://admin.example.com/users/{id}"
+    );
+    assert_eq!(
+        path_of(Method::GET, "/files/{id}"),
+        "https://eu.files.example.com/files/{id}"
+    );
+}
+
+#[test]
+fn test_postman_folder_depth_limit() {
+    // Each folder holds one request plus the next folder, 40 levels deep
+    let mut folder = serde_json::json!([]);
+    for level in (0..40).rev() {
+        folder = serde_json::json!([
+            {"name": format!("req{}", level), "request": {"method": "GET", "url": format!("https://api/{}", level)}},
+            {"name": format!("folder{}", level), "item": folder}
+        ]);
+    }
+    let collection = serde_json::json!({"info": {"name": "Deep"}, "item": folder}).to_string();
+
+    let endpoints = PostmanParser
+        .parse_content(&collection)
+        .expect("Malformed nesting should not fail the parse");
+    assert!(endpoints.iter().any(|e| e.path == "https://api/0"));
+    assert!(
+        endpoints.len() < 40,
+        "folders past the depth limit are skipped"
+    );
+}
+
+#[test]
+fn test_large_openapi_spec_keeps_path_order() {
+    // Enough paths to be parsed on several threads
+    let paths: Vec<String> = (0..600)
+        .map(|i| {
+            format!(
+                r#""/items{:04}/{{id}}": {{"get": {{"operationId": "get{}"}}, "delete": {{}}}}"#,
+                i, i
+            )
+        })
+        .collect();
+    let spec = format!(
+        r#"{{"openapi": "3.0.0", "info": {{"title": "Big", "version": "1"}}, "paths": {{{}}}}}"#,
+        paths.join(",")
+    );
+
+    let endpoints = OpenApiParser
+        .parse_content(&spec)
+        .expect("Should parse successfully");
+    assert_eq!(endpoints.len(), 1200);
+    let keys: Vec<(String, String)> = endpoints
+        .iter()
+        .map(|e| (e.path.clone(), e.method.to_string()))
+        .collect();
+    let mut sorted = keys.clone();
+    sorted.sort();
+    assert_eq!(keys, sorted);
+    assert_eq!(endpoints[1].description.as_deref(), Some("get0"));
+}
+
+#[test]
+fn test_openapi_content_parameter() {
+    let spec = r##"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test API", "version": "1.0.0"},
+        "paths": {
+            "/orders": {
+                "get": {
+                    "parameters": [{
+                        "name": "filter",
+                        "in": "query",
+                        "content": {
+                            "application/json": {
+                                "schema": {"type": "object"},
+                                "example": {"ownerId": 5}
+                            }
+                        }
+                    }]
+                }
+            }
+        }
+    }"##;
+
+    let endpoints = OpenApiParser
+        .parse_content(spec)
+        .expect("Should parse successfully");
+    let filter = endpoints[0].parameter("filter").expect("filter parameter");
+    assert_eq!(filter.content_type.as_deref(), Some("application/json"));
+    assert_eq!(filter.schema_type.as_deref(), Some("object"));
+    assert!(filter.is_json_encoded());
+    assert_eq!(filter.array_style, None);
+    assert_eq!(
+        endpoints[0].example_values.get("filter"),
+        Some(&serde_json::json!({"ownerId": 5}))
+    );
+}
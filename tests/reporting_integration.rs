@@ -1,18 +1,23 @@
+use doppel::models::ScanResult;
 use std::fs;
+use std::path::Path;
 
 #[test]
 fn reporting_exports_create_files() {
     // call the reporting functions
-    let results = vec![(
-        "GET".to_string(),
-        "/api/users/1".to_string(),
-        "VULNERABLE".to_string(),
-    )];
+    let results = vec![ScanResult::new("GET", "/api/users/1", "VULNERABLE")];
+
+    let dir = std::env::temp_dir().join(format!("doppel_exports_{}", std::process::id()));
+    let config = doppel::reporting::ReportConfig {
+        output_dir: Some(dir.clone()),
+        ..Default::default()
+    };
 
     // Use the library functions - they now return filenames with timestamps
-    let csv_filename = doppel::reporting::export_csv(&results).expect("CSV export should succeed");
-    let md_filename =
-        doppel::reporting::export_markdown(&results).expect("Markdown export should succeed");
+    let csv_filename = doppel::reporting::export_csv_with_config(&results, &config)
+        .expect("CSV export should succeed");
+    let md_filename = doppel::reporting::export_markdown_with_config(&results, &config)
+        .expect("Markdown export should succeed");
 
     // Check files exist with the returned filenames
     assert!(
@@ -27,12 +32,584 @@ fn reporting_exports_create_files() {
     );
 
     // Verify filenames contain timestamp pattern
-    assert!(csv_filename.starts_with("doppel_report_"));
+    let name = |path: &str| {
+        Path::new(path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(str::to_string)
+            .unwrap_or_default()
+    };
+    assert!(name(&csv_filename).starts_with("doppel_report_"));
     assert!(csv_filename.ends_with(".csv"));
-    assert!(md_filename.starts_with("doppel_report_"));
+    assert!(name(&md_filename).starts_with("doppel_report_"));
     assert!(md_filename.ends_with(".md"));
 
     // Clean up
-    let _ = fs::remove_file(&csv_filename);
-    let _ = fs::remove_file(&md_filename);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn markdown_includes_remediation_for_actionable_results() {
+    let results = vec![
+        ScanResult::new("GET", "/api/users/1", "VULNERABLE"),
+        ScanResult::new("GET", "/api/health", "SECURE"),
+    ];
+
+    let content =
+        doppel::reporting::render_markdown(&results, &doppel::reporting::ReportConfig::default());
+
+    assert!(content.contains(&format!(
+        "  - Remediation: {}",
+        doppel::reporting::DEFAULT_REMEDIATION
+    )));
+    // Only the VULNERABLE result gets advice
+    assert_eq!(content.matches("Remediation:").count(), 1);
+}
+
+#[test]
+fn remediation_text_is_overridable() {
+    let config = doppel::reporting::ReportConfig {
+        remediation_override: Some("Follow the internal authz playbook".to_string()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        config.remediation_for("UNCERTAIN", "GET /api/users/1"),
+        Some("Follow the internal authz playbook".to_string())
+    );
+    assert_eq!(config.remediation_for("SECURE", "GET /api/users/1"), None);
+    assert!(
+        doppel::reporting::remediation_for("VULNERABLE", "DELETE /api/users/1")
+            .unwrap()
+            .contains("before applying any modification")
+    );
+}
+
+#[test]
+fn json_report_round_trips() {
+    let results = vec![
+        ScanResult::new("GET", "/api/users/1", "VULNERABLE | PII: YES"),
+        ScanResult::new("DELETE", "/api/users/1", "SECURE"),
+    ];
+
+    let dir = std::env::temp_dir().join(format!("doppel_json_{}", std::process::id()));
+    let config = doppel::reporting::ReportConfig {
+        output_dir: Some(dir.clone()),
+        ..Default::default()
+    };
+    let json_filename = doppel::reporting::export_json_with_config(&results, &config)
+        .expect("JSON export should succeed");
+    let loaded = doppel::reporting::load_json_report(&json_filename);
+    let _ = fs::remove_dir_all(&dir);
+
+    assert_eq!(loaded.expect("report should load"), results);
+}
+
+#[test]
+fn report_diff_classifies_changes() {
+    let old = vec![
+        ScanResult::new("GET", "/api/users/1", "VULNERABLE"),
+        ScanResult::new("GET", "/api/orders/1", "SECURE"),
+        ScanResult::new("GET", "/api/posts/1", "SECURE"),
+        ScanResult::new("GET", "/api/files/1", "UNCERTAIN"),
+    ];
+    let new = vec![
+        ScanResult::new("GET", "/api/users/1", "SECURE"),
+        // Worst verdict per endpoint wins when mutations share a URL
+        ScanResult::new("GET", "/api/orders/1", "SECURE"),
+        ScanResult::new("GET", "/api/orders/1", "VULNERABLE | PII: YES"),
+        ScanResult::new("GET", "/api/posts/1", "SECURE"),
+        ScanResult::new("GET", "/api/files/1", "ERROR: timeout"),
+    ];
+
+    let diff = doppel::reporting::report_diff(&old, &new);
+
+    assert_eq!(diff.newly_vulnerable.len(), 1);
+    assert_eq!(diff.newly_vulnerable[0].finding, "/api/orders/1");
+    assert_eq!(diff.fixed.len(), 1);
+    assert_eq!(diff.fixed[0].finding, "/api/users/1");
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].old_verdict.as_deref(), Some("UNCERTAIN"));
+    assert_eq!(diff.changed[0].new_verdict.as_deref(), Some("ERROR"));
+
+    assert!(doppel::reporting::report_diff(&new, &new).is_empty());
+}
+
+#[test]
+fn dedupe_groups_mutations_of_the_same_endpoint() {
+    let results = vec![
+        ScanResult::new("GET", "http://api/users/122", "VULNERABLE").with_value("122"),
+        ScanResult::new("GET", "http://api/users/124", "VULNERABLE").with_value("124"),
+        ScanResult::new("GET", "http://api/users/admin", "VULNERABLE").with_value("admin"),
+        ScanResult::new("GET", "http://api/users/", "SECURE").with_value(""),
+        ScanResult::new("DELETE", "http://api/users/122", "VULNERABLE").with_value("122"),
+    ];
+
+    let grouped = doppel::reporting::dedupe_results(&results, false);
+
+    assert_eq!(grouped.len(), 3);
+    assert_eq!(grouped[0].url, "http://api/users/{id}");
+    assert_eq!(grouped[0].count, 3);
+    assert_eq!(grouped[0].triggering_values, vec!["122", "124", "admin"]);
+    assert_eq!(grouped[1].url, "http://api/users/{id}");
+    assert_eq!(grouped[1].verdict, "SECURE");
+    assert_eq!(grouped[2].method, "DELETE");
+
+    let md =
+        doppel::reporting::render_markdown(&grouped, &doppel::reporting::ReportConfig::default());
+    assert!(
+        md.contains("- **GET** http://api/users/{id}: VULNERABLE (x3, values: 122, 124, admin)")
+    );
+}
+
+#[test]
+fn path_template_keeps_static_segments() {
+    assert_eq!(
+        doppel::reporting::path_template(
+            "https://api.example.com/api/v1/users/42/posts",
+            &[],
+            false
+        ),
+        "https://api.example.com/api/v1/users/{id}/posts"
+    );
+    assert_eq!(
+        doppel::reporting::path_template(
+            "/orders/ord_7?expand=items",
+            &["ord_7".to_string()],
+            false
+        ),
+        "/orders/{id}?expand=items"
+    );
+}
+
+#[test]
+fn paths_are_canonicalized_for_grouping_and_ids() {
+    use doppel::reporting::{dedupe_results, finding_id, normalize_path};
+    assert_eq!(
+        normalize_path("https://api.example.com//users/1/?a=b", false),
+        "https://api.example.com/users/1?a=b"
+    );
+    assert_eq!(
+        normalize_path("https://api.example.com/", false),
+        "https://api.example.com/"
+    );
+    assert_eq!(normalize_path("/Users/1", false), "/Users/1");
+    assert_eq!(normalize_path("/Users/1?Q=X", true), "/users/1?Q=X");
+
+    let results = vec![
+        ScanResult::new("GET", "/users/1", "SECURE"),
+        ScanResult::new("GET", "/users/2/", "SECURE"),
+        ScanResult::new("GET", "//users/3", "SECURE"),
+    ];
+    let grouped = dedupe_results(&results, false);
+    assert_eq!(grouped.len(), 1);
+    assert_eq!(grouped[0].url, "/users/{id}");
+    assert_eq!(
+        finding_id(&results[0], false),
+        finding_id(&results[1], false)
+    );
+
+    // Case stays significant unless folded explicitly
+    let upper = ScanResult::new("GET", "/Users/4", "SECURE");
+    assert_ne!(finding_id(&upper, false), finding_id(&results[0], false));
+    assert_eq!(finding_id(&upper, true), finding_id(&results[0], true));
+
+    // Folding groups by a lowercased key but keeps the reported URL as sent,
+    // and still recognizes upper-case mutations of a case-sensitive id
+    let slugs = vec![
+        ScanResult::new("GET", "/Teams/AbC", "VULNERABLE").with_value("AbC"),
+        ScanResult::new("GET", "/teams/ABC", "VULNERABLE").with_value("ABC"),
+    ];
+    assert_eq!(dedupe_results(&slugs, false).len(), 2);
+    let grouped = dedupe_results(&slugs, true);
+    assert_eq!(grouped.len(), 1);
+    assert_eq!(grouped[0].url, "/Teams/{id}");
+    assert_eq!(grouped[0].triggering_values, vec!["AbC", "ABC"]);
+
+    // An injected empty string keeps its empty segment
+    assert_eq!(
+        doppel::reporting::path_template("/users/", &["".to_string()], false),
+        "/users/{id}"
+    );
+}
+
+#[test]
+fn latency_stats_summarize_measured_results() {
+    let results = vec![
+        ScanResult::new("GET", "/a", "SECURE").with_latency(40),
+        ScanResult::new("GET", "/b", "SECURE").with_latency(10),
+        ScanResult::new("GET", "/c", "SECURE"),
+        ScanResult::new("GET", "/d", "SECURE").with_latency(30),
+        ScanResult::new("GET", "/e", "SECURE").with_latency(100),
+    ];
+
+    let stats = doppel::reporting::latency_stats(&results).expect("stats should exist");
+    assert_eq!(stats.min_ms, 10);
+    assert_eq!(stats.median_ms, 35);
+    assert_eq!(stats.max_ms, 100);
+
+    assert!(doppel::reporting::latency_stats(&[ScanResult::new("GET", "/a", "SECURE")]).is_none());
+
+    let md = doppel::reporting::render_markdown(
+        &results[..1],
+        &doppel::reporting::ReportConfig::default(),
+    );
+    assert!(md.contains("- **GET** /a: SECURE (40 ms)"));
+}
+
+#[test]
+fn reports_show_the_injected_values() {
+    let dir = std::env::temp_dir().join(format!("doppel_injected_{}", std::process::id()));
+    let config = doppel::reporting::ReportConfig {
+        output_dir: Some(dir.clone()),
+        ..Default::default()
+    };
+    let results = vec![
+        ScanResult::new("GET", "/orgs/admin/users/admin", "VULNERABLE")
+            .with_value("admin")
+            .with_substitutions([
+                ("userId".to_string(), "admin".to_string()),
+                ("orgId".to_string(), "admin".to_string()),
+            ]),
+    ];
+    assert_eq!(results[0].injected_label(), "orgId=admin, userId=admin");
+
+    let md = doppel::reporting::render_markdown(&results, &config);
+    assert!(md.contains("(injected: orgId=admin, userId=admin)"));
+    let csv =
+        fs::read_to_string(doppel::reporting::export_csv_with_config(&results, &config).unwrap())
+            .unwrap();
+    assert!(csv.contains("\"orgId=admin, userId=admin\""));
+    let json = doppel::reporting::export_json_with_config(&results, &config).unwrap();
+    assert_eq!(doppel::reporting::load_json_report(&json).unwrap(), results);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn csv_splits_status_and_notes_into_columns() {
+    let dir = std::env::temp_dir().join(format!("doppel_csv_columns_{}", std::process::id()));
+    let config = doppel::reporting::ReportConfig {
+        output_dir: Some(dir.clone()),
+        ..Default::default()
+    };
+    let results = vec![
+        ScanResult::new("GET", "/users/5", "VULNERABLE | PII: YES")
+            .with_status(Some(200))
+            .with_risk_score(Some(90)),
+        ScanResult::new("GET", "/users/6", "SECURE | Soft fail: 'error'").with_status(Some(200)),
+        ScanResult::new("GET", "/users/7", "ERROR: timeout"),
+    ];
+    assert_eq!(results[0].verdict_note("PII"), Some("YES"));
+    assert_eq!(results[1].soft_fail(), Some("error"));
+
+    let csv =
+        fs::read_to_string(doppel::reporting::export_csv_with_config(&results, &config).unwrap())
+            .unwrap();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert!(rows[1].starts_with("GET,/users/5,VULNERABLE | PII: YES,200,Critical,,YES,90,"));
+    assert!(rows[2].starts_with("GET,/users/6,SECURE | Soft fail: 'error',200,Info,error,,,"));
+    assert!(rows[3].starts_with("GET,/users/7,ERROR: timeout,,Info,,,,"));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn report_diff_keeps_operations_with_the_same_summary_apart() {
+    let name = || Some("Get item".to_string());
+    let old = vec![
+        ScanResult::new("GET", "/orders/1", "VULNERABLE").with_name(name()),
+        ScanResult::new("GET", "/invoices/1", "SECURE").with_name(name()),
+    ];
+    let new = vec![
+        ScanResult::new("GET", "/orders/1", "VULNERABLE").with_name(name()),
+        ScanResult::new("GET", "/invoices/1", "VULNERABLE").with_name(name()),
+    ];
+
+    // Keyed by name both would collapse to one VULNERABLE entry and the
+    // newly vulnerable invoice endpoint would be lost
+    let diff = doppel::reporting::report_diff(&old, &new);
+    assert_eq!(diff.newly_vulnerable.len(), 1);
+    assert_eq!(diff.newly_vulnerable[0].finding, "Get item");
+    assert!(diff.fixed.is_empty() && diff.changed.is_empty());
+}
+
+#[test]
+fn markdown_annotates_deprecated_findings() {
+    let results = vec![ScanResult::new("GET", "/v1/users/1", "VULNERABLE").with_deprecated(true)];
+    let md =
+        doppel::reporting::render_markdown(&results, &doppel::reporting::ReportConfig::default());
+    assert!(md.contains("- **GET** /v1/users/1: VULNERABLE [deprecated]"));
+}
+
+#[test]
+fn operation_name_is_reported_in_diffs() {
+    let old = vec![
+        ScanResult::new("GET", "/v2/users/1", "SECURE").with_name(Some("getUser".to_string()))
+    ];
+    let new =
+        vec![ScanResult::new("GET", "/v2/users/1", "VULNERABLE")
+            .with_name(Some("getUser".to_string()))];
+
+    let diff = doppel::reporting::report_diff(&old, &new);
+    assert_eq!(diff.newly_vulnerable.len(), 1);
+    assert_eq!(diff.newly_vulnerable[0].finding, "getUser");
+    assert!(diff.changed.is_empty());
+
+    let md = doppel::reporting::render_markdown(&new, &doppel::reporting::ReportConfig::default());
+    assert!(md.contains("- **GET** /v2/users/1 (getUser): VULNERABLE"));
+}
+
+#[test]
+fn reports_are_written_to_output_dir() {
+    let dir = std::env::temp_dir().join(format!("doppel_output_dir_{}", std::process::id()));
+    let config = doppel::reporting::ReportConfig {
+        output_dir: Some(dir.join("nested")),
+        ..Default::default()
+    };
+    let results = vec![ScanResult::new("GET", "/users/1", "SECURE")];
+
+    let csv = doppel::reporting::export_csv_with_config(&results, &config)
+        .expect("CSV export should succeed");
+    let json = doppel::reporting::export_json_with_config(&results, &config)
+        .expect("JSON export should succeed");
+    let md = doppel::reporting::export_markdown_with_config(&results, &config)
+        .expect("Markdown export should succeed");
+
+    for path in [&csv, &json, &md] {
+        assert!(
+            path.starts_with(dir.to_str().unwrap()),
+            "{} not in output dir",
+            path
+        );
+        assert!(Path::new(path).exists());
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn summary_flags_rejected_token() {
+    let denied = |url: &str, note: &str| ScanResult::new("GET", url, format!("SECURE | {}", note));
+    let results = vec![
+        denied("/users/1", doppel::NOT_AUTHENTICATED_NOTE),
+        denied("/users/2", doppel::NOT_AUTHENTICATED_NOTE),
+        ScanResult::new("GET", "/orders/7", "SECURE").with_status(Some(403)),
+    ];
+    let summary = doppel::reporting::summarize(&results, false);
+    assert_eq!(summary.secure, 3);
+    assert_eq!(summary.unauthenticated, 2);
+    assert!(summary.token_rejected());
+
+    // Object-level denials alone are a working check, not a broken token
+    let summary = doppel::reporting::summarize(&results[2..], false);
+    assert_eq!(summary.unauthenticated, 0);
+    assert!(!summary.token_rejected());
+}
+
+#[test]
+fn summary_counts_outcomes() {
+    let results = vec![
+        ScanResult::new("GET", "/users/1", "VULNERABLE | PII: YES").with_value("1"),
+        ScanResult::new("GET", "/users/2", "VULNERABLE | PII: NO").with_value("2"),
+        ScanResult::new("GET", "/orders/7", "SECURE"),
+        ScanResult::new("DELETE", "/users/1", "UNCERTAIN"),
+        ScanResult::new("GET", "/health", "ERROR: timeout"),
+    ];
+
+    let summary = doppel::reporting::summarize(&results, false);
+    assert_eq!(summary.endpoints_scanned, 4);
+    assert_eq!(summary.requests_sent, 5);
+    assert_eq!(summary.vulnerable, 2);
+    assert_eq!(summary.secure, 1);
+    assert_eq!(summary.uncertain, 1);
+    assert_eq!(summary.errors, 1);
+    assert_eq!(summary.with_pii, 1);
+
+    assert_eq!(summary.unauthenticated, 0);
+    assert!(!summary.token_rejected());
+
+    // Grouping does not change the totals
+    let grouped = doppel::reporting::dedupe_results(&results, false);
+    assert_eq!(
+        doppel::reporting::summarize(&grouped, false).requests_sent,
+        5
+    );
+
+    let md = doppel::reporting::render_markdown(
+        &results,
+        &doppel::reporting::ReportConfig {
+            summary: Some(summary.with_duration(std::time::Duration::from_millis(1500))),
+            ..Default::default()
+        },
+    );
+    assert!(md.contains("| Requests sent | 5 |"));
+    assert!(md.contains("| VULNERABLE | 2 |"));
+    assert!(md.contains("| ERROR | 1 |"));
+    assert!(md.contains("| Duration | 1.5 s |"));
+}
+
+#[test]
+fn coverage_separates_skipped_and_inconclusive_endpoints() {
+    use doppel::models::{Endpoint, Method};
+    let endpoint =
+        |method: Method, path: &str| Endpoint::new(method, path.to_string(), None, vec![]);
+    let endpoints = vec![
+        endpoint(Method::GET, "{{baseUrl}}/users/{userId}"),
+        endpoint(Method::GET, "/orders/{orderId}"),
+        endpoint(Method::DELETE, "/users/{userId}"),
+        endpoint(Method::GET, "/health"),
+    ];
+    let results = vec![
+        ScanResult::new("GET", "http://api/users/1", "SECURE"),
+        ScanResult::new("GET", "http://api/users/2", "UNCERTAIN"),
+        ScanResult::new("GET", "http://api/orders/7", "UNCERTAIN"),
+        ScanResult::new("GET", "http://api/orders/8", "ERROR: timeout"),
+    ];
+
+    let coverage = doppel::reporting::coverage(&results, &endpoints);
+    assert_eq!(coverage.endpoints_total, 4);
+    assert_eq!(coverage.endpoints_tested, 2);
+    assert_eq!(coverage.endpoints_skipped, 2);
+    assert_eq!(coverage.endpoints_conclusive, 1);
+    assert_eq!(coverage.tested_line(), "2/4 (50%)");
+
+    let mut summary = doppel::reporting::summarize(&results, false);
+    summary.coverage = Some(coverage);
+    let md = doppel::reporting::render_markdown(
+        &results,
+        &doppel::reporting::ReportConfig {
+            summary: Some(summary),
+            ..Default::default()
+        },
+    );
+    assert!(md.contains("| Endpoints tested | 2/4 (50%) |"));
+    assert!(md.contains("| Conclusive endpoints | 1/2 (50%) |"));
+}
+
+#[test]
+fn json_report_loads_with_and_without_summary() {
+    let dir = std::env::temp_dir().join(format!("doppel_json_summary_{}", std::process::id()));
+    let config = doppel::reporting::ReportConfig {
+        output_dir: Some(dir.clone()),
+        ..Default::default()
+    };
+    let results = vec![ScanResult::new("GET", "/users/1", "SECURE")];
+    let path = doppel::reporting::export_json_with_config(&results, &config)
+        .expect("JSON export should succeed");
+    let written: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(written["summary"]["secure"], 1);
+    assert_eq!(written["results"][0]["severity"], "Info");
+    assert!(written["results"][0].get("owasp").is_none());
+    assert_eq!(
+        written["results"][0]["finding_id"],
+        doppel::reporting::finding_id(&results[0], false)
+    );
+    assert_eq!(doppel::reporting::load_json_report(&path).unwrap(), results);
+
+    // Older reports are a bare array
+    let legacy = dir.join("legacy.json");
+    fs::write(&legacy, serde_json::to_string(&results).unwrap()).unwrap();
+    assert_eq!(
+        doppel::reporting::load_json_report(legacy.to_str().unwrap()).unwrap(),
+        results
+    );
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn min_verdict_filter_keeps_severe_results() {
+    let results = vec![
+        ScanResult::new("GET", "/users/1", "VULNERABLE | PII: YES"),
+        ScanResult::new("GET", "/users/2", "UNCERTAIN"),
+        ScanResult::new("GET", "/users/3", "ERROR: timeout"),
+        ScanResult::new("GET", "/users/4", "SECURE"),
+    ];
+
+    let count = |min: &str| doppel::reporting::filter_min_verdict(&results, min).len();
+    assert_eq!(count("VULNERABLE"), 1);
+    assert_eq!(count("UNCERTAIN"), 2);
+    assert_eq!(count("ERROR"), 3);
+    assert_eq!(count("SECURE"), 4);
+}
+
+#[test]
+fn severity_combines_verdict_risk_and_pii() {
+    use doppel::reporting::{severity_of, sort_by_severity, Severity};
+
+    let critical =
+        ScanResult::new("GET", "/users/1", "VULNERABLE | PII: YES").with_risk_score(Some(95));
+    let high = ScanResult::new("GET", "/users/2", "VULNERABLE | PII: NO").with_risk_score(Some(95));
+    let medium = ScanResult::new("GET", "/tags/3", "VULNERABLE").with_risk_score(Some(40));
+    let low = ScanResult::new("GET", "/tags/4", "UNCERTAIN").with_risk_score(Some(40));
+    let info = ScanResult::new("GET", "/users/5", "SECURE").with_risk_score(Some(95));
+
+    assert_eq!(severity_of(&critical), Severity::Critical);
+    assert_eq!(severity_of(&high), Severity::High);
+    assert_eq!(severity_of(&medium), Severity::Medium);
+    assert_eq!(severity_of(&low), Severity::Low);
+    assert_eq!(severity_of(&info), Severity::Info);
+    // Older reports carry no risk score; a confirmed finding is still High
+    assert_eq!(
+        severity_of(&ScanResult::new("GET", "/x", "VULNERABLE")),
+        Severity::High
+    );
+
+    let mut results = vec![info, low, critical.clone(), medium, high];
+    sort_by_severity(&mut results);
+    assert_eq!(results[0], critical);
+    assert_eq!(severity_of(&results[4]), Severity::Info);
+}
+
+#[test]
+fn findings_map_to_owasp_categories() {
+    use doppel::reporting::{finding_types, owasp_categories, owasp_category, FindingType};
+
+    let bola = ScanResult::new("GET", "/users/1", "VULNERABLE | PII: YES");
+    assert_eq!(
+        finding_types(&bola),
+        vec![FindingType::ObjectLevelAuthorization]
+    );
+    assert_eq!(
+        owasp_categories(&bola),
+        vec!["API1:2023 Broken Object Level Authorization"]
+    );
+    let cors = ScanResult::new(
+        "GET",
+        "/users/2",
+        "SECURE | CORS misconfiguration: reflects any origin with credentials",
+    );
+    assert_eq!(
+        owasp_categories(&cors),
+        vec!["API8:2023 Security Misconfiguration"]
+    );
+    assert!(owasp_categories(&ScanResult::new("GET", "/users/3", "SECURE")).is_empty());
+    assert!(owasp_category(FindingType::BrokenAuthentication).starts_with("API2:2023"));
+    assert!(owasp_category(FindingType::PropertyLevelAuthorization).starts_with("API3:2023"));
+
+    let md =
+        doppel::reporting::render_markdown(&[bola], &doppel::reporting::ReportConfig::default());
+    assert!(md.contains("  - OWASP: API1:2023 Broken Object Level Authorization\n"));
+}
+
+#[test]
+fn finding_ids_are_stable_across_injected_values() {
+    use doppel::reporting::{dedupe_results, finding_id};
+
+    let first =
+        ScanResult::new("GET", "http://api/users/123", "VULNERABLE | PII: YES").with_value("123");
+    let rescan = ScanResult::new("GET", "http://api/users/124?x=1", "VULNERABLE").with_value("124");
+    let secure = ScanResult::new("GET", "http://api/users/123", "SECURE").with_value("123");
+    let other = ScanResult::new("DELETE", "http://api/users/123", "VULNERABLE").with_value("123");
+
+    let id = finding_id(&first, false);
+    assert_eq!(id.len(), 12);
+    assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_eq!(id, finding_id(&rescan, false));
+    assert_ne!(id, finding_id(&secure, false));
+    assert_ne!(id, finding_id(&other, false));
+    // Grouping keeps the id of its members
+    assert_eq!(finding_id(&dedupe_results(&[first], false)[0], false), id);
+
+    let md =
+        doppel::reporting::render_markdown(&[rescan], &doppel::reporting::ReportConfig::default());
+    assert!(md.contains(&format!("(id: {})", id)));
 }
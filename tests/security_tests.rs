@@ -1,40 +1,34 @@
 /// Security tests for Doppel
 /// Tests CSV injection protection, path traversal prevention, and other security features
+use doppel::models::ScanResult;
+use doppel::reporting::ReportConfig;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Reports go to a per-test temp directory, never the working directory
+fn temp_report_config(test: &str) -> (ReportConfig, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("doppel_{}_{}", test, std::process::id()));
+    let config = ReportConfig {
+        output_dir: Some(dir.clone()),
+        ..Default::default()
+    };
+    (config, dir)
+}
 
 #[test]
 fn test_csv_injection_protection() {
     // Test that CSV fields starting with dangerous characters are properly escaped
+    let (config, dir) = temp_report_config("csv_injection_protection");
     let results = vec![
-        (
-            "GET".to_string(),
-            "/api/users".to_string(),
-            "=HYPERLINK(\"http://evil.com\")".to_string(),
-        ),
-        (
-            "POST".to_string(),
-            "/api/data".to_string(),
-            "+cmd|'/C calc'!A1".to_string(),
-        ),
-        (
-            "DELETE".to_string(),
-            "/api/items".to_string(),
-            "-2+3+cmd|'/C calc'!A1".to_string(),
-        ),
-        (
-            "PUT".to_string(),
-            "/api/update".to_string(),
-            "@SUM(1+1)*cmd|'/C calc'!A1".to_string(),
-        ),
-        (
-            "PATCH".to_string(),
-            "/api/modify".to_string(),
-            "\t=1+1".to_string(),
-        ),
+        ScanResult::new("GET", "/api/users", "=HYPERLINK(\"http://evil.com\")"),
+        ScanResult::new("POST", "/api/data", "+cmd|'/C calc'!A1"),
+        ScanResult::new("DELETE", "/api/items", "-2+3+cmd|'/C calc'!A1"),
+        ScanResult::new("PUT", "/api/update", "@SUM(1+1)*cmd|'/C calc'!A1"),
+        ScanResult::new("PATCH", "/api/modify", "\t=1+1"),
     ];
 
-    let csv_filename = doppel::reporting::export_csv(&results).expect("CSV export should succeed");
+    let csv_filename = doppel::reporting::export_csv_with_config(&results, &config)
+        .expect("CSV export should succeed");
 
     // Read the CSV file
     let content = fs::read_to_string(&csv_filename).expect("Should be able to read CSV file");
@@ -54,31 +48,27 @@ fn test_csv_injection_protection() {
 
     // Verify header is not escaped
     assert!(
-        content.starts_with("Method,URL,Result\n"),
+        content.starts_with(
+            "Method,URL,Result,Status,Severity,Soft Fail,PII,Risk Score,Finding ID,Injected\n"
+        ),
         "CSV header should be intact"
     );
 
     // Clean up
-    let _ = fs::remove_file(&csv_filename);
+    let _ = fs::remove_dir_all(&dir);
 }
 
 #[test]
 fn test_csv_normal_content_not_escaped() {
     // Test that normal content is not unnecessarily escaped
+    let (config, dir) = temp_report_config("csv_normal_content_not_escaped");
     let results = vec![
-        (
-            "GET".to_string(),
-            "/api/users/123".to_string(),
-            "SAFE: No vulnerability".to_string(),
-        ),
-        (
-            "POST".to_string(),
-            "/api/data".to_string(),
-            "VULNERABLE: BOLA detected".to_string(),
-        ),
+        ScanResult::new("GET", "/api/users/123", "SAFE: No vulnerability"),
+        ScanResult::new("POST", "/api/data", "VULNERABLE: BOLA detected"),
     ];
 
-    let csv_filename = doppel::reporting::export_csv(&results).expect("CSV export should succeed");
+    let csv_filename = doppel::reporting::export_csv_with_config(&results, &config)
+        .expect("CSV export should succeed");
 
     let content = fs::read_to_string(&csv_filename).expect("Should be able to read CSV file");
 
@@ -89,19 +79,21 @@ fn test_csv_normal_content_not_escaped() {
     );
 
     // Clean up
-    let _ = fs::remove_file(&csv_filename);
+    let _ = fs::remove_dir_all(&dir);
 }
 
 #[test]
 fn test_csv_comma_and_quote_escaping() {
     // Test that commas and quotes are properly escaped
-    let results = vec![(
-        "GET".to_string(),
-        "/api/test,comma".to_string(),
-        "Result with \"quotes\"".to_string(),
+    let (config, dir) = temp_report_config("csv_comma_and_quote_escaping");
+    let results = vec![ScanResult::new(
+        "GET",
+        "/api/test,comma",
+        "Result with \"quotes\"",
     )];
 
-    let csv_filename = doppel::reporting::export_csv(&results).expect("CSV export should succeed");
+    let csv_filename = doppel::reporting::export_csv_with_config(&results, &config)
+        .expect("CSV export should succeed");
 
     let content = fs::read_to_string(&csv_filename).expect("Should be able to read CSV file");
 
@@ -118,44 +110,49 @@ fn test_csv_comma_and_quote_escaping() {
     );
 
     // Clean up
-    let _ = fs::remove_file(&csv_filename);
+    let _ = fs::remove_dir_all(&dir);
 }
 
 #[test]
 fn test_csv_empty_fields() {
     // Test that empty fields are handled correctly
-    let results = vec![("".to_string(), "".to_string(), "".to_string())];
+    let (config, dir) = temp_report_config("csv_empty_fields");
+    let results = vec![ScanResult::new("", "", "")];
 
-    let csv_filename = doppel::reporting::export_csv(&results).expect("CSV export should succeed");
+    let csv_filename = doppel::reporting::export_csv_with_config(&results, &config)
+        .expect("CSV export should succeed");
 
     let content = fs::read_to_string(&csv_filename).expect("Should be able to read CSV file");
 
     // Should have header plus one empty line
     let lines: Vec<&str> = content.lines().collect();
     assert_eq!(lines.len(), 2, "Should have header and one data row");
-    assert_eq!(lines[1], ",,", "Empty fields should result in commas only");
+    // Only the computed severity and finding id are non-empty
+    let id = doppel::reporting::finding_id(&results[0], false);
+    assert_eq!(
+        lines[1],
+        format!(",,,,Info,,,,{},", id),
+        "Empty fields should stay empty around severity and finding id"
+    );
 
     // Clean up
-    let _ = fs::remove_file(&csv_filename);
+    let _ = fs::remove_dir_all(&dir);
 }
 
 #[test]
 fn test_report_filenames_have_timestamps() {
     // Test that exported files have timestamps to prevent overwrites
-    let results = vec![(
-        "GET".to_string(),
-        "/api/test".to_string(),
-        "SAFE".to_string(),
-    )];
+    let (config, dir) = temp_report_config("report_filenames_have_timestamps");
+    let results = vec![ScanResult::new("GET", "/api/test", "SAFE")];
 
-    let csv_filename1 =
-        doppel::reporting::export_csv(&results).expect("First CSV export should succeed");
+    let csv_filename1 = doppel::reporting::export_csv_with_config(&results, &config)
+        .expect("First CSV export should succeed");
 
     // Small delay to ensure different timestamp
     std::thread::sleep(std::time::Duration::from_millis(1100));
 
-    let csv_filename2 =
-        doppel::reporting::export_csv(&results).expect("Second CSV export should succeed");
+    let csv_filename2 = doppel::reporting::export_csv_with_config(&results, &config)
+        .expect("Second CSV export should succeed");
 
     // Verify filenames are different
     assert_ne!(
@@ -174,42 +171,32 @@ fn test_report_filenames_have_timestamps() {
     );
 
     // Verify filename format
+    let name1 = Path::new(&csv_filename1)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .expect("Report path should end in a file name");
     assert!(
-        csv_filename1.starts_with("doppel_report_"),
+        name1.starts_with("doppel_report_"),
         "Should have correct prefix"
     );
-    assert!(
-        csv_filename1.ends_with(".csv"),
-        "Should have .csv extension"
-    );
-    assert!(
-        csv_filename1.len() > 20,
-        "Filename should include timestamp"
-    );
+    assert!(name1.ends_with(".csv"), "Should have .csv extension");
+    assert!(name1.len() > 20, "Filename should include timestamp");
 
     // Clean up
-    let _ = fs::remove_file(&csv_filename1);
-    let _ = fs::remove_file(&csv_filename2);
+    let _ = fs::remove_dir_all(&dir);
 }
 
 #[test]
 fn test_markdown_export_structure() {
     // Test that markdown export creates proper structure
+    let (config, dir) = temp_report_config("markdown_export_structure");
     let results = vec![
-        (
-            "GET".to_string(),
-            "/api/users/1".to_string(),
-            "VULNERABLE: BOLA".to_string(),
-        ),
-        (
-            "POST".to_string(),
-            "/api/data".to_string(),
-            "SAFE".to_string(),
-        ),
+        ScanResult::new("GET", "/api/users/1", "VULNERABLE: BOLA"),
+        ScanResult::new("POST", "/api/data", "SAFE"),
     ];
 
-    let md_filename =
-        doppel::reporting::export_markdown(&results).expect("Markdown export should succeed");
+    let md_filename = doppel::reporting::export_markdown_with_config(&results, &config)
+        .expect("Markdown export should succeed");
 
     let content = fs::read_to_string(&md_filename).expect("Should be able to read markdown file");
 
@@ -228,43 +215,29 @@ fn test_markdown_export_structure() {
     );
 
     // Clean up
-    let _ = fs::remove_file(&md_filename);
+    let _ = fs::remove_dir_all(&dir);
 }
 
 #[test]
 fn test_multiple_vulnerabilities_export() {
     // Test exporting a realistic set of scan results
+    let (config, dir) = temp_report_config("multiple_vulnerabilities_export");
     let results = vec![
-        (
-            "GET".to_string(),
-            "/api/users/1".to_string(),
-            "VULNERABLE: BOLA detected".to_string(),
-        ),
-        (
-            "GET".to_string(),
-            "/api/users/2".to_string(),
-            "VULNERABLE: BOLA detected".to_string(),
-        ),
-        (
-            "GET".to_string(),
-            "/api/posts/1".to_string(),
-            "VULNERABLE: IDOR detected".to_string(),
-        ),
-        (
-            "DELETE".to_string(),
-            "/api/users/1".to_string(),
-            "VULNERABLE: Unauthorized deletion".to_string(),
-        ),
-        (
-            "GET".to_string(),
-            "/api/public/info".to_string(),
-            "SAFE: No vulnerability".to_string(),
+        ScanResult::new("GET", "/api/users/1", "VULNERABLE: BOLA detected"),
+        ScanResult::new("GET", "/api/users/2", "VULNERABLE: BOLA detected"),
+        ScanResult::new("GET", "/api/posts/1", "VULNERABLE: IDOR detected"),
+        ScanResult::new(
+            "DELETE",
+            "/api/users/1",
+            "VULNERABLE: Unauthorized deletion",
         ),
+        ScanResult::new("GET", "/api/public/info", "SAFE: No vulnerability"),
     ];
 
-    let csv_filename = doppel::reporting::export_csv(&results).expect("CSV export should succeed");
-    let md_filename =
-        doppel::reporting::export_markdown(&results).expect("Markdown export should succeed");
+    let csv_filename = doppel::reporting::export_csv_with_config(&results, &config)
+        .expect("CSV export should succeed");
+    let md_filename = doppel::reporting::export_markdown_with_config(&results, &config)
+        .expect("Markdown export should succeed");
 
     // Verify both files exist
     assert!(Path::new(&csv_filename).exists(), "CSV file should exist");
@@ -290,6 +263,5 @@ fn test_multiple_vulnerabilities_export() {
     );
 
     // Clean up
-    let _ = fs::remove_file(&csv_filename);
-    let _ = fs::remove_file(&md_filename);
+    let _ = fs::remove_dir_all(&dir);
 }
@@ -1,6 +1,10 @@
 /// Unit tests for core Doppel modules
 /// Tests models, utilities, and helper functions
 use doppel::models::{Endpoint, Method, Parameter, ParameterLocation};
+use doppel::verdict::{
+    analyze_404_context, analyze_response_ownership, contains_id, contains_identity,
+    is_error_response, FieldWeight, MatchInfo, Verdict,
+};
 
 #[test]
 fn test_method_display() {
@@ -12,6 +16,24 @@ fn test_method_display() {
     assert_eq!(Method::PATCH.to_string(), "PATCH");
     assert_eq!(Method::OPTIONS.to_string(), "OPTIONS");
     assert_eq!(Method::HEAD.to_string(), "HEAD");
+    assert_eq!(Method::TRACE.to_string(), "TRACE");
+    assert_eq!(
+        Method::Other("PROPFIND".to_string()).to_string(),
+        "PROPFIND"
+    );
+}
+
+#[test]
+fn test_method_parse() {
+    assert_eq!(Method::parse("GET"), Some(Method::GET));
+    assert_eq!(Method::parse("CONNECT"), Some(Method::CONNECT));
+    assert_eq!(
+        Method::parse("REPORT"),
+        Some(Method::Other("REPORT".to_string()))
+    );
+    // Not an HTTP token
+    assert_eq!(Method::parse("GET ME"), None);
+    assert_eq!(Method::parse(""), None);
 }
 
 #[test]
@@ -121,11 +143,13 @@ fn test_parameter_creation() {
         location: ParameterLocation::Path,
         required: true,
         schema_type: Some("string".to_string()),
+        array_style: None,
+        content_type: None,
     };
 
     assert_eq!(param.name, "user_id");
     assert_eq!(param.location, ParameterLocation::Path);
-    assert_eq!(param.required, true);
+    assert!(param.required);
 }
 
 #[test]
@@ -136,6 +160,8 @@ fn test_parameter_locations() {
         location: ParameterLocation::Path,
         required: true,
         schema_type: Some("string".to_string()),
+        array_style: None,
+        content_type: None,
     };
 
     let query_param = Parameter {
@@ -143,6 +169,8 @@ fn test_parameter_locations() {
         location: ParameterLocation::Query,
         required: false,
         schema_type: Some("string".to_string()),
+        array_style: None,
+        content_type: None,
     };
 
     let body_param = Parameter {
@@ -150,6 +178,8 @@ fn test_parameter_locations() {
         location: ParameterLocation::Body,
         required: true,
         schema_type: Some("object".to_string()),
+        array_style: None,
+        content_type: None,
     };
 
     let header_param = Parameter {
@@ -157,6 +187,8 @@ fn test_parameter_locations() {
         location: ParameterLocation::Header,
         required: true,
         schema_type: Some("string".to_string()),
+        array_style: None,
+        content_type: None,
     };
 
     assert_eq!(path_param.location, ParameterLocation::Path);
@@ -173,6 +205,8 @@ fn test_parameter_required_flag() {
         location: ParameterLocation::Path,
         required: true,
         schema_type: Some("string".to_string()),
+        array_style: None,
+        content_type: None,
     };
 
     let optional_param = Parameter {
@@ -180,10 +214,12 @@ fn test_parameter_required_flag() {
         location: ParameterLocation::Query,
         required: false,
         schema_type: Some("integer".to_string()),
+        array_style: None,
+        content_type: None,
     };
 
-    assert_eq!(required_param.required, true);
-    assert_eq!(optional_param.required, false);
+    assert!(required_param.required);
+    assert!(!optional_param.required);
 }
 
 #[test]
@@ -279,6 +315,8 @@ fn test_parameter_clone() {
         location: ParameterLocation::Query,
         required: true,
         schema_type: Some("string".to_string()),
+        array_style: None,
+        content_type: None,
     };
 
     let param2 = param1.clone();
@@ -299,3 +337,30 @@ fn test_parameter_location_equality() {
     assert_ne!(ParameterLocation::Path, ParameterLocation::Query);
     assert_ne!(ParameterLocation::Body, ParameterLocation::Header);
 }
+
+#[test]
+fn test_public_ownership_helpers() {
+    let body = r#"{"order": {"id": "42", "created_by": "7", "notes": "ask 99"}}"#;
+    assert_eq!(
+        contains_identity(body, "42"),
+        Some(MatchInfo {
+            field: "id".to_string(),
+            weight: FieldWeight::Critical,
+        })
+    );
+    assert_eq!(
+        contains_identity(body, "7").map(|m| m.weight),
+        Some(FieldWeight::Metadata)
+    );
+    // User-editable text and non-JSON bodies are not identity matches
+    assert_eq!(contains_identity(body, "99"), None);
+    assert_eq!(contains_identity("user 42", "42"), None);
+    assert!(contains_id("user 42", "42"));
+
+    assert_eq!(
+        analyze_response_ownership(body, "7", "42"),
+        Verdict::Vulnerable
+    );
+    assert!(is_error_response(&serde_json::json!({"success": false})));
+    assert_eq!(analyze_404_context("Access denied"), Verdict::Secure);
+}